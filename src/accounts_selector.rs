@@ -1,8 +1,24 @@
+use crate::config::GeyserPluginPostgresConfig;
+use crate::locks;
+use crate::postgres_client::validate_account_handlers;
 use log::*;
 use serde::Deserialize;
 use serde::Serialize;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
+use solana_sdk::hash::hash;
+use solana_sdk::system_program;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::thread::sleep;
+use std::thread::Builder;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::SystemTime;
 
 /// * The `accounts_selector` section allows the user to controls accounts selections.
 /// "accounts_selector" : {
@@ -37,23 +53,108 @@ pub(crate) struct AccountsSelector {
 
 impl AccountsSelector {
     pub fn new(config: &AccountsSelectorConfig) -> Self {
+        Self::try_new(config).unwrap()
+    }
+
+    /// The selector, an error naming the first key of `config` which is not base58
+    pub fn try_new(config: &AccountsSelectorConfig) -> Result<Self, GeyserPluginError> {
         info!("[accounts_selector] accounts=[{:?}] owners=[{:?}]", config.accounts, config.owners);
-        let owners = match &config.owners {
-            Some(owners) => owners.iter().map(|(key, _)| bs58::decode(key).into_vec().unwrap()).collect(),
-            None => HashSet::default(),
-        };
-        let accounts = match &config.accounts {
-            Some(accounts) => accounts.iter().map(|(key, _)| bs58::decode(key).into_vec().unwrap()).collect(),
-            None => HashSet::default(),
+        let decode = |keys: &Option<HashMap<String, Vec<AccountHandlerConfig>>>| {
+            keys.iter()
+                .flat_map(|keys| keys.keys())
+                .map(|key| {
+                    bs58::decode(key).into_vec().map_err(|err| GeyserPluginError::ConfigFileReadError {
+                        msg: format!("\"accounts_selector\" key \"{}\" is not base58: {}", key, err),
+                    })
+                })
+                .collect::<Result<HashSet<Vec<u8>>, GeyserPluginError>>()
         };
-        AccountsSelector { accounts, owners }
+        Ok(AccountsSelector {
+            accounts: decode(&config.accounts)?,
+            owners: decode(&config.owners)?,
+        })
     }
 
     pub fn is_account_selected(&self, account: &[u8], owner: &[u8]) -> bool {
         self.accounts.contains(account) || self.owners.contains(owner)
     }
 
+    /// Whether an account closed and reassigned to the system program is selected, as it may have been selected
+    /// by its former owner
+    pub fn is_closed_account_selected(&self, lamports: u64, owner: &[u8]) -> bool {
+        lamports == 0 && owner == system_program::id().as_ref() && !self.owners.is_empty()
+    }
+
     pub fn is_enabled(&self) -> bool {
         !self.accounts.is_empty() || !self.owners.is_empty()
     }
 }
+
+/// A selector and the config it was built from, the handlers being read from the config
+#[derive(Debug, Default)]
+pub struct LoadedAccountsSelector {
+    pub config: Option<AccountsSelectorConfig>,
+    pub(crate) selector: Option<AccountsSelector>,
+}
+
+/// Selector shared by the plugin and its workers, swapped as a whole when the config file is reloaded
+#[derive(Clone, Debug, Default)]
+pub struct SharedAccountsSelector {
+    loaded: Arc<RwLock<Arc<LoadedAccountsSelector>>>,
+}
+
+impl SharedAccountsSelector {
+    pub fn new(config: Option<AccountsSelectorConfig>) -> Self {
+        let selector = config.as_ref().map(AccountsSelector::new);
+        Self {
+            loaded: Arc::new(RwLock::new(Arc::new(LoadedAccountsSelector { config, selector }))),
+        }
+    }
+
+    /// The current selector, unaffected by a later swap
+    pub fn load(&self) -> Arc<LoadedAccountsSelector> {
+        locks::read(&self.loaded, "accounts_selector").clone()
+    }
+
+    /// Replaces the selector once `config` is checked, the current one being kept on error
+    pub fn swap(&self, config: Option<AccountsSelectorConfig>) -> Result<(), GeyserPluginError> {
+        validate_account_handlers(&config)?;
+        let selector = config.as_ref().map(AccountsSelector::try_new).transpose()?;
+        *locks::write(&self.loaded, "accounts_selector") = Arc::new(LoadedAccountsSelector { config, selector });
+        Ok(())
+    }
+}
+
+/// Swaps the selector of `shared` for the one of the config file whenever the file is modified, checking its
+/// modification time every `interval` until `exit` is set
+pub fn watch_config_file(config_file: &str, shared: SharedAccountsSelector, interval: Duration, exit: Arc<AtomicBool>) -> std::io::Result<JoinHandle<()>> {
+    let path = PathBuf::from(config_file);
+    let modified = |path: &PathBuf| std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+    let mut last_modified: Option<SystemTime> = modified(&path);
+    info!("[accounts_selector] watching path={:?} interval={:?}", path, interval);
+    Builder::new().name("selector-reload".to_string()).spawn(move || {
+        while !exit.load(Ordering::Relaxed) {
+            sleep(interval);
+            let current = modified(&path);
+            if current.is_none() || current == last_modified {
+                continue;
+            }
+            last_modified = current;
+            match GeyserPluginPostgresConfig::read_from(&path).and_then(|config| shared.swap(config.accounts_selector)) {
+                Ok(()) => info!("[accounts_selector] reloaded path={:?}", path),
+                Err(err) => error!("[accounts_selector] keeping the current selector, failed to reload path={:?} error=[{}]", path, err),
+            }
+        }
+    })
+}
+
+/// Deterministically samples 1 in `sampling_rate` accounts by hashing the pubkey, so the
+/// same accounts, including their closing updates, are always selected.
+pub fn is_account_sampled(account: &[u8], sampling_rate: u64) -> bool {
+    if sampling_rate <= 1 {
+        return true;
+    }
+    let account_hash = hash(account);
+    let bucket = u64::from_le_bytes(account_hash.as_ref()[..8].try_into().unwrap());
+    bucket % sampling_rate == 0
+}
@@ -0,0 +1,118 @@
+//! Admin interface exposing the plugin's runtime state as JSON on a unix socket, e.g.
+//! `nc -U /tmp/geyser-postgres.sock`
+
+use crate::locks;
+use crate::parallel_client_worker::WorkRequest;
+use crossbeam_channel::Sender;
+use log::*;
+use serde_json::json;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::os::unix::net::UnixListener;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread::sleep;
+use std::thread::Builder;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+pub const WORKER_STARTING: u8 = 0;
+pub const WORKER_IDLE: u8 = 1;
+pub const WORKER_BUSY: u8 = 2;
+pub const WORKER_STOPPED: u8 = 3;
+
+#[derive(Default)]
+pub struct WorkerStats {
+    pub state: AtomicU8,
+    pub pending_account_updates: AtomicUsize,
+    pub pending_transactions: AtomicUsize,
+    /// Bytes of the account and slot queries written by the worker
+    pub bytes_written: AtomicU64,
+}
+
+/// Runtime state shared by the workers
+#[derive(Default)]
+pub struct RuntimeStats {
+    pub workers: Vec<WorkerStats>,
+    pub last_error: Mutex<Option<String>>,
+    /// Startup accounts written by all the workers once startup is done
+    pub flushed_startup_accounts: AtomicUsize,
+}
+
+impl RuntimeStats {
+    pub fn new(worker_count: usize) -> Self {
+        Self {
+            workers: (0..worker_count).map(|_| WorkerStats::default()).collect(),
+            last_error: Mutex::new(None),
+            flushed_startup_accounts: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn set_last_error(&self, error: String) {
+        *locks::lock(&self.last_error, "admin_socket") = Some(error);
+    }
+
+    pub fn to_json(&self, queue_depth: usize) -> serde_json::Value {
+        let state = |state: u8| match state {
+            WORKER_STARTING => "starting",
+            WORKER_IDLE => "idle",
+            WORKER_BUSY => "busy",
+            _ => "stopped",
+        };
+        json!({
+            "pending_account_updates": self.workers.iter().map(|w| w.pending_account_updates.load(Ordering::Relaxed)).sum::<usize>(),
+            "pending_transactions": self.workers.iter().map(|w| w.pending_transactions.load(Ordering::Relaxed)).sum::<usize>(),
+            "bytes_written": self.workers.iter().map(|w| w.bytes_written.load(Ordering::Relaxed)).sum::<u64>(),
+            "queue_depth": queue_depth,
+            "workers": self.workers.iter().map(|w| json!({
+                "state": state(w.state.load(Ordering::Relaxed)),
+                "pending_account_updates": w.pending_account_updates.load(Ordering::Relaxed),
+                "pending_transactions": w.pending_transactions.load(Ordering::Relaxed),
+                "bytes_written": w.bytes_written.load(Ordering::Relaxed),
+            })).collect::<Vec<serde_json::Value>>(),
+            "last_error": *locks::lock(&self.last_error, "admin_socket"),
+        })
+    }
+}
+
+/// Serves the runtime state to every connection on `path` until `exit` is set
+pub fn serve(path: &str, stats: Arc<RuntimeStats>, sender: Sender<WorkRequest>, exit: Arc<AtomicBool>) -> std::io::Result<JoinHandle<()>> {
+    let path = PathBuf::from(path);
+    // a socket file left behind by a previous run prevents binding
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+    let listener = UnixListener::bind(&path)?;
+    listener.set_nonblocking(true)?;
+    info!("[admin_socket] listening path={:?}", path);
+    Builder::new().name("admin-socket".to_string()).spawn(move || {
+        while !exit.load(Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    if let Err(err) = respond(stream, &stats, sender.len()) {
+                        warn!("[admin_socket] error=[{}]", err);
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => sleep(Duration::from_millis(100)),
+                Err(err) => {
+                    error!("[admin_socket] error=[{}]", err);
+                    break;
+                }
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+    })
+}
+
+fn respond(mut stream: UnixStream, stats: &RuntimeStats, queue_depth: usize) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.write_all(stats.to_json(queue_depth).to_string().as_bytes())?;
+    stream.write_all(b"\n")
+}
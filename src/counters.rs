@@ -0,0 +1,38 @@
+//! Counters of the plugin's own timings, sampled at the rates of the `counter_sampling` config rather than the
+//! rates compiled into each `inc_new_counter_*!` call.
+
+use crate::config::CounterSamplingConfig;
+use crate::locks;
+use solana_metrics::counter::Counter;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+static LOG_RATE: AtomicUsize = AtomicUsize::new(100_000);
+static METRICS_RATE_MS: AtomicU64 = AtomicU64::new(100_000);
+
+/// Sets the sampling rates of the counters from the config, applied from their next increment
+pub fn init(config: &CounterSamplingConfig) {
+    LOG_RATE.store(config.log_rate, Ordering::Relaxed);
+    METRICS_RATE_MS.store(config.metrics_rate_ms, Ordering::Relaxed);
+}
+
+/// Increments the counter at the configured sampling rates
+pub fn inc_sampled_counter(counter: &Mutex<Counter>, level: log::Level, count: usize) {
+    let mut counter = locks::lock(counter, "counter");
+    counter.lograte.store(LOG_RATE.load(Ordering::Relaxed), Ordering::Relaxed);
+    counter.metricsrate.store(METRICS_RATE_MS.load(Ordering::Relaxed), Ordering::Relaxed);
+    counter.inc(level, count);
+}
+
+/// `inc_new_counter_debug!` at the configured sampling rates
+macro_rules! inc_sampled_counter_debug {
+    ($name:expr, $count:expr) => {{
+        if log::log_enabled!(log::Level::Debug) {
+            static COUNTER: std::sync::Mutex<solana_metrics::counter::Counter> = std::sync::Mutex::new(solana_metrics::create_counter!($name, 0, 0));
+            $crate::counters::inc_sampled_counter(&COUNTER, log::Level::Debug, $count);
+        }
+    }};
+}
+pub(crate) use inc_sampled_counter_debug;
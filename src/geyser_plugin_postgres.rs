@@ -1,7 +1,12 @@
-use crate::accounts_selector::AccountsSelector;
+use crate::accounts_selector;
+use crate::accounts_selector::is_account_sampled;
+use crate::accounts_selector::SharedAccountsSelector;
 use crate::config::GeyserPluginPostgresConfig;
+use crate::counters;
+use crate::counters::inc_sampled_counter_debug;
 use crate::parallel_client::ParallelClient;
 use crate::postgres_client::PostgresClientBuilder;
+use crate::telemetry;
 use crate::transaction_selector::TransactionSelector;
 use bs58;
 use log::*;
@@ -13,14 +18,21 @@ use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionI
 use solana_geyser_plugin_interface::geyser_plugin_interface::Result;
 use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
 use solana_measure::measure::Measure;
-use solana_metrics::*;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Default)]
 pub struct GeyserPluginPostgres {
     pub config: Option<GeyserPluginPostgresConfig>,
     client: Option<ParallelClient>,
-    accounts_selector: Option<AccountsSelector>,
+    accounts_selector: SharedAccountsSelector,
+    /// Watches the config file for a new accounts selector, set when `accounts_selector_reload_ms` is
+    selector_reload: Option<JoinHandle<()>>,
+    selector_reload_exit: Arc<AtomicBool>,
     transaction_selector: Option<TransactionSelector>,
     batch_starting_slot: Option<u64>,
 }
@@ -43,6 +55,8 @@ pub enum GeyserPluginPostgresError {
     ConnectionError { msg: String },
     #[error("Error preparing data store schema. Error message: ({msg})")]
     DataSchemaError { msg: String },
+    #[error("Error writing to the data store. Error message: ({msg})")]
+    WriteError { msg: String, source: postgres::Error },
 }
 
 fn client_err() -> Result<()> {
@@ -60,10 +74,18 @@ impl GeyserPlugin for GeyserPluginPostgres {
         solana_logger::setup_with_default("info");
         info!("[on_load] name=[{:?}] config_file=[{:?}]", self.name(), config_file);
         let config = GeyserPluginPostgresConfig::read_from(config_file)?;
+        telemetry::init(&config);
+        counters::init(&config.counter_sampling);
         let (client, batch_starting_slot) = PostgresClientBuilder::build_pararallel_postgres_client(&config)?;
+        self.accounts_selector = client.accounts_selector();
         self.client = Some(client);
         self.batch_starting_slot = batch_starting_slot;
-        self.accounts_selector = config.accounts_selector.as_ref().map(AccountsSelector::new);
+        if let Some(reload_ms) = config.accounts_selector_reload_ms {
+            let watch = accounts_selector::watch_config_file(config_file, self.accounts_selector.clone(), Duration::from_millis(reload_ms), self.selector_reload_exit.clone());
+            self.selector_reload = Some(watch.map_err(|err| GeyserPluginError::ConfigFileReadError {
+                msg: format!("Failed to watch the config file \"accounts_selector_reload_ms\": {}. Error: ({})", config_file, err),
+            })?);
+        }
         self.transaction_selector = config.transaction_selector.as_ref().map(TransactionSelector::new);
         self.config = Some(config);
         Ok(())
@@ -77,10 +99,15 @@ impl GeyserPlugin for GeyserPluginPostgres {
                 client.join().unwrap();
             }
         }
+        self.selector_reload_exit.store(true, Ordering::Relaxed);
+        if let Some(selector_reload) = self.selector_reload.take() {
+            selector_reload.join().unwrap();
+        }
+        telemetry::shutdown();
     }
 
     fn update_account(&mut self, account: ReplicaAccountInfoVersions, slot: u64, is_startup: bool) -> Result<()> {
-        // skip updating account on startup of batch_starting_slot is configured
+        // skip the startup accounts below batch_starting_slot when it is configured, they are already written
         if is_startup && self.batch_starting_slot.map(|slot_limit| slot < slot_limit).unwrap_or(false) {
             return Ok(());
         }
@@ -94,15 +121,20 @@ impl GeyserPlugin for GeyserPluginPostgres {
         match account {
             ReplicaAccountInfoVersions::V0_0_2(account) => {
                 let mut measure_select = Measure::start("geyser-plugin-postgres-update-account-select");
-                if let Some(accounts_selector) = &self.accounts_selector {
-                    if !accounts_selector.is_account_selected(account.pubkey, account.owner) {
+                if let Some(accounts_selector) = &self.accounts_selector.load().selector {
+                    if !accounts_selector.is_account_selected(account.pubkey, account.owner) && !accounts_selector.is_closed_account_selected(account.lamports, account.owner) {
                         return Ok(());
                     }
                 } else {
                     return Ok(());
                 }
+                if let Some(sampling_rate) = self.config.as_ref().and_then(|config| config.sampling_rate) {
+                    if !is_account_sampled(account.pubkey, sampling_rate) {
+                        return Ok(());
+                    }
+                }
                 measure_select.stop();
-                inc_new_counter_debug!("geyser-plugin-postgres-update-account-select-us", measure_select.as_us() as usize, 100000, 100000);
+                inc_sampled_counter_debug!("geyser-plugin-postgres-update-account-select-us", measure_select.as_us() as usize);
 
                 debug!(
                     "[update_account][ingest] pubkey=[{:?}] owner=[{:?}] slot=[{:?}]",
@@ -115,7 +147,7 @@ impl GeyserPlugin for GeyserPluginPostgres {
                 let result = client.update_account(account, slot, is_startup);
                 measure_update.stop();
 
-                inc_new_counter_debug!("geyser-plugin-postgres-update-account-client-us", measure_update.as_us() as usize, 100000, 100000);
+                inc_sampled_counter_debug!("geyser-plugin-postgres-update-account-client-us", measure_update.as_us() as usize);
                 if let Err(err) = result {
                     return Err(GeyserPluginError::AccountsUpdateError {
                         msg: format!("Failed to persist the update of account to the PostgreSQL database. Error: {:?}", err),
@@ -130,7 +162,7 @@ impl GeyserPlugin for GeyserPluginPostgres {
         }
 
         measure_all.stop();
-        inc_new_counter_debug!("geyser-plugin-postgres-update-account-main-us", measure_all.as_us() as usize, 100000, 100000);
+        inc_sampled_counter_debug!("geyser-plugin-postgres-update-account-main-us", measure_all.as_us() as usize);
         Ok(())
     }
 
@@ -154,6 +186,8 @@ impl GeyserPlugin for GeyserPluginPostgres {
             Some(client) => client,
             None => return client_err(),
         };
+        // the indexes are only created by the first notification, the client ignoring the later ones
+        let was_startup_done = client.is_startup_done();
         let result = client.notify_end_of_startup();
 
         if let Err(err) = result {
@@ -161,7 +195,11 @@ impl GeyserPlugin for GeyserPluginPostgres {
                 msg: format!("Failed to notify the end of startup for accounts notifications. Error: {:?}", err),
             });
         }
-        Ok(())
+        // the indexes left out for the cold load are created once the startup accounts are written
+        match &self.config {
+            Some(config) if !config.create_indexes && !was_startup_done => PostgresClientBuilder::create_indexes(config),
+            _ => Ok(()),
+        }
     }
 
     fn notify_transaction(&mut self, transaction_info: ReplicaTransactionInfoVersions, slot: u64) -> Result<()> {
@@ -221,7 +259,9 @@ impl GeyserPlugin for GeyserPluginPostgres {
     }
 
     fn account_data_notifications_enabled(&self) -> bool {
-        self.accounts_selector.as_ref().map_or_else(|| false, |selector| selector.is_enabled())
+        // a selector may be loaded later, the notifications being only enabled at startup
+        self.config.as_ref().is_some_and(|config| config.accounts_selector_reload_ms.is_some())
+            || self.accounts_selector.load().selector.as_ref().map_or_else(|| false, |selector| selector.is_enabled())
     }
 
     fn transaction_notifications_enabled(&self) -> bool {
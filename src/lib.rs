@@ -2,11 +2,17 @@ use geyser_plugin_postgres::GeyserPluginPostgres;
 use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
 
 pub mod accounts_selector;
+pub mod admin_socket;
 pub mod config;
+pub mod counters;
 pub mod geyser_plugin_postgres;
+pub mod locks;
 pub mod parallel_client;
 pub mod parallel_client_worker;
 pub mod postgres_client;
+pub mod redis_sink;
+pub mod serializer;
+pub mod telemetry;
 pub mod transaction_selector;
 
 #[no_mangle]
@@ -0,0 +1,33 @@
+//! Locks of the state shared by the workers, recovered when poisoned. A panic while holding one leaves the state of
+//! the operation that panicked, every other operation still being consistent, so the next ones go on rather than
+//! cascading the panic to all the workers.
+
+use log::*;
+use solana_metrics::*;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+use std::sync::RwLockWriteGuard;
+
+fn recover<G>(err: PoisonError<G>, name: &str) -> G {
+    warn!("[{}] recovering a lock poisoned by a panic", name);
+    inc_new_counter_info!("geyser-plugin-postgres-poisoned-lock", 1);
+    err.into_inner()
+}
+
+/// Locks `mutex`, recovering the guard if a panic poisoned it. `name` tells the lock in the warning
+pub fn lock<'a, T>(mutex: &'a Mutex<T>, name: &str) -> MutexGuard<'a, T> {
+    mutex.lock().unwrap_or_else(|err| recover(err, name))
+}
+
+/// Read-locks `lock`, recovering the guard if a panic poisoned it
+pub fn read<'a, T>(lock: &'a RwLock<T>, name: &str) -> RwLockReadGuard<'a, T> {
+    lock.read().unwrap_or_else(|err| recover(err, name))
+}
+
+/// Write-locks `lock`, recovering the guard if a panic poisoned it
+pub fn write<'a, T>(lock: &'a RwLock<T>, name: &str) -> RwLockWriteGuard<'a, T> {
+    lock.write().unwrap_or_else(|err| recover(err, name))
+}
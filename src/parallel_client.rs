@@ -1,14 +1,30 @@
 use crate::abort;
+use crate::accounts_selector::SharedAccountsSelector;
+use crate::admin_socket;
+use crate::admin_socket::RuntimeStats;
 use crate::config::GeyserPluginPostgresConfig;
+use crate::config::IntegerOverflow;
+use crate::counters::inc_sampled_counter_debug;
 use crate::parallel_client_worker::LogTransactionRequest;
 use crate::parallel_client_worker::ParallelClientWorker;
+use crate::parallel_client_worker::SharedWorkerState;
 use crate::parallel_client_worker::UpdateAccountRequest;
+use crate::parallel_client_worker::UpdateAccountViewRequest;
 use crate::parallel_client_worker::UpdateBlockMetadataRequest;
 use crate::parallel_client_worker::UpdateSlotRequest;
 use crate::parallel_client_worker::WorkRequest;
 use crate::postgres_client::build_db_transaction;
+use crate::postgres_client::commitment_view::CommitmentView;
+use crate::postgres_client::pool::ConnectionPools;
+use crate::postgres_client::slot_completion::SlotCompletion;
+use crate::postgres_client::startup_integrity::StartupIntegrity;
+use crate::postgres_client::startup_memory::StartupMemory;
 use crate::postgres_client::DbAccountInfo;
 use crate::postgres_client::DbBlockInfo;
+use crate::postgres_client::DbTransaction;
+use crate::postgres_client::ForkEvent;
+use crate::postgres_client::MintDecimals;
+use chrono::Utc;
 use crossbeam_channel::bounded;
 use crossbeam_channel::Sender;
 use log::*;
@@ -20,6 +36,9 @@ use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
 use solana_measure::measure::Measure;
 use solana_metrics::*;
 use solana_sdk::timing::AtomicInterval;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::AtomicUsize;
@@ -30,8 +49,20 @@ use std::thread::Builder;
 use std::thread::JoinHandle;
 use std::thread::{self};
 use std::time::Duration;
+use std::time::Instant;
 
 const MAX_ASYNC_REQUESTS: usize = 40960;
+/// Wait of the startup notifications between checks of `startup_memory_budget_bytes`
+const STARTUP_MEMORY_WAIT: Duration = Duration::from_millis(10);
+/// Interval of the `geyser_plugin_postgres_pool` metric
+const POOL_REPORT_INTERVAL_MS: u64 = 10000;
+
+/// Account updates of a slot not rooted yet, written to the commitment views as the slot progresses
+#[derive(Default)]
+struct ViewSlot {
+    accounts: Vec<DbAccountInfo>,
+    is_confirmed: bool,
+}
 
 #[warn(clippy::large_enum_variant)]
 pub struct ParallelClient {
@@ -43,30 +74,79 @@ pub struct ParallelClient {
     sender: Sender<WorkRequest>,
     last_report: AtomicInterval,
     transaction_write_version: AtomicU64,
+    write_transactions_on_root: bool,
+    /// Transactions waiting for their slot to be rooted, keyed by slot and signature
+    pending_rooted_transactions: BTreeMap<u64, HashMap<Vec<u8>, DbTransaction>>,
+    highest_rooted_slot: Option<u64>,
+    /// Slots notified above the highest root, abandoned if a later slot roots first
+    unrooted_slots: BTreeSet<u64>,
+    write_fork_events: bool,
+    commitment_views: bool,
+    integer_overflow: IntegerOverflow,
+    /// Account updates waiting for their slot to be confirmed or rooted, set when `commitment_views` is
+    view_slots: BTreeMap<u64, ViewSlot>,
+    /// When the writes of an unrooted slot started being held, in `view_slots` or `pending_rooted_transactions`
+    unrooted_buffers: BTreeMap<u64, Instant>,
+    highest_notified_slot: u64,
+    max_unrooted_buffer_age_slots: Option<u64>,
+    max_unrooted_buffer_age: Option<Duration>,
+    slot_catchup_lag_secs: Option<u64>,
+    /// Set while the latest block time lags by more than `slot_catchup_lag_secs`
+    is_catching_up: bool,
+    skipped_slot_status_count: u64,
+    admin_socket: Option<JoinHandle<()>>,
+    /// In-flight writes per slot, set when `write_slot_complete` is
+    slot_completion: Option<Arc<SlotCompletion>>,
+    stats: Arc<RuntimeStats>,
+    /// Startup accounts notified, set when `startup_integrity_report` is
+    startup_integrity: Option<StartupIntegrity>,
+    /// Memory of the startup accounts not written yet, set when `startup_memory_budget_bytes` is
+    startup_memory: Option<Arc<StartupMemory>>,
+    /// Selector of the workers, swapped when the config file is reloaded
+    accounts_selector: SharedAccountsSelector,
+    /// The pools of the workers, whose utilization is reported
+    pools: ConnectionPools,
+    last_pool_report: AtomicInterval,
 }
 
 impl ParallelClient {
-    pub fn new(config: &GeyserPluginPostgresConfig) -> Result<Self, GeyserPluginError> {
-        info!("[ParallelClient] config=[{:?}]", config);
+    /// The workers write through the connections of `pools`
+    pub fn new(config: &GeyserPluginPostgresConfig, pools: ConnectionPools) -> Result<Self, GeyserPluginError> {
+        info!("[ParallelClient] config=[{:?}]", config.redacted());
         let (sender, receiver) = bounded(MAX_ASYNC_REQUESTS);
         let exit_worker = Arc::new(AtomicBool::new(false));
         let mut workers = Vec::default();
         let is_startup_done = Arc::new(AtomicBool::new(false));
         let startup_done_count = Arc::new(AtomicUsize::new(0));
-        let worker_count = config.threads;
+        // the workers beyond `threads` only help restoring the accounts at startup
+        let worker_count = config.threads.max(config.startup_worker_count.unwrap_or_default());
         let initialized_worker_count = Arc::new(AtomicUsize::new(0));
+        let stats = Arc::new(RuntimeStats::new(worker_count));
+        let slot_completion = config.write_slot_complete.then(|| Arc::new(SlotCompletion::default()));
+        let startup_memory = config.startup_memory_budget_bytes.map(|budget| Arc::new(StartupMemory::new(budget)));
+        let accounts_selector = SharedAccountsSelector::new(config.accounts_selector.clone());
+        let shared = SharedWorkerState {
+            slot_completion: slot_completion.clone(),
+            startup_memory: startup_memory.clone(),
+            mint_decimals: (config.nft_token_accounts_only || config.store_ui_amount).then(|| MintDecimals::new(config.caches.mint_decimals)),
+            accounts_selector: accounts_selector.clone(),
+            pools: pools.clone(),
+        };
         for i in 0..worker_count {
             let cloned_receiver = receiver.clone();
             let exit_clone = exit_worker.clone();
             let is_startup_done_clone = is_startup_done.clone();
             let startup_done_count_clone = startup_done_count.clone();
             let initialized_worker_count_clone = initialized_worker_count.clone();
+            let stats_clone = stats.clone();
+            let shared_clone = shared.clone();
+            let startup_only = i >= config.threads;
             let config = config.clone();
             let worker = Builder::new()
                 .name(format!("worker-{}", i))
                 .spawn(move || -> Result<(), GeyserPluginError> {
                     let panic_on_db_errors = config.panic_on_db_errors;
-                    match ParallelClientWorker::new(config) {
+                    match ParallelClientWorker::new(config, stats_clone.clone(), i, shared_clone, startup_only) {
                         Ok(mut worker) => {
                             initialized_worker_count_clone.fetch_add(1, Ordering::Relaxed);
                             worker.do_work(cloned_receiver, exit_clone, is_startup_done_clone, startup_done_count_clone, panic_on_db_errors)?;
@@ -74,6 +154,7 @@ impl ParallelClient {
                         }
                         Err(err) => {
                             error!("Error when making connection to database: ({})", err);
+                            stats_clone.set_last_error(format!("Error when making connection to database: ({})", err));
                             if panic_on_db_errors {
                                 abort();
                             }
@@ -86,6 +167,18 @@ impl ParallelClient {
             workers.push(worker);
         }
 
+        let admin_socket = match &config.admin_socket_path {
+            Some(path) => match admin_socket::serve(path, stats.clone(), sender.clone(), exit_worker.clone()) {
+                Ok(admin_socket) => Some(admin_socket),
+                Err(err) => {
+                    return Err(GeyserPluginError::ConfigFileReadError {
+                        msg: format!("Failed to bind the admin socket \"admin_socket_path\": {}. Error: ({})", path, err),
+                    });
+                }
+            },
+            None => None,
+        };
+
         Ok(Self {
             last_report: AtomicInterval::default(),
             workers,
@@ -95,10 +188,51 @@ impl ParallelClient {
             initialized_worker_count,
             sender,
             transaction_write_version: AtomicU64::default(),
+            write_transactions_on_root: config.write_transactions_on_root,
+            pending_rooted_transactions: BTreeMap::default(),
+            highest_rooted_slot: None,
+            unrooted_slots: BTreeSet::default(),
+            write_fork_events: config.write_fork_events,
+            commitment_views: config.commitment_views,
+            integer_overflow: config.integer_overflow,
+            view_slots: BTreeMap::default(),
+            unrooted_buffers: BTreeMap::default(),
+            highest_notified_slot: 0,
+            max_unrooted_buffer_age_slots: config.max_unrooted_buffer_age_slots,
+            max_unrooted_buffer_age: config.max_unrooted_buffer_age_secs.map(Duration::from_secs),
+            slot_catchup_lag_secs: config.slot_catchup_lag_secs,
+            is_catching_up: false,
+            skipped_slot_status_count: 0,
+            admin_socket,
+            slot_completion,
+            stats,
+            startup_integrity: config.startup_integrity_report.then(|| StartupIntegrity::new(config)),
+            startup_memory,
+            accounts_selector,
+            pools,
+            last_pool_report: AtomicInterval::default(),
         })
     }
 
+    /// Reports the utilization of the pools every `POOL_REPORT_INTERVAL_MS`
+    fn report_pools(&self) {
+        if self.last_pool_report.should_update(POOL_REPORT_INTERVAL_MS) {
+            self.pools.report();
+        }
+    }
+
+    pub fn accounts_selector(&self) -> SharedAccountsSelector {
+        self.accounts_selector.clone()
+    }
+
     pub fn join(&mut self) -> thread::Result<()> {
+        if !self.pending_rooted_transactions.is_empty() {
+            info!(
+                "[join] dropping unrooted transactions slots={} count={}",
+                self.pending_rooted_transactions.len(),
+                self.pending_rooted_transactions.values().map(|t| t.len()).sum::<usize>()
+            );
+        }
         self.exit_worker.store(true, Ordering::Relaxed);
         while !self.workers.is_empty() {
             let worker = self.workers.pop();
@@ -111,6 +245,9 @@ impl ParallelClient {
                 error!("The worker thread has failed: {:?}", result);
             }
         }
+        if let Some(admin_socket) = self.admin_socket.take() {
+            admin_socket.join()?;
+        }
 
         Ok(())
     }
@@ -119,13 +256,35 @@ impl ParallelClient {
         if self.last_report.should_update(30000) {
             datapoint_debug!("postgres-plugin-stats", ("message-queue-length", self.sender.len() as i64, i64),);
         }
+        self.report_pools();
         let mut measure = Measure::start("geyser-plugin-posgres-create-work-item");
-        let wrk_item = WorkRequest::UpdateAccount(Box::new(UpdateAccountRequest {
-            account: DbAccountInfo::new(account, slot),
-            is_startup,
-        }));
+        let Some(db_account) = DbAccountInfo::checked(account, slot, self.integer_overflow) else {
+            return Ok(());
+        };
+        let wrk_item = WorkRequest::UpdateAccount(Box::new(UpdateAccountRequest { account: db_account, is_startup }));
         measure.stop();
-        inc_new_counter_debug!("geyser-plugin-posgres-create-work-item-us", measure.as_us() as usize, 100000, 100000);
+        inc_sampled_counter_debug!("geyser-plugin-posgres-create-work-item-us", measure.as_us() as usize);
+        // the startup accounts are written to the views by the workers, being rooted
+        if self.commitment_views && !is_startup {
+            if let WorkRequest::UpdateAccount(request) = &wrk_item {
+                self.hold_for_views(&request.account)?;
+            }
+        }
+        self.begin_write(slot);
+        if is_startup {
+            if let Some(startup_integrity) = &mut self.startup_integrity {
+                startup_integrity.record_account(slot);
+            }
+            if let Some(startup_memory) = &self.startup_memory {
+                // wait for the workers to write the accounts held over the budget
+                while startup_memory.is_exceeded() && !self.exit_worker.load(Ordering::Relaxed) {
+                    sleep(STARTUP_MEMORY_WAIT);
+                }
+                if let WorkRequest::UpdateAccount(request) = &wrk_item {
+                    startup_memory.reserve(StartupMemory::account_bytes(&request.account));
+                }
+            }
+        }
 
         let mut measure = Measure::start("geyser-plugin-posgres-send-msg");
         if let Err(err) = self.sender.send(wrk_item) {
@@ -134,12 +293,44 @@ impl ParallelClient {
             });
         }
         measure.stop();
-        inc_new_counter_debug!("geyser-plugin-posgres-send-msg-us", measure.as_us() as usize, 100000, 100000);
+        inc_sampled_counter_debug!("geyser-plugin-posgres-send-msg-us", measure.as_us() as usize);
         Ok(())
     }
 
     pub fn update_slot_status(&mut self, slot: u64, parent: Option<u64>, status: SlotStatus) -> Result<(), GeyserPluginError> {
-        if let Err(err) = self.sender.send(WorkRequest::UpdateSlot(Box::new(UpdateSlotRequest { slot, parent, slot_status: status }))) {
+        self.highest_notified_slot = self.highest_notified_slot.max(slot);
+        self.report_pools();
+        if self.commitment_views {
+            self.update_views(slot, status)?;
+        }
+        if status == SlotStatus::Rooted {
+            self.highest_rooted_slot = Some(self.highest_rooted_slot.map_or(slot, |s| s.max(slot)));
+            self.unrooted_slots.remove(&slot);
+            if let Some(transactions) = self.pending_rooted_transactions.remove(&slot) {
+                let mut transactions = transactions.into_values().collect::<Vec<DbTransaction>>();
+                transactions.sort_by_key(|t| t.write_version);
+                for transaction_info in transactions {
+                    self.send_transaction(transaction_info)?;
+                }
+            }
+            self.abandon_forks(slot)?;
+            // the buffers of the slots up to the root are released or abandoned
+            self.unrooted_buffers = self.unrooted_buffers.split_off(&(slot + 1));
+        } else if self.highest_rooted_slot.is_none_or(|rooted_slot| slot > rooted_slot) {
+            self.unrooted_slots.insert(slot);
+        }
+        self.discard_stale_buffers()?;
+        // only rooted statuses are kept while catching up
+        if self.is_catching_up && status != SlotStatus::Rooted {
+            self.skipped_slot_status_count += 1;
+            return Ok(());
+        }
+        if let Err(err) = self.sender.send(WorkRequest::UpdateSlot(Box::new(UpdateSlotRequest {
+            slot,
+            parent,
+            slot_status: status,
+            queued_at: Instant::now(),
+        }))) {
             return Err(GeyserPluginError::SlotStatusUpdateError {
                 msg: format!("Failed to update the slot {:?}, error: {:?}", slot, err),
             });
@@ -148,6 +339,19 @@ impl ParallelClient {
     }
 
     pub fn update_block_metadata(&mut self, block_info: &ReplicaBlockInfo) -> Result<(), GeyserPluginError> {
+        if let (Some(lag_threshold), Some(block_time)) = (self.slot_catchup_lag_secs, block_info.block_time) {
+            let lag = Utc::now().timestamp().saturating_sub(block_time);
+            let is_catching_up = lag > lag_threshold as i64;
+            if is_catching_up != self.is_catching_up {
+                info!("[update_block_metadata] catching_up={} slot={} lag_secs={}", is_catching_up, block_info.slot, lag);
+                if !is_catching_up {
+                    datapoint_info!("geyser_plugin_slot_catchup_summary", ("skipped-slot-statuses", self.skipped_slot_status_count, i64),);
+                    self.skipped_slot_status_count = 0;
+                }
+                self.is_catching_up = is_catching_up;
+            }
+        }
+        self.begin_write(block_info.slot);
         if let Err(err) = self.sender.send(WorkRequest::UpdateBlockMetadata(Box::new(UpdateBlockMetadataRequest {
             block_info: DbBlockInfo::from(block_info),
         }))) {
@@ -158,8 +362,17 @@ impl ParallelClient {
         Ok(())
     }
 
+    /// Whether `notify_end_of_startup` was already called, later calls being no-ops
+    pub fn is_startup_done(&self) -> bool {
+        self.is_startup_done.load(Ordering::Relaxed)
+    }
+
     pub fn notify_end_of_startup(&mut self) -> Result<(), GeyserPluginError> {
         info!("[notify_end_of_startup]");
+        if self.is_startup_done.load(Ordering::Relaxed) {
+            warn!("[notify_end_of_startup] called again, startup already done");
+            return Ok(());
+        }
         // Ensure all items in the queue has been received by the workers
         while !self.sender.is_empty() {
             sleep(Duration::from_millis(100));
@@ -175,15 +388,31 @@ impl ParallelClient {
             );
             sleep(Duration::from_millis(100));
         }
+
+        if let Some(startup_integrity) = &self.startup_integrity {
+            if let Err(err) = startup_integrity.report(self.stats.flushed_startup_accounts.load(Ordering::Relaxed)) {
+                error!("[notify_end_of_startup] failed to report startup integrity: ({})", err);
+            }
+        }
         Ok(())
     }
 
     pub fn log_transaction_info(&mut self, transaction_info: &ReplicaTransactionInfoV2, slot: u64) -> Result<(), GeyserPluginError> {
         self.transaction_write_version.fetch_add(1, Ordering::Relaxed);
-        let wrk_item = WorkRequest::LogTransaction(Box::new(LogTransactionRequest {
-            transaction_info: build_db_transaction(slot, transaction_info, self.transaction_write_version.load(Ordering::Relaxed)),
-        }));
+        let transaction_info = build_db_transaction(slot, transaction_info, self.transaction_write_version.load(Ordering::Relaxed));
 
+        // hold back transactions until their slot roots, a later notification for the same signature replaces the earlier one
+        if self.write_transactions_on_root && self.highest_rooted_slot.is_none_or(|rooted_slot| slot > rooted_slot) {
+            self.pending_rooted_transactions.entry(slot).or_default().insert(transaction_info.signature.clone(), transaction_info);
+            self.unrooted_buffers.entry(slot).or_insert_with(Instant::now);
+            return Ok(());
+        }
+        self.send_transaction(transaction_info)
+    }
+
+    fn send_transaction(&mut self, transaction_info: DbTransaction) -> Result<(), GeyserPluginError> {
+        self.begin_write(transaction_info.slot as u64);
+        let wrk_item = WorkRequest::LogTransaction(Box::new(LogTransactionRequest { transaction_info }));
         if let Err(err) = self.sender.send(wrk_item) {
             return Err(GeyserPluginError::SlotStatusUpdateError {
                 msg: format!("Failed to update the transaction, error: {:?}", err),
@@ -191,4 +420,173 @@ impl ParallelClient {
         }
         Ok(())
     }
+
+    /// Holds an account update until its slot is confirmed then rooted, those of slots already confirmed or rooted
+    /// being written to their views at once
+    fn hold_for_views(&mut self, account: &DbAccountInfo) -> Result<(), GeyserPluginError> {
+        let slot = account.slot as u64;
+        if self.highest_rooted_slot.is_some_and(|rooted_slot| slot <= rooted_slot) {
+            for view in CommitmentView::ALL {
+                self.send_account_view(account.clone(), view)?;
+            }
+            return Ok(());
+        }
+        self.unrooted_buffers.entry(slot).or_insert_with(Instant::now);
+        let view_slot = self.view_slots.entry(slot).or_default();
+        view_slot.accounts.push(account.clone());
+        if view_slot.is_confirmed {
+            self.send_account_view(account.clone(), CommitmentView::Confirmed)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the account updates of `slot` to the confirmed view once confirmed and to the rooted one once rooted.
+    /// A slot rooted without being notified confirmed is written to both
+    fn update_views(&mut self, slot: u64, status: SlotStatus) -> Result<(), GeyserPluginError> {
+        match status {
+            SlotStatus::Confirmed => {
+                self.unrooted_buffers.entry(slot).or_insert_with(Instant::now);
+                let view_slot = self.view_slots.entry(slot).or_default();
+                if view_slot.is_confirmed {
+                    return Ok(());
+                }
+                view_slot.is_confirmed = true;
+                let accounts = view_slot.accounts.clone();
+                for account in accounts {
+                    self.send_account_view(account, CommitmentView::Confirmed)?;
+                }
+            }
+            SlotStatus::Rooted => {
+                let view_slot = self.view_slots.remove(&slot).unwrap_or_default();
+                for account in view_slot.accounts {
+                    if !view_slot.is_confirmed {
+                        self.send_account_view(account.clone(), CommitmentView::Confirmed)?;
+                    }
+                    self.send_account_view(account, CommitmentView::Rooted)?;
+                }
+                // the slots below the root still held are abandoned, the confirmed ones restored
+                let view_slots = self.view_slots.split_off(&slot);
+                let abandoned_slots = std::mem::replace(&mut self.view_slots, view_slots);
+                let confirmed_slots = abandoned_slots.into_iter().filter(|(_, view_slot)| view_slot.is_confirmed).map(|(slot, _)| slot).collect::<Vec<u64>>();
+                if !confirmed_slots.is_empty() {
+                    warn!("[update_views] rooted_slot={} abandoned_confirmed_slots={:?}", slot, confirmed_slots);
+                    if let Err(err) = self.sender.send(WorkRequest::AbandonConfirmedSlots(confirmed_slots)) {
+                        return Err(GeyserPluginError::SlotStatusUpdateError {
+                            msg: format!("Failed to abandon the confirmed slots below {:?}, error: {:?}", slot, err),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn send_account_view(&mut self, account: DbAccountInfo, view: CommitmentView) -> Result<(), GeyserPluginError> {
+        self.begin_write(account.slot as u64);
+        let pubkey = account.pubkey_str().to_string();
+        if let Err(err) = self.sender.send(WorkRequest::UpdateAccountView(Box::new(UpdateAccountViewRequest { account, view }))) {
+            return Err(GeyserPluginError::AccountsUpdateError {
+                msg: format!("Failed to update the {:?} view of the account {:?}, error: {:?}", view, pubkey, err),
+            });
+        }
+        Ok(())
+    }
+
+    /// Discards the writes held for the unrooted slots older than `max_unrooted_buffer_age_slots` or
+    /// `max_unrooted_buffer_age_secs`, e.g. on a fork the root never reaches. The confirmed ones are restored
+    fn discard_stale_buffers(&mut self) -> Result<(), GeyserPluginError> {
+        if self.max_unrooted_buffer_age_slots.is_none() && self.max_unrooted_buffer_age.is_none() {
+            return Ok(());
+        }
+        let now = Instant::now();
+        let stale_slots = self
+            .unrooted_buffers
+            .iter()
+            .filter(|(slot, buffered_at)| {
+                self.max_unrooted_buffer_age_slots.is_some_and(|max_age| self.highest_notified_slot.saturating_sub(**slot) > max_age)
+                    || self.max_unrooted_buffer_age.is_some_and(|max_age| now.duration_since(**buffered_at) > max_age)
+            })
+            .map(|(slot, _)| *slot)
+            .collect::<Vec<u64>>();
+        if stale_slots.is_empty() {
+            return Ok(());
+        }
+        let mut discarded_writes = 0;
+        let mut confirmed_slots = vec![];
+        for slot in &stale_slots {
+            self.unrooted_buffers.remove(slot);
+            discarded_writes += self.pending_rooted_transactions.remove(slot).map_or(0, |transactions| transactions.len());
+            if let Some(view_slot) = self.view_slots.remove(slot) {
+                discarded_writes += view_slot.accounts.len();
+                if view_slot.is_confirmed {
+                    confirmed_slots.push(*slot);
+                }
+            }
+        }
+        warn!(
+            "[discard_stale_buffers] highest_slot={} discarded_slots={:?} discarded_writes={}",
+            self.highest_notified_slot, stale_slots, discarded_writes
+        );
+        inc_new_counter_info!("geyser-plugin-postgres-unrooted-buffer-discarded-slots", stale_slots.len());
+        inc_new_counter_info!("geyser-plugin-postgres-unrooted-buffer-discarded-writes", discarded_writes);
+        if !confirmed_slots.is_empty() {
+            if let Err(err) = self.sender.send(WorkRequest::AbandonConfirmedSlots(confirmed_slots)) {
+                return Err(GeyserPluginError::SlotStatusUpdateError {
+                    msg: format!("Failed to abandon the stale confirmed slots, error: {:?}", err),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops the slots below `rooted_slot` still unrooted, the ancestors of a root being rooted before it,
+    /// and reports them as an abandoned fork
+    fn abandon_forks(&mut self, rooted_slot: u64) -> Result<(), GeyserPluginError> {
+        let unrooted_slots = self.unrooted_slots.split_off(&rooted_slot);
+        let mut slots = std::mem::replace(&mut self.unrooted_slots, unrooted_slots);
+        let pending_rooted_transactions = self.pending_rooted_transactions.split_off(&rooted_slot);
+        let dropped_transactions = std::mem::replace(&mut self.pending_rooted_transactions, pending_rooted_transactions);
+        slots.extend(dropped_transactions.keys());
+        if slots.is_empty() {
+            return Ok(());
+        }
+        let event = ForkEvent {
+            rooted_slot,
+            slots: slots.into_iter().collect(),
+            dropped_transactions: dropped_transactions.values().map(|t| t.len()).sum(),
+        };
+        let (first_slot, last_slot) = (event.slots[0], event.slots[event.slots.len() - 1]);
+        warn!(
+            "[fork_event] rooted_slot={} abandoned_slots={}..={} count={} dropped_transactions={}",
+            rooted_slot,
+            first_slot,
+            last_slot,
+            event.slots.len(),
+            event.dropped_transactions
+        );
+        datapoint_info!(
+            "geyser_plugin_fork_event",
+            ("rooted-slot", rooted_slot, i64),
+            ("first-slot", first_slot, i64),
+            ("last-slot", last_slot, i64),
+            ("abandoned-slots", event.slots.len(), i64),
+            ("dropped-transactions", event.dropped_transactions, i64),
+        );
+        if self.write_fork_events {
+            if let Err(err) = self.sender.send(WorkRequest::LogForkEvent(Box::new(event))) {
+                return Err(GeyserPluginError::SlotStatusUpdateError {
+                    msg: format!("Failed to log the fork event at slot {:?}, error: {:?}", rooted_slot, err),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts a write of `slot` in flight until the worker commits it, the slot's rooted status being dispatched after all its writes
+    fn begin_write(&self, slot: u64) {
+        if let Some(slot_completion) = &self.slot_completion {
+            slot_completion.begin_write(slot);
+        }
+    }
 }
@@ -1,32 +1,52 @@
 use crate::abort;
+use crate::accounts_selector::SharedAccountsSelector;
+use crate::admin_socket::RuntimeStats;
+use crate::admin_socket::WORKER_BUSY;
+use crate::admin_socket::WORKER_IDLE;
+use crate::admin_socket::WORKER_STOPPED;
 use crate::config::GeyserPluginPostgresConfig;
+use crate::counters::inc_sampled_counter_debug;
+use crate::postgres_client::commitment_view::CommitmentView;
+use crate::postgres_client::pool::ConnectionPools;
+use crate::postgres_client::slot_completion::SlotCompletion;
+use crate::postgres_client::startup_memory::StartupMemory;
 use crate::postgres_client::DbAccountInfo;
 use crate::postgres_client::DbBlockInfo;
 use crate::postgres_client::DbTransaction;
+use crate::postgres_client::ForkEvent;
+use crate::postgres_client::MintDecimals;
 use crate::postgres_client::PostgresClient;
 use crate::postgres_client::SimplePostgresClient;
+use crate::redis_sink::RedisSink;
 use crossbeam_channel::Receiver;
 use crossbeam_channel::RecvTimeoutError;
 use log::*;
 use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
 use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
 use solana_measure::measure::Measure;
-use solana_metrics::*;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 pub struct UpdateAccountRequest {
     pub account: DbAccountInfo,
     pub is_startup: bool,
 }
 
+pub struct UpdateAccountViewRequest {
+    pub account: DbAccountInfo,
+    pub view: CommitmentView,
+}
+
 pub struct UpdateSlotRequest {
     pub slot: u64,
     pub parent: Option<u64>,
     pub slot_status: SlotStatus,
+    /// When the status was notified, its wait behind the writes queued before it being reported
+    pub queued_at: Instant,
 }
 
 pub struct LogTransactionRequest {
@@ -43,19 +63,63 @@ pub enum WorkRequest {
     UpdateSlot(Box<UpdateSlotRequest>),
     LogTransaction(Box<LogTransactionRequest>),
     UpdateBlockMetadata(Box<UpdateBlockMetadataRequest>),
+    LogForkEvent(Box<ForkEvent>),
+    UpdateAccountView(Box<UpdateAccountViewRequest>),
+    /// Confirmed slots abandoned by a new root
+    AbandonConfirmedSlots(Vec<u64>),
+}
+
+/// The state the workers share, cloned for each of them
+#[derive(Clone)]
+pub struct SharedWorkerState {
+    /// Set when `write_slot_complete` is
+    pub slot_completion: Option<Arc<SlotCompletion>>,
+    /// Set when `startup_memory_budget_bytes` is
+    pub startup_memory: Option<Arc<StartupMemory>>,
+    /// Set when the token accounts need the decimals of their mint
+    pub mint_decimals: Option<MintDecimals>,
+    pub accounts_selector: SharedAccountsSelector,
+    pub pools: ConnectionPools,
 }
 
 pub struct ParallelClientWorker {
     client: SimplePostgresClient,
     /// Indicating if accounts notification during startup is done.
     is_startup_done: bool,
+    /// Runtime state shared with the admin socket, this worker's entry is at `index`
+    stats: Arc<RuntimeStats>,
+    index: usize,
+    /// Whether the worker stops once startup is done, being beyond `threads`
+    startup_only: bool,
+    /// Mirror of the selected accounts, set when `redis_address` is
+    redis_sink: Option<RedisSink>,
 }
 
 impl ParallelClientWorker {
-    pub fn new(config: GeyserPluginPostgresConfig) -> Result<Self, GeyserPluginError> {
-        let result = SimplePostgresClient::new(&config);
+    pub fn new(config: GeyserPluginPostgresConfig, stats: Arc<RuntimeStats>, index: usize, shared: SharedWorkerState, startup_only: bool) -> Result<Self, GeyserPluginError> {
+        let mut redis_sink = RedisSink::connect(&config)?;
+        if let Some(redis_sink) = &mut redis_sink {
+            redis_sink.set_accounts_selector(shared.accounts_selector.clone());
+        }
+        let result = SimplePostgresClient::with_pools(&config, shared.mint_decimals, shared.pools);
         match result {
-            Ok(client) => Ok(ParallelClientWorker { client, is_startup_done: false }),
+            Ok(mut client) => {
+                client.set_accounts_selector(shared.accounts_selector);
+                if let Some(slot_completion) = shared.slot_completion {
+                    client.set_slot_completion(slot_completion);
+                }
+                if let Some(startup_memory) = shared.startup_memory {
+                    client.set_startup_memory(startup_memory);
+                }
+                Ok(ParallelClientWorker {
+                    client,
+                    is_startup_done: false,
+                    stats,
+                    index,
+                    startup_only,
+                    redis_sink,
+                })
+            }
             Err(err) => {
                 error!("[ParallelClientWorker] error=[{}]", err);
                 Err(err)
@@ -71,57 +135,100 @@ impl ParallelClientWorker {
         startup_done_count: Arc<AtomicUsize>,
         panic_on_db_errors: bool,
     ) -> Result<(), GeyserPluginError> {
+        let stats = self.stats.clone();
+        let worker_stats = &stats.workers[self.index];
         while !exit_worker.load(Ordering::Relaxed) {
+            worker_stats.state.store(WORKER_IDLE, Ordering::Relaxed);
+            worker_stats.pending_account_updates.store(self.client.pending_account_update_count(), Ordering::Relaxed);
+            worker_stats.pending_transactions.store(self.client.pending_transaction_count(), Ordering::Relaxed);
+            worker_stats.bytes_written.store(self.client.bytes_written(), Ordering::Relaxed);
             let mut measure = Measure::start("geyser-plugin-postgres-worker-recv");
             let work = receiver.recv_timeout(Duration::from_millis(500));
             measure.stop();
-            inc_new_counter_debug!("geyser-plugin-postgres-worker-recv-us", measure.as_us() as usize, 100000, 100000);
+            inc_sampled_counter_debug!("geyser-plugin-postgres-worker-recv-us", measure.as_us() as usize);
+            if work.is_ok() {
+                worker_stats.state.store(WORKER_BUSY, Ordering::Relaxed);
+            }
             match work {
                 Ok(work) => match work {
                     WorkRequest::UpdateAccount(request) => {
-                        if let Err(err) = self.client.update_account(request.account, request.is_startup) {
-                            error!("Failed to update account: ({})", err);
-                            if panic_on_db_errors {
-                                abort();
+                        if let Some(redis_sink) = &mut self.redis_sink {
+                            if let Err(err) = redis_sink.update_account(&request.account, request.is_startup) {
+                                report_error(&stats, format!("Failed to mirror account to Redis: ({})", err), panic_on_db_errors);
                             }
                         }
+                        if let Err(err) = self.client.update_account(request.account, request.is_startup) {
+                            report_error(&stats, format!("Failed to update account: ({})", err), panic_on_db_errors);
+                        }
                     }
                     WorkRequest::UpdateSlot(request) => {
+                        inc_sampled_counter_debug!("geyser-plugin-postgres-slot-status-queue-us", request.queued_at.elapsed().as_micros() as usize);
                         if let Err(err) = self.client.update_slot_status(request.slot, request.parent, request.slot_status) {
-                            error!("Failed to update slot: ({})", err);
-                            if panic_on_db_errors {
-                                abort();
-                            }
+                            report_error(&stats, format!("Failed to update slot: ({})", err), panic_on_db_errors);
                         }
+                        inc_sampled_counter_debug!("geyser-plugin-postgres-slot-status-latency-us", request.queued_at.elapsed().as_micros() as usize);
                     }
                     WorkRequest::LogTransaction(transaction_log_info) => {
                         if let Err(err) = self.client.log_transaction(transaction_log_info.transaction_info) {
-                            error!("Failed to update transaction: ({})", err);
-                            if panic_on_db_errors {
-                                abort();
-                            }
+                            report_error(&stats, format!("Failed to update transaction: ({})", err), panic_on_db_errors);
                         }
                     }
                     WorkRequest::UpdateBlockMetadata(block_info) => {
                         if let Err(err) = self.client.update_block_metadata(block_info.block_info) {
-                            error!("Failed to update block metadata: ({})", err);
-                            if panic_on_db_errors {
-                                abort();
-                            }
+                            report_error(&stats, format!("Failed to update block metadata: ({})", err), panic_on_db_errors);
+                        }
+                    }
+                    WorkRequest::LogForkEvent(event) => {
+                        if let Err(err) = self.client.log_fork_event(*event) {
+                            report_error(&stats, format!("Failed to log fork event: ({})", err), panic_on_db_errors);
+                        }
+                    }
+                    WorkRequest::UpdateAccountView(request) => {
+                        if let Err(err) = self.client.update_account_view(request.account, request.view) {
+                            report_error(&stats, format!("Failed to update account view: ({})", err), panic_on_db_errors);
+                        }
+                    }
+                    WorkRequest::AbandonConfirmedSlots(slots) => {
+                        if let Err(err) = self.client.abandon_confirmed_slots(slots) {
+                            report_error(&stats, format!("Failed to abandon confirmed slots: ({})", err), panic_on_db_errors);
                         }
                     }
                 },
                 Err(err) => match err {
                     RecvTimeoutError::Timeout => {
+                        if let Some(redis_sink) = &mut self.redis_sink {
+                            if let Err(err) = redis_sink.flush() {
+                                report_error(&stats, format!("Failed to mirror accounts to Redis: ({})", err), panic_on_db_errors);
+                            }
+                        }
+                        // write the transactions buffered while idle
+                        if let Err(err) = self.client.flush_transactions() {
+                            report_error(&stats, format!("Failed to flush transactions: ({})", err), panic_on_db_errors);
+                        }
+                        // and commit the writes grouped so far
+                        if let Err(err) = self.client.commit_grouped_writes() {
+                            report_error(&stats, format!("Failed to commit grouped writes: ({})", err), panic_on_db_errors);
+                        }
+                        // the notifications may be waiting for the accounts buffered by idle workers
+                        if !self.is_startup_done {
+                            if let Err(err) = self.client.flush_for_startup_memory() {
+                                report_error(&stats, format!("Failed to flush accounts: ({})", err), panic_on_db_errors);
+                            }
+                            if let Err(err) = self.client.flush_for_interval() {
+                                report_error(&stats, format!("Failed to flush accounts: ({})", err), panic_on_db_errors);
+                            }
+                        }
                         if !self.is_startup_done && is_startup_done.load(Ordering::Relaxed) {
                             if let Err(err) = self.client.notify_end_of_startup() {
-                                error!("Error in notifying end of startup: ({})", err);
-                                if panic_on_db_errors {
-                                    abort();
-                                }
+                                report_error(&stats, format!("Error in notifying end of startup: ({})", err), panic_on_db_errors);
                             }
                             self.is_startup_done = true;
+                            stats.flushed_startup_accounts.fetch_add(self.client.flushed_startup_account_count(), Ordering::Relaxed);
                             startup_done_count.fetch_add(1, Ordering::Relaxed);
+                            if self.startup_only {
+                                info!("[ParallelClientWorker] stopping startup worker={}", self.index);
+                                break;
+                            }
                         }
 
                         continue;
@@ -136,6 +243,31 @@ impl ParallelClientWorker {
                 },
             }
         }
+        // the startup accounts still buffered when stopping before the end of startup
+        if let Err(err) = self.client.join() {
+            let err = err.downcast::<GeyserPluginError>().map_or_else(|_| "unknown error".to_string(), |err| err.to_string());
+            report_error(&stats, format!("Failed to flush accounts: ({})", err), panic_on_db_errors);
+        }
+        if let Err(err) = self.client.flush_transactions() {
+            report_error(&stats, format!("Failed to flush transactions: ({})", err), panic_on_db_errors);
+        }
+        if let Err(err) = self.client.commit_grouped_writes() {
+            report_error(&stats, format!("Failed to commit grouped writes: ({})", err), panic_on_db_errors);
+        }
+        if let Some(redis_sink) = &mut self.redis_sink {
+            if let Err(err) = redis_sink.flush() {
+                report_error(&stats, format!("Failed to mirror accounts to Redis: ({})", err), panic_on_db_errors);
+            }
+        }
+        worker_stats.state.store(WORKER_STOPPED, Ordering::Relaxed);
         Ok(())
     }
 }
+
+fn report_error(stats: &RuntimeStats, msg: String, panic_on_db_errors: bool) {
+    error!("{}", msg);
+    stats.set_last_error(msg);
+    if panic_on_db_errors {
+        abort();
+    }
+}
@@ -1,26 +1,76 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
 use std::str::FromStr;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::OnceLock;
 
 use crate::accounts_selector::AccountHandlerConfig;
 use crate::accounts_selector::AccountsSelectorConfig;
 use crate::config::GeyserPluginPostgresConfig;
+use crate::config::IntegerOverflow;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use log::*;
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
 use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_metrics::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+use thiserror::Error;
 
+use crate::postgres_client::migrations::Migration;
+
+use super::account_projection::AccountProjections;
+use super::account_query::AccountQuery;
+use super::address_lookup_table_handler::AddressLookupTableHandler;
+use super::anchor_account_handler::GenericAnchorHandler;
+use super::candy_machine_handler::CandyMachineHandler;
 use super::metadata_creators_account_handler::MetadataCreatorsAccountHandler;
+use super::mint_account_handler::MintAccountHandler;
+use super::mint_allowlist::MintAllowlist;
+use super::mint_decimals::MintDecimals;
+use super::owner_history_handler::OwnerHistoryHandler;
+use super::program_census_handler::ProgramCensusHandler;
+use super::raw_account_handler::RawAccountHandler;
+use super::rent_epoch_history_handler::RentEpochHistoryHandler;
+use super::spl_governance_account_handler::SplGovernanceAccountHandler;
 use super::token_account_handler::TokenAccountHandler;
 use super::token_manager_handler::TokenManagerAccountHandler;
 use super::unknown_account_handler::UnknownAccountHandler;
 
+/// Rent epoch of the rent-exempt accounts, stored as -1
+const RENT_EXEMPT_RENT_EPOCH: u64 = u64::MAX;
+/// An out-of-range value is logged once every so many, the counter counting them all
+const INTEGER_OVERFLOW_LOG_INTERVAL: usize = 10_000;
+static INTEGER_OVERFLOWS: AtomicUsize = AtomicUsize::new(0);
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum AccountHandlerId {
     TokenMetadataCreators,
     TokenAccount,
     TokenManager,
     UnknownAccount,
+    SplGovernance,
+    ProgramCensus,
+    TokenMint,
+    AnchorAccount,
+    RawAccount,
+    OwnerHistory,
+    AddressLookupTable,
+    CandyMachine,
+    RentEpochHistory,
 }
 #[derive(Debug)]
 pub struct UnknownAccountHandlerId;
 
+#[derive(Error, Debug)]
+#[error("Account handler {0:?} is already registered")]
+pub struct DuplicateAccountHandlerId(pub AccountHandlerId);
+
 impl FromStr for AccountHandlerId {
     type Err = UnknownAccountHandlerId;
 
@@ -30,35 +80,182 @@ impl FromStr for AccountHandlerId {
             "token_account" => Ok(Self::TokenAccount),
             "token_manager" => Ok(Self::TokenManager),
             "unknown_account" => Ok(Self::UnknownAccount),
+            "spl_governance" => Ok(Self::SplGovernance),
+            "program_census" => Ok(Self::ProgramCensus),
+            "token_mint" => Ok(Self::TokenMint),
+            "anchor_account" => Ok(Self::AnchorAccount),
+            "raw_account" => Ok(Self::RawAccount),
+            "owner_history" => Ok(Self::OwnerHistory),
+            "address_lookup_table" => Ok(Self::AddressLookupTable),
+            "candy_machine" => Ok(Self::CandyMachine),
+            "rent_epoch_history" => Ok(Self::RentEpochHistory),
             _ => Err(UnknownAccountHandlerId),
         }
     }
 }
 
-pub fn all_account_handlers() -> HashMap<AccountHandlerId, Box<dyn AccountHandler>> {
+/// Adds a handler under its id, refusing to replace the handler already registered with it
+pub fn register_account_handler(
+    account_handlers: &mut HashMap<AccountHandlerId, Box<dyn AccountHandler>>,
+    id: AccountHandlerId,
+    handler: Box<dyn AccountHandler>,
+) -> Result<(), DuplicateAccountHandlerId> {
+    if account_handlers.contains_key(&id) {
+        return Err(DuplicateAccountHandlerId(id));
+    }
+    account_handlers.insert(id, handler);
+    Ok(())
+}
+
+/// Handlers whose upsert supports an `upsert_guards` predicate
+pub const UPSERT_GUARD_HANDLERS: [AccountHandlerId; 4] = [
+    AccountHandlerId::TokenAccount,
+    AccountHandlerId::TokenMint,
+    AccountHandlerId::UnknownAccount,
+    AccountHandlerId::RawAccount,
+];
+
+/// The `upsert_guards` predicate of the handler, to be appended to the condition of its upsert's update
+fn upsert_guard(config: &GeyserPluginPostgresConfig, handler_id: &str) -> String {
+    config.upsert_guards.get(handler_id).map_or(String::new(), |predicate| format!(" AND ({})", predicate))
+}
+
+pub fn all_account_handlers(
+    config: &GeyserPluginPostgresConfig,
+    mint_allowlist: Option<MintAllowlist>,
+    mint_decimals: Option<MintDecimals>,
+) -> Result<HashMap<AccountHandlerId, Box<dyn AccountHandler>>, GeyserPluginError> {
+    let handlers: Vec<(AccountHandlerId, Box<dyn AccountHandler>)> = vec![
+        (
+            AccountHandlerId::TokenAccount,
+            Box::new(TokenAccountHandler {
+                mint_allowlist,
+                skipped_owners: match config.skip_high_cardinality_owners {
+                    true => config.high_cardinality_owners.iter().cloned().collect(),
+                    false => HashSet::default(),
+                },
+                mint_decimals,
+                nft_only: config.nft_token_accounts_only,
+                store_ui_amount: config.store_ui_amount,
+                index_wallets: config.index_wallets,
+                upsert_guard: upsert_guard(config, "token_account"),
+            }),
+        ),
+        (
+            AccountHandlerId::TokenMetadataCreators,
+            Box::new(MetadataCreatorsAccountHandler {
+                strip_control_characters: config.strip_control_characters,
+            }),
+        ),
+        (AccountHandlerId::TokenManager, Box::new(TokenManagerAccountHandler {})),
+        (
+            AccountHandlerId::UnknownAccount,
+            Box::new(UnknownAccountHandler {
+                raw_data_max_bytes: config.store_raw_data_max_bytes,
+                rent: config.rent(),
+                projections: AccountProjections::new(config)?,
+                upsert_guard: upsert_guard(config, "unknown_account"),
+            }),
+        ),
+        (AccountHandlerId::SplGovernance, Box::new(SplGovernanceAccountHandler {})),
+        (
+            AccountHandlerId::TokenMint,
+            Box::new(MintAccountHandler {
+                strip_control_characters: config.strip_control_characters,
+                index_burns: config.index_token_burns,
+                upsert_guard: upsert_guard(config, "token_mint"),
+            }),
+        ),
+        (
+            AccountHandlerId::ProgramCensus,
+            Box::new(ProgramCensusHandler {
+                sampling_rate: config.program_census_sampling_rate,
+            }),
+        ),
+        (AccountHandlerId::AnchorAccount, Box::new(GenericAnchorHandler::new(config)?)),
+        (
+            AccountHandlerId::RawAccount,
+            Box::new(RawAccountHandler {
+                upsert_guard: upsert_guard(config, "raw_account"),
+            }),
+        ),
+        (AccountHandlerId::OwnerHistory, Box::new(OwnerHistoryHandler {})),
+        (AccountHandlerId::AddressLookupTable, Box::new(AddressLookupTableHandler {})),
+        (AccountHandlerId::CandyMachine, Box::new(CandyMachineHandler {})),
+        (AccountHandlerId::RentEpochHistory, Box::new(RentEpochHistoryHandler {})),
+    ];
     let mut account_handlers: HashMap<AccountHandlerId, Box<dyn AccountHandler>> = HashMap::default();
-    account_handlers.insert(AccountHandlerId::TokenAccount, Box::new(TokenAccountHandler {}));
-    account_handlers.insert(AccountHandlerId::TokenMetadataCreators, Box::new(MetadataCreatorsAccountHandler {}));
-    account_handlers.insert(AccountHandlerId::TokenManager, Box::new(TokenManagerAccountHandler {}));
-    account_handlers.insert(AccountHandlerId::UnknownAccount, Box::new(UnknownAccountHandler {}));
-    account_handlers
+    for (id, handler) in handlers {
+        register_account_handler(&mut account_handlers, id, handler).map_err(|err| GeyserPluginError::Custom(Box::new(err)))?;
+    }
+    Ok(account_handlers)
+}
+
+/// Checks every handler id referenced by the selector is registered
+pub fn validate_account_handlers(account_selector: &Option<AccountsSelectorConfig>) -> Result<(), GeyserPluginError> {
+    let Some(selector) = account_selector else {
+        return Ok(());
+    };
+    let handlers = selector.accounts.iter().chain(selector.owners.iter()).flat_map(|handlers| handlers.iter());
+    for (key, handler_configs) in handlers {
+        for h in handler_configs {
+            if AccountHandlerId::from_str(&h.handler_id).is_err() {
+                return Err(GeyserPluginError::ConfigFileReadError {
+                    msg: format!("\"accounts_selector\" references unknown handler_id \"{}\" for \"{}\"", h.handler_id, key),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks the handlers of `handler_dependencies` are registered and do not depend on each other in a cycle
+pub fn validate_handler_dependencies(handler_dependencies: &HashMap<String, Vec<String>>) -> Result<(), GeyserPluginError> {
+    let config_error = |msg: String| GeyserPluginError::ConfigFileReadError { msg };
+    for handler_id in handler_dependencies.keys().chain(handler_dependencies.values().flatten()) {
+        if AccountHandlerId::from_str(handler_id).is_err() {
+            return Err(config_error(format!("\"handler_dependencies\" references unknown handler_id \"{}\"", handler_id)));
+        }
+    }
+    let mut done = HashSet::default();
+    for handler_id in handler_dependencies.keys() {
+        visit_handler_dependencies(handler_id, handler_dependencies, &mut Vec::new(), &mut done).map_err(|cycle| config_error(format!("\"handler_dependencies\" form a cycle: {}", cycle)))?;
+    }
+    Ok(())
+}
+
+/// Depth-first walk of the dependencies of a handler, a handler met again on its own path closing a cycle
+fn visit_handler_dependencies<'a>(handler_id: &'a str, handler_dependencies: &'a HashMap<String, Vec<String>>, path: &mut Vec<&'a str>, done: &mut HashSet<&'a str>) -> Result<(), String> {
+    if done.contains(handler_id) {
+        return Ok(());
+    }
+    if path.contains(&handler_id) {
+        return Err(format!("{} -> {}", path.join(" -> "), handler_id));
+    }
+    path.push(handler_id);
+    for dependency in handler_dependencies.get(handler_id).into_iter().flatten() {
+        visit_handler_dependencies(dependency, handler_dependencies, path, done)?;
+    }
+    path.pop();
+    done.insert(handler_id);
+    Ok(())
 }
 
 pub fn select_account_handlers(account_selector: &Option<AccountsSelectorConfig>, account: &DbAccountInfo, is_startup: bool) -> Vec<AccountHandlerConfig> {
-    let account_key = bs58::encode(&account.pubkey).into_string();
-    let owner_key = bs58::encode(&account.owner).into_string();
+    let account_key = account.pubkey_str();
+    let owner_key = account.owner_str();
     // get selected handlers from config
     let mut selected_handlers = Vec::new();
     if let Some(selector) = &account_selector {
         // add with any account specific handlers
         if let Some(accounts) = &selector.accounts {
-            if let Some(handlers) = accounts.get(&account_key) {
+            if let Some(handlers) = accounts.get(account_key) {
                 selected_handlers = handlers.to_vec();
             }
         }
         // get account owner handlers
         if let Some(owners) = &selector.owners {
-            if let Some(handlers) = owners.get(&owner_key) {
+            if let Some(handlers) = owners.get(owner_key) {
                 selected_handlers = handlers.to_vec();
             }
         }
@@ -66,6 +263,21 @@ pub fn select_account_handlers(account_selector: &Option<AccountsSelectorConfig>
     selected_handlers.into_iter().filter(|h| !is_startup || !h.skip_on_startup.unwrap_or(false)).collect()
 }
 
+/// The handlers of all owners for an account closed and reassigned to the system program, which any of them may have written
+pub fn closed_account_handlers(account_selector: &Option<AccountsSelectorConfig>, account: &DbAccountInfo) -> Vec<AccountHandlerConfig> {
+    if account.lamports != 0 || account.owner != system_program::id().as_ref() {
+        return Vec::new();
+    }
+    let mut handlers: Vec<AccountHandlerConfig> = Vec::new();
+    let owner_handlers = account_selector.iter().flat_map(|selector| selector.owners.iter()).flat_map(|owners| owners.values()).flatten();
+    for h in owner_handlers {
+        if !handlers.iter().any(|handler| handler.handler_id == h.handler_id) {
+            handlers.push(h.clone());
+        }
+    }
+    handlers
+}
+
 pub trait AccountHandler {
     fn enabled(&self, _config: &GeyserPluginPostgresConfig) -> bool {
         true
@@ -73,12 +285,55 @@ pub trait AccountHandler {
 
     fn init(&self, config: &GeyserPluginPostgresConfig) -> String;
 
+    /// Changes of the tables of earlier versions `init` cannot express idempotently, applied once after it in version
+    /// order. Their versions are unique across the handlers
+    fn migrations(&self, _config: &GeyserPluginPostgresConfig) -> Vec<Migration> {
+        Vec::new()
+    }
+
     fn account_match(&self, account: &DbAccountInfo) -> bool;
 
-    fn account_update(&self, account: &DbAccountInfo) -> String;
+    /// Query updating the account. The values read from its data are bound as parameters of the statements
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery;
+
+    /// Query updating the accounts of a startup batch, in one statement per table where the handler supports it.
+    /// The accounts are updated one by one by default
+    fn account_update_batch(&self, accounts: &[&DbAccountInfo]) -> AccountQuery {
+        let mut query = AccountQuery::default();
+        for account in accounts {
+            query.append(self.account_update(account));
+        }
+        query
+    }
+
+    /// Query removing a closed account, its lamports being zero, instead of updating it. It must not remove the
+    /// account recreated at a later slot. Closed accounts are ignored by default
+    fn account_delete(&self, _account: &DbAccountInfo) -> AccountQuery {
+        AccountQuery::default()
+    }
 }
 
-#[derive(Clone, PartialEq, Debug)]
+/// Base58 encodings of the pubkey and owner of an account, computed on first use. They are neither serialized nor
+/// compared, the accounts being equal by their values
+#[derive(Clone, Default)]
+pub struct ReadableAccountInfo {
+    pubkey: OnceLock<String>,
+    owner: OnceLock<String>,
+}
+
+impl PartialEq for ReadableAccountInfo {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for ReadableAccountInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadableAccountInfo").finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct DbAccountInfo {
     pub pubkey: Vec<u8>,
     pub lamports: i64,
@@ -89,21 +344,82 @@ pub struct DbAccountInfo {
     pub slot: i64,
     pub write_version: i64,
     pub txn_signature: Option<Vec<u8>>,
+    /// Encodings of `pubkey` and `owner`, which are not to be changed once read through `pubkey_str` or `owner_str`
+    #[serde(skip)]
+    #[borsh_skip]
+    pub readable: ReadableAccountInfo,
 }
 
 impl DbAccountInfo {
+    /// `pubkey` in base58, encoded once for the logs, the selection and the handlers of the account
+    pub fn pubkey_str(&self) -> &str {
+        self.readable.pubkey.get_or_init(|| bs58::encode(&self.pubkey).into_string())
+    }
+
+    /// `owner` in base58, encoded once
+    pub fn owner_str(&self) -> &str {
+        self.readable.owner.get_or_init(|| bs58::encode(&self.owner).into_string())
+    }
+
+    /// The account values exceeding the range of BIGINT are clamped to it
     pub fn new(account: &ReplicaAccountInfoV2, slot: u64) -> DbAccountInfo {
-        let data = account.data.to_vec();
-        Self {
+        Self::checked(account, slot, IntegerOverflow::Clamp).unwrap()
+    }
+
+    /// The account, None when one of its values exceeds the range of BIGINT and `integer_overflow` skips it. The rent
+    /// epoch `u64::MAX` of the rent-exempt accounts is not an overflow, it is stored as -1
+    pub fn checked(account: &ReplicaAccountInfoV2, slot: u64, integer_overflow: IntegerOverflow) -> Option<DbAccountInfo> {
+        let checked = |name: &str, value: u64| {
+            let checked = i64::try_from(value).ok();
+            if checked.is_none() {
+                let overflows = INTEGER_OVERFLOWS.fetch_add(1, Ordering::Relaxed);
+                if overflows.is_multiple_of(INTEGER_OVERFLOW_LOG_INTERVAL) {
+                    warn!(
+                        "[DbAccountInfo] {} out of range pubkey={} value={} overflow={:?} overflows={}",
+                        name,
+                        bs58::encode(account.pubkey).into_string(),
+                        value,
+                        integer_overflow,
+                        overflows + 1
+                    );
+                }
+                inc_new_counter_info!("geyser-plugin-postgres-integer-overflows", 1);
+            }
+            match integer_overflow {
+                IntegerOverflow::Clamp => Some(checked.unwrap_or(i64::MAX)),
+                IntegerOverflow::Skip => checked,
+            }
+        };
+        Some(Self {
             pubkey: account.pubkey.to_vec(),
-            lamports: account.lamports as i64,
+            lamports: checked("lamports", account.lamports)?,
             owner: account.owner.to_vec(),
             executable: account.executable,
-            rent_epoch: account.rent_epoch as i64,
-            data,
+            rent_epoch: match account.rent_epoch {
+                RENT_EXEMPT_RENT_EPOCH => -1,
+                rent_epoch => checked("rent_epoch", rent_epoch)?,
+            },
+            data: account.data.to_vec(),
+            slot: checked("slot", slot)?,
+            write_version: checked("write_version", account.write_version)?,
+            txn_signature: account.txn_signature.map(|signature| signature.as_ref().to_vec()),
+            readable: ReadableAccountInfo::default(),
+        })
+    }
+
+    /// An account of `owner` holding `data` at `slot`, with a lamport so it is not taken for a closed account
+    pub fn from_raw(pubkey: &Pubkey, owner: &Pubkey, data: &[u8], slot: u64) -> DbAccountInfo {
+        Self {
+            pubkey: pubkey.to_bytes().to_vec(),
+            lamports: 1,
+            owner: owner.to_bytes().to_vec(),
+            executable: false,
+            rent_epoch: 0,
+            data: data.to_vec(),
             slot: slot as i64,
-            write_version: account.write_version as i64,
+            write_version: 0,
             txn_signature: None,
+            readable: ReadableAccountInfo::default(),
         }
     }
 }
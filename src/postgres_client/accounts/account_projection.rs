@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::pubkey::PUBKEY_BYTES;
+
+use super::DbAccountInfo;
+use crate::config::AccountProjection;
+use crate::config::GeyserPluginPostgresConfig;
+use crate::config::ProjectionType;
+
+/// Columns of the `account` table written by the `unknown_account` handler itself
+const ACCOUNT_COLUMNS: [&str; 12] = [
+    "pubkey",
+    "owner",
+    "lamports",
+    "slot",
+    "executable",
+    "rent_epoch",
+    "data",
+    "write_version",
+    "updated_on",
+    "txn_signature",
+    "data_omitted",
+    "is_rent_exempt",
+];
+
+impl ProjectionType {
+    pub(super) fn column_type(&self) -> &'static str {
+        match self {
+            ProjectionType::Pubkey => "VARCHAR(44)",
+            ProjectionType::U64 => "NUMERIC(20)",
+            ProjectionType::I64 => "BIGINT",
+            ProjectionType::U32 => "BIGINT",
+            ProjectionType::U8 => "SMALLINT",
+            ProjectionType::Bool => "BOOL",
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            ProjectionType::Pubkey => PUBKEY_BYTES,
+            ProjectionType::U64 => 8,
+            ProjectionType::I64 => 8,
+            ProjectionType::U32 => 4,
+            ProjectionType::U8 => 1,
+            ProjectionType::Bool => 1,
+        }
+    }
+
+    /// The field as a SQL literal, NULL when it does not fit in the data
+    pub(super) fn value(&self, data: &[u8], offset: usize) -> String {
+        let Some(bytes) = offset.checked_add(self.size()).and_then(|end| data.get(offset..end)) else {
+            return "NULL".to_string();
+        };
+        match self {
+            ProjectionType::Pubkey => format!("'{}'", bs58::encode(bytes).into_string()),
+            ProjectionType::U64 => u64::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+            ProjectionType::I64 => i64::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+            ProjectionType::U32 => u32::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+            ProjectionType::U8 => bytes[0].to_string(),
+            ProjectionType::Bool => match bytes[0] {
+                0 => "false".to_string(),
+                1 => "true".to_string(),
+                _ => "NULL".to_string(),
+            },
+        }
+    }
+}
+
+/// Whether the name can be used as a column or table name without quoting
+pub fn is_identifier(name: &str) -> bool {
+    name.len() <= 63 && name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_') && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// The `account_projections` of the config, every projected column being set on each write so
+/// an account changing owner does not keep the fields projected for its previous one
+#[derive(Clone, Default)]
+pub struct AccountProjections {
+    by_owner: HashMap<Vec<u8>, Vec<AccountProjection>>,
+    /// Projected columns of all owners with their type
+    columns: BTreeMap<String, ProjectionType>,
+}
+
+impl AccountProjections {
+    pub fn new(config: &GeyserPluginPostgresConfig) -> Result<Self, GeyserPluginError> {
+        let config_error = |msg: String| GeyserPluginError::ConfigFileReadError { msg };
+        let mut projections = Self::default();
+        for (owner, owner_projections) in &config.account_projections {
+            let owner_key = Pubkey::from_str(owner).map_err(|_| config_error(format!("\"account_projections\" owner \"{}\" is not a pubkey", owner)))?;
+            for (i, projection) in owner_projections.iter().enumerate() {
+                let name = &projection.name;
+                if !is_identifier(name) || ACCOUNT_COLUMNS.contains(&name.as_str()) {
+                    return Err(config_error(format!("\"account_projections\" column \"{}\" of \"{}\" is not a valid column name", name, owner)));
+                }
+                if owner_projections[..i].iter().any(|p| &p.name == name) {
+                    return Err(config_error(format!("\"account_projections\" column \"{}\" is projected twice for \"{}\"", name, owner)));
+                }
+                if *projections.columns.entry(name.clone()).or_insert(projection.projection_type) != projection.projection_type {
+                    return Err(config_error(format!("\"account_projections\" column \"{}\" is projected with different types", name)));
+                }
+            }
+            projections.by_owner.insert(owner_key.to_bytes().to_vec(), owner_projections.clone());
+        }
+        Ok(projections)
+    }
+
+    pub fn init(&self) -> String {
+        self.columns
+            .iter()
+            .map(|(name, projection_type)| format!("ALTER TABLE account ADD COLUMN IF NOT EXISTS {} {};\n", name, projection_type.column_type()))
+            .collect()
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = &String> {
+        self.columns.keys()
+    }
+
+    /// Values of the `columns` for the account, NULL for those not projected for its owner
+    pub fn values(&self, account: &DbAccountInfo) -> Vec<String> {
+        let owner_projections = self.by_owner.get(&account.owner);
+        self.columns
+            .keys()
+            .map(|name| {
+                owner_projections
+                    .and_then(|projections| projections.iter().find(|p| &p.name == name))
+                    .map_or("NULL".to_string(), |p| p.projection_type.value(&account.data, p.offset))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,83 @@
+use postgres::types::ToSql;
+use postgres::GenericClient;
+
+/// A statement of an account query, its text holding `$1`, `$2`, ... placeholders bound to `params` if any
+pub struct AccountStatement {
+    pub sql: String,
+    pub params: Vec<Box<dyn ToSql + Sync>>,
+}
+
+/// The statements a handler writes accounts with, executed in order in the transaction of the batch. The values read
+/// from account data, like metadata names, are bound as parameters instead of being rendered in the statement text
+#[derive(Default)]
+pub struct AccountQuery {
+    statements: Vec<AccountStatement>,
+}
+
+impl AccountQuery {
+    /// A query of the single statement `sql` bound to `params`
+    pub fn statement(sql: String, params: Vec<Box<dyn ToSql + Sync>>) -> AccountQuery {
+        let mut query = AccountQuery::default();
+        query.push(sql, params);
+        query
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// Length of the statement texts, without the bound parameters
+    pub fn len(&self) -> usize {
+        self.statements.iter().map(|statement| statement.sql.len()).sum()
+    }
+
+    pub fn statements(&self) -> &[AccountStatement] {
+        &self.statements
+    }
+
+    /// Appends `sql` bound to `params`. Statements without parameters are merged with the previous ones, to be sent
+    /// in a single round trip
+    pub fn push(&mut self, sql: String, params: Vec<Box<dyn ToSql + Sync>>) {
+        if sql.is_empty() {
+            return;
+        }
+        match self.statements.last_mut() {
+            Some(last) if params.is_empty() && last.params.is_empty() => last.sql.push_str(&sql),
+            _ => self.statements.push(AccountStatement { sql, params }),
+        }
+    }
+
+    pub fn append(&mut self, query: AccountQuery) {
+        for statement in query.statements {
+            self.push(statement.sql, statement.params);
+        }
+    }
+
+    /// Runs the statements in the transaction of `transaction`, the ones with parameters as prepared statements
+    pub fn execute(&self, transaction: &mut impl GenericClient) -> Result<(), postgres::Error> {
+        for statement in &self.statements {
+            if statement.params.is_empty() {
+                transaction.batch_execute(&statement.sql)?;
+            } else {
+                let params = statement.params.iter().map(|param| param.as_ref() as &(dyn ToSql + Sync)).collect::<Vec<&(dyn ToSql + Sync)>>();
+                transaction.execute(statement.sql.as_str(), &params)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The statements of `sql`, holding no values to bind
+impl From<String> for AccountQuery {
+    fn from(sql: String) -> Self {
+        let mut query = AccountQuery::default();
+        query.push(sql, vec![]);
+        query
+    }
+}
+
+impl From<&str> for AccountQuery {
+    fn from(sql: &str) -> Self {
+        AccountQuery::from(sql.to_string())
+    }
+}
@@ -0,0 +1,128 @@
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::pubkey::PUBKEY_BYTES;
+
+use super::account_handler::AccountHandler;
+use super::account_query::AccountQuery;
+use super::DbAccountInfo;
+
+pub static ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Pubkey = pubkey!("AddressLookupTab1e1111111111111111111111111");
+/*
+    ProgramState::LookupTable(LookupTableMeta {
+        deactivation_slot: Slot,
+        last_extended_slot: Slot,
+        last_extended_slot_start_index: u8,
+        authority: Option<Pubkey>,
+        _padding: u16,
+    })
+    serialized with bincode, a u32 variant index then the meta, followed by the addresses of the table
+*/
+const LOOKUP_TABLE_DISCRIMINATOR: u32 = 1;
+const LOOKUP_TABLE_DEACTIVATION_SLOT_OFFSET: usize = 4;
+const LOOKUP_TABLE_LAST_EXTENDED_SLOT_OFFSET: usize = 12;
+const LOOKUP_TABLE_AUTHORITY_OFFSET: usize = 21;
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+/// Stores the addresses of the lookup tables so the accounts loaded by v0 transactions can be resolved
+#[derive(Clone, Copy)]
+pub struct AddressLookupTableHandler {}
+
+impl AccountHandler for AddressLookupTableHandler {
+    fn init(&self, config: &crate::config::GeyserPluginPostgresConfig) -> String {
+        if !self.enabled(config) {
+            return "".to_string();
+        };
+        "
+            CREATE TABLE IF NOT EXISTS address_lookup_table (
+                pubkey VARCHAR(44) PRIMARY KEY,
+                authority VARCHAR(44),
+                deactivation_slot BIGINT,
+                last_extended_slot BIGINT NOT NULL,
+                slot BIGINT NOT NULL,
+                write_version BIGINT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS address_lookup_table_entry (
+                table_pubkey VARCHAR(44) NOT NULL,
+                index SMALLINT NOT NULL,
+                address VARCHAR(44) NOT NULL,
+                PRIMARY KEY (table_pubkey, index)
+            );
+            CREATE INDEX IF NOT EXISTS address_lookup_table_entry_address ON address_lookup_table_entry (address);
+        "
+        .to_string()
+    }
+
+    fn account_match(&self, account: &DbAccountInfo) -> bool {
+        account.owner == ADDRESS_LOOKUP_TABLE_PROGRAM_ID.as_ref()
+            && account.data.len() >= LOOKUP_TABLE_META_SIZE
+            && u32::from_le_bytes(account.data[0..4].try_into().unwrap()) == LOOKUP_TABLE_DISCRIMINATOR
+    }
+
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        if !self.account_match(account) {
+            return AccountQuery::default();
+        };
+        let data = &account.data;
+        let pubkey = account.pubkey_str();
+        // u64::MAX while the table is active
+        let deactivation_slot = u64::from_le_bytes(data[LOOKUP_TABLE_DEACTIVATION_SLOT_OFFSET..LOOKUP_TABLE_DEACTIVATION_SLOT_OFFSET + 8].try_into().unwrap());
+        let last_extended_slot = u64::from_le_bytes(data[LOOKUP_TABLE_LAST_EXTENDED_SLOT_OFFSET..LOOKUP_TABLE_LAST_EXTENDED_SLOT_OFFSET + 8].try_into().unwrap());
+        // a frozen table has no authority
+        let authority = match data[LOOKUP_TABLE_AUTHORITY_OFFSET] {
+            0 => "NULL".to_string(),
+            _ => format!(
+                "'{}'",
+                bs58::encode(&data[LOOKUP_TABLE_AUTHORITY_OFFSET + 1..LOOKUP_TABLE_AUTHORITY_OFFSET + 1 + PUBKEY_BYTES]).into_string()
+            ),
+        };
+        let table_upsert = format!(
+            "INSERT INTO address_lookup_table AS lookup_table (pubkey, authority, deactivation_slot, last_extended_slot, slot, write_version) \
+            VALUES ('{0}', {1}, {2}, {3}, {4}, {5}) \
+            ON CONFLICT (pubkey) DO UPDATE SET authority=excluded.authority, deactivation_slot=excluded.deactivation_slot, \
+                last_extended_slot=excluded.last_extended_slot, slot=excluded.slot, write_version=excluded.write_version \
+            WHERE (lookup_table.slot, lookup_table.write_version) < (excluded.slot, excluded.write_version)",
+            pubkey,
+            authority,
+            match deactivation_slot {
+                u64::MAX => "NULL".to_string(),
+                slot => slot.to_string(),
+            },
+            last_extended_slot,
+            &account.slot,
+            &account.write_version,
+        );
+        let entries = data[LOOKUP_TABLE_META_SIZE..]
+            .chunks_exact(PUBKEY_BYTES)
+            .enumerate()
+            .map(|(index, address)| format!("({}, '{}')", index, bs58::encode(address).into_string()))
+            .collect::<Vec<String>>();
+        if entries.is_empty() {
+            return format!("{};", table_upsert).into();
+        }
+        // the entries are only written along with the table, not by an update older than the stored one
+        format!(
+            "
+                WITH updated AS ({0} RETURNING pubkey) \
+                INSERT INTO address_lookup_table_entry (table_pubkey, index, address) \
+                SELECT updated.pubkey, entry.index, entry.address FROM updated, (VALUES {1}) AS entry (index, address) \
+                ON CONFLICT (table_pubkey, index) DO UPDATE SET address=excluded.address;
+            ",
+            table_upsert,
+            entries.join(", "),
+        )
+        .into()
+    }
+
+    fn account_delete(&self, account: &DbAccountInfo) -> AccountQuery {
+        format!(
+            "
+                WITH closed AS (DELETE FROM address_lookup_table WHERE pubkey='{0}' AND (slot, write_version) <= ({1}, {2}) RETURNING pubkey) \
+                DELETE FROM address_lookup_table_entry WHERE table_pubkey IN (SELECT pubkey FROM closed);
+            ",
+            account.pubkey_str(),
+            &account.slot,
+            &account.write_version,
+        )
+        .into()
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
+use solana_sdk::pubkey::Pubkey;
+
+use super::account_handler::AccountHandler;
+use super::account_projection::is_identifier;
+use super::account_query::AccountQuery;
+use super::DbAccountInfo;
+use crate::config::AccountProjection;
+use crate::config::GeyserPluginPostgresConfig;
+
+/// Columns of the tables of the `anchor_account` handler written for every account type
+const ANCHOR_ACCOUNT_COLUMNS: [&str; 3] = ["pubkey", "slot", "write_version"];
+
+#[derive(Clone)]
+struct AnchorAccount {
+    owner: Vec<u8>,
+    discriminator: [u8; 8],
+    table_name: String,
+    fields: Vec<AccountProjection>,
+}
+
+/// Writes the `anchor_accounts` of the config to their own table, the fields of an account being read at their
+/// offset in its data as the `account_projections` are
+#[derive(Clone, Default)]
+pub struct GenericAnchorHandler {
+    accounts: Vec<AnchorAccount>,
+}
+
+impl GenericAnchorHandler {
+    pub fn new(config: &GeyserPluginPostgresConfig) -> Result<Self, GeyserPluginError> {
+        let config_error = |msg: String| GeyserPluginError::ConfigFileReadError { msg };
+        let mut handler = Self::default();
+        let mut table_names = HashSet::new();
+        for anchor_account in &config.anchor_accounts {
+            let table_name = &anchor_account.table_name;
+            let owner = Pubkey::from_str(&anchor_account.program_id).map_err(|_| config_error(format!("\"anchor_accounts\" program_id \"{}\" is not a pubkey", anchor_account.program_id)))?;
+            if !is_identifier(table_name) {
+                return Err(config_error(format!("\"anchor_accounts\" table_name \"{}\" is not a valid table name", table_name)));
+            }
+            if !table_names.insert(table_name) {
+                return Err(config_error(format!("\"anchor_accounts\" table_name \"{}\" is used twice", table_name)));
+            }
+            if handler.accounts.iter().any(|a| a.owner == owner.as_ref() && a.discriminator == anchor_account.discriminator) {
+                return Err(config_error(format!("\"anchor_accounts\" discriminator of \"{}\" is used twice for \"{}\"", table_name, owner)));
+            }
+            for (i, field) in anchor_account.fields.iter().enumerate() {
+                if !is_identifier(&field.name) || ANCHOR_ACCOUNT_COLUMNS.contains(&field.name.as_str()) {
+                    return Err(config_error(format!("\"anchor_accounts\" field \"{}\" of \"{}\" is not a valid column name", field.name, table_name)));
+                }
+                if anchor_account.fields[..i].iter().any(|f| f.name == field.name) {
+                    return Err(config_error(format!("\"anchor_accounts\" field \"{}\" is declared twice for \"{}\"", field.name, table_name)));
+                }
+            }
+            handler.accounts.push(AnchorAccount {
+                owner: owner.to_bytes().to_vec(),
+                discriminator: anchor_account.discriminator,
+                table_name: table_name.clone(),
+                fields: anchor_account.fields.clone(),
+            });
+        }
+        Ok(handler)
+    }
+
+    fn anchor_account(&self, account: &DbAccountInfo) -> Option<&AnchorAccount> {
+        self.accounts.iter().find(|a| a.owner == account.owner && account.data.starts_with(&a.discriminator))
+    }
+}
+
+impl AccountHandler for GenericAnchorHandler {
+    fn enabled(&self, config: &GeyserPluginPostgresConfig) -> bool {
+        !config.anchor_accounts.is_empty()
+    }
+
+    fn init(&self, config: &GeyserPluginPostgresConfig) -> String {
+        if !self.enabled(config) {
+            return "".to_string();
+        };
+        let mut init = String::new();
+        for anchor_account in &self.accounts {
+            init.push_str(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (pubkey VARCHAR(44) PRIMARY KEY, slot BIGINT NOT NULL, write_version BIGINT NOT NULL);\n",
+                anchor_account.table_name
+            ));
+            for field in &anchor_account.fields {
+                init.push_str(&format!(
+                    "ALTER TABLE {} ADD COLUMN IF NOT EXISTS {} {};\n",
+                    anchor_account.table_name,
+                    field.name,
+                    field.projection_type.column_type()
+                ));
+            }
+        }
+        init
+    }
+
+    fn account_match(&self, account: &DbAccountInfo) -> bool {
+        self.anchor_account(account).is_some()
+    }
+
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        let Some(anchor_account) = self.anchor_account(account) else {
+            return AccountQuery::default();
+        };
+        let columns = anchor_account.fields.iter().map(|field| format!(", {}", field.name)).collect::<String>();
+        let values = anchor_account
+            .fields
+            .iter()
+            .map(|field| format!(", {}", field.projection_type.value(&account.data, field.offset)))
+            .collect::<String>();
+        let updates = anchor_account.fields.iter().map(|field| format!(", {0}=excluded.{0}", field.name)).collect::<String>();
+        format!(
+            "
+                INSERT INTO {0} AS entry (pubkey, slot, write_version{4}) \
+                VALUES ('{1}', {2}, {3}{5}) \
+                ON CONFLICT (pubkey) DO UPDATE SET slot=excluded.slot, write_version=excluded.write_version{6} \
+                WHERE (entry.slot, entry.write_version) < (excluded.slot, excluded.write_version);
+            ",
+            anchor_account.table_name,
+            account.pubkey_str(),
+            &account.slot,
+            &account.write_version,
+            columns,
+            values,
+            updates,
+        )
+        .into()
+    }
+
+    fn account_delete(&self, account: &DbAccountInfo) -> AccountQuery {
+        // a closed account has usually lost its discriminator, it is removed from the tables of all types
+        self.accounts
+            .iter()
+            .map(|anchor_account| {
+                format!(
+                    "DELETE FROM {} WHERE pubkey='{}' AND (slot, write_version) <= ({}, {});\n",
+                    anchor_account.table_name,
+                    account.pubkey_str(),
+                    &account.slot,
+                    &account.write_version,
+                )
+            })
+            .collect::<String>()
+            .into()
+    }
+}
@@ -0,0 +1,138 @@
+use borsh::BorshDeserialize;
+use log::error;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+use super::account_handler::AccountHandler;
+use super::account_query::AccountQuery;
+use super::DbAccountInfo;
+
+pub static CANDY_MACHINE_CORE_PROGRAM_ID: Pubkey = pubkey!("CndyV3LdqHUfDLmE5naZjVN8rBZz4tqhSefbAnjHMFr");
+const CANDY_MACHINE_DISCRIMINATOR: [u8; 8] = [51, 173, 177, 113, 25, 241, 109, 189];
+
+/// Leading fields of the CandyMachine account, after its discriminator. The config lines, when any, follow the data.
+#[derive(BorshDeserialize)]
+struct CandyMachine {
+    /// The version and token standard, or the features flags of the first releases
+    _features: [u8; 8],
+    authority: Pubkey,
+    mint_authority: Pubkey,
+    collection_mint: Pubkey,
+    items_redeemed: u64,
+    data: CandyMachineData,
+}
+
+#[derive(BorshDeserialize)]
+struct CandyMachineData {
+    items_available: u64,
+    symbol: String,
+    _seller_fee_basis_points: u16,
+    _max_supply: u64,
+    _is_mutable: bool,
+    _creators: Vec<CandyMachineCreator>,
+    _config_line_settings: Option<ConfigLineSettings>,
+    hidden_settings: Option<HiddenSettings>,
+}
+
+#[derive(BorshDeserialize)]
+struct CandyMachineCreator {
+    _address: Pubkey,
+    _verified: bool,
+    _percentage_share: u8,
+}
+
+#[derive(BorshDeserialize)]
+struct ConfigLineSettings {
+    _prefix_name: String,
+    _name_length: u32,
+    _prefix_uri: String,
+    _uri_length: u32,
+    _is_sequential: bool,
+}
+
+#[derive(BorshDeserialize)]
+struct HiddenSettings {
+    _name: String,
+    _uri: String,
+    _hash: [u8; 32],
+}
+
+/// Tracks the mint progress of the candy machines of Candy Machine Core (v3)
+#[derive(Clone, Copy)]
+pub struct CandyMachineHandler {}
+
+impl AccountHandler for CandyMachineHandler {
+    fn init(&self, config: &crate::config::GeyserPluginPostgresConfig) -> String {
+        if !self.enabled(config) {
+            return "".to_string();
+        };
+        "
+            CREATE TABLE IF NOT EXISTS candy_machine (
+                pubkey VARCHAR(44) PRIMARY KEY,
+                authority VARCHAR(44) NOT NULL,
+                mint_authority VARCHAR(44) NOT NULL,
+                collection_mint VARCHAR(44) NOT NULL,
+                symbol TEXT NOT NULL,
+                items_redeemed BIGINT NOT NULL,
+                items_available BIGINT NOT NULL,
+                hidden_settings BOOL NOT NULL,
+                slot BIGINT NOT NULL,
+                write_version BIGINT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS candy_machine_collection_mint ON candy_machine (collection_mint);
+        "
+        .to_string()
+    }
+
+    fn account_match(&self, account: &DbAccountInfo) -> bool {
+        account.owner == CANDY_MACHINE_CORE_PROGRAM_ID.as_ref() && account.data.starts_with(&CANDY_MACHINE_DISCRIMINATOR)
+    }
+
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        if !self.account_match(account) {
+            return AccountQuery::default();
+        };
+        // the data is followed by the config lines of the machines without hidden settings, left unread
+        let candy_machine = match CandyMachine::deserialize(&mut &account.data[CANDY_MACHINE_DISCRIMINATOR.len()..]) {
+            Ok(candy_machine) => candy_machine,
+            Err(e) => {
+                error!("[account_update] Failed to deserialize candy machine pubkey=[{:?}] error=[{:?}]", account.pubkey, e);
+                return AccountQuery::default();
+            }
+        };
+        // the symbol is set by the machine authority, it is bound rather than rendered in the statement
+        AccountQuery::statement(
+            "
+                INSERT INTO candy_machine AS candy_machine (pubkey, authority, mint_authority, collection_mint, symbol, items_redeemed, items_available, hidden_settings, slot, write_version) \
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) \
+                ON CONFLICT (pubkey) DO UPDATE SET authority=excluded.authority, mint_authority=excluded.mint_authority, \
+                    collection_mint=excluded.collection_mint, symbol=excluded.symbol, items_redeemed=excluded.items_redeemed, \
+                    items_available=excluded.items_available, hidden_settings=excluded.hidden_settings, slot=excluded.slot, write_version=excluded.write_version \
+                WHERE (candy_machine.slot, candy_machine.write_version) < (excluded.slot, excluded.write_version);
+            "
+            .to_string(),
+            vec![
+                Box::new(account.pubkey_str().to_string()),
+                Box::new(candy_machine.authority.to_string()),
+                Box::new(candy_machine.mint_authority.to_string()),
+                Box::new(candy_machine.collection_mint.to_string()),
+                Box::new(candy_machine.data.symbol.replace('\0', "")),
+                Box::new(candy_machine.items_redeemed as i64),
+                Box::new(candy_machine.data.items_available as i64),
+                Box::new(candy_machine.data.hidden_settings.is_some()),
+                Box::new(account.slot),
+                Box::new(account.write_version),
+            ],
+        )
+    }
+
+    fn account_delete(&self, account: &DbAccountInfo) -> AccountQuery {
+        format!(
+            "DELETE FROM candy_machine WHERE pubkey='{}' AND (slot, write_version) <= ({}, {});",
+            account.pubkey_str(),
+            &account.slot,
+            &account.write_version,
+        )
+        .into()
+    }
+}
@@ -6,10 +6,13 @@ use solana_sdk::pubkey::Pubkey;
 use solana_sdk::pubkey::PUBKEY_BYTES;
 
 use super::account_handler::AccountHandler;
+use super::account_query::AccountQuery;
+use super::mint_account_handler::TOKEN2022_POINTER_METADATA_VIEW;
 use super::DbAccountInfo;
 
 pub static METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 const TOKEN_METADATA_MINT_OFFSET: usize = 33;
+const TOKEN_METADATA_NAME_OFFSET: usize = 65;
 const TOKEN_METADATA_CREATORS_OFFSET: usize = 322;
 const TOKEN_METADATA_DISCRIMINATOR: u8 = 4;
 
@@ -23,8 +26,58 @@ pub struct Creator {
     pub share: u8,
 }
 
+/// Decodes a text of a metadata account. They are padded with null bytes on chain and set by the update authority,
+/// so they are stripped of null bytes and invalid UTF-8 sequences are replaced, Postgres rejecting both in text
+pub fn sanitize_text(bytes: &[u8], strip_control_characters: bool) -> String {
+    String::from_utf8_lossy(bytes).chars().filter(|c| *c != '\0' && !(strip_control_characters && c.is_control())).collect()
+}
+
+/// Writes the name, symbol and uri of the metadata and its creators
 #[derive(Clone, Copy)]
-pub struct MetadataCreatorsAccountHandler {}
+pub struct MetadataCreatorsAccountHandler {
+    pub strip_control_characters: bool,
+}
+
+impl MetadataCreatorsAccountHandler {
+    /// Reads the borsh string at the offset, returning it with the offset following it
+    fn metadata_text(&self, data: &[u8], offset: usize) -> Option<(String, usize)> {
+        let len = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().unwrap()) as usize;
+        let bytes = data.get(offset + 4..offset + 4 + len)?;
+        Some((sanitize_text(bytes, self.strip_control_characters), offset + 4 + len))
+    }
+
+    fn metadata_update(&self, account: &DbAccountInfo, mint: &Pubkey) -> AccountQuery {
+        let texts = self.metadata_text(&account.data, TOKEN_METADATA_NAME_OFFSET).and_then(|(name, offset)| {
+            let (symbol, offset) = self.metadata_text(&account.data, offset)?;
+            let (uri, _) = self.metadata_text(&account.data, offset)?;
+            Some((name, symbol, uri))
+        });
+        let Some((name, symbol, uri)) = texts else {
+            error!("[account_update] Failed to read metadata name, symbol and uri pubkey=[{:?}]", account.pubkey);
+            return AccountQuery::default();
+        };
+        // the texts are set by the update authority, they are bound rather than rendered in the statement
+        AccountQuery::statement(
+            "
+                INSERT INTO token_metadata AS metadata (mint, name, symbol, uri, slot, write_version, address) \
+                VALUES ($1, $2, $3, $4, $5, $6, $7) \
+                ON CONFLICT (mint) DO UPDATE SET name=excluded.name, symbol=excluded.symbol, uri=excluded.uri, \
+                    address=excluded.address, slot=excluded.slot, write_version=excluded.write_version \
+                WHERE (metadata.slot, metadata.write_version) < (excluded.slot, excluded.write_version);
+            "
+            .to_string(),
+            vec![
+                Box::new(bs58::encode(mint).into_string()),
+                Box::new(name),
+                Box::new(symbol),
+                Box::new(uri),
+                Box::new(account.slot),
+                Box::new(account.write_version),
+                Box::new(account.pubkey_str().to_string()),
+            ],
+        )
+    }
+}
 
 impl AccountHandler for MetadataCreatorsAccountHandler {
     fn init(&self, config: &crate::config::GeyserPluginPostgresConfig) -> String {
@@ -41,33 +94,45 @@ impl AccountHandler for MetadataCreatorsAccountHandler {
                 slot BIGINT NOT NULL,
                 PRIMARY KEY(creator, mint)
             );
+            CREATE TABLE IF NOT EXISTS token_metadata (
+                mint VARCHAR(44) PRIMARY KEY,
+                name TEXT NOT NULL,
+                symbol TEXT NOT NULL,
+                uri TEXT NOT NULL,
+                slot BIGINT NOT NULL,
+                write_version BIGINT NOT NULL
+            );
+            ALTER TABLE token_metadata ADD COLUMN IF NOT EXISTS address VARCHAR(44);
+            CREATE INDEX IF NOT EXISTS token_metadata_address ON token_metadata (address);
         "
-        .to_string();
+        .to_string()
+            + TOKEN2022_POINTER_METADATA_VIEW;
     }
 
     fn account_match(&self, account: &DbAccountInfo) -> bool {
         account.owner == METADATA_PROGRAM_ID.as_ref() && TOKEN_METADATA_DISCRIMINATOR == *account.data.get(0).unwrap_or(&0)
     }
 
-    fn account_update(&self, account: &DbAccountInfo) -> String {
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
         if !self.account_match(account) {
-            return "".to_string();
+            return AccountQuery::default();
         };
+        let mint: &Pubkey = bytemuck::from_bytes(&account.data[TOKEN_METADATA_MINT_OFFSET..TOKEN_METADATA_MINT_OFFSET + PUBKEY_BYTES]);
+        let mut query = self.metadata_update(account, mint);
 
         let buf = &mut &account.data[TOKEN_METADATA_CREATORS_OFFSET..];
         if buf[0] == 0 {
-            return "".to_string();
+            return query;
         }
         let creators: Vec<Creator> = match BorshDeserialize::deserialize(buf) {
             Ok(c) => c,
             Err(e) => {
                 error!("[account_update] Failed to deserialize creators pubkey=[{:?}] error=[{:?}]", account.pubkey, e);
-                return "".to_string();
+                return query;
             }
         };
-        let mint: &Pubkey = bytemuck::from_bytes(&account.data[TOKEN_METADATA_MINT_OFFSET..TOKEN_METADATA_MINT_OFFSET + PUBKEY_BYTES]);
         let slot = account.slot;
-        return creators
+        let creators = creators
             .iter()
             .enumerate()
             .map(|(index, c)| {
@@ -89,5 +154,7 @@ impl AccountHandler for MetadataCreatorsAccountHandler {
             })
             .collect::<Vec<String>>()
             .join("");
+        query.append(creators.into());
+        query
     }
 }
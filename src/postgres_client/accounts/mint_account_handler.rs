@@ -0,0 +1,261 @@
+use solana_sdk::pubkey::PUBKEY_BYTES;
+
+use super::account_handler::AccountHandler;
+use super::account_query::AccountQuery;
+use super::metadata_creators_account_handler::sanitize_text;
+use super::token_account_handler::TokenAccountHandler;
+use super::token_extensions;
+use super::token_extensions::EXTENSION_METADATA_POINTER;
+use super::token_extensions::EXTENSION_NON_TRANSFERABLE;
+use super::token_extensions::EXTENSION_PERMANENT_DELEGATE;
+use super::token_extensions::EXTENSION_TOKEN_METADATA;
+use super::token_extensions::EXTENSION_TRANSFER_FEE_CONFIG;
+use super::DbAccountInfo;
+
+/*
+    spl_token::state::Mint {
+        mint_authority: COption<Pubkey>,
+        supply: u64,
+        decimals: u8,
+        is_initialized: bool,
+        freeze_authority: COption<Pubkey>,
+    }
+*/
+const SPL_TOKEN_MINT_AUTHORITY_OFFSET: usize = 0;
+const SPL_TOKEN_MINT_SUPPLY_OFFSET: usize = 36;
+const SPL_TOKEN_MINT_DECIMALS_OFFSET: usize = 44;
+const SPL_TOKEN_MINT_IS_INITIALIZED_OFFSET: usize = 45;
+const SPL_TOKEN_MINT_FREEZE_AUTHORITY_OFFSET: usize = 46;
+/// Length of the tag of a `COption`, preceding its value
+const COPTION_TAG_LENGTH: usize = 4;
+
+/// Decodes the `COption<Pubkey>` at `offset` as a SQL literal, NULL when None
+pub fn coption_pubkey(data: &[u8], offset: usize) -> String {
+    let tag = u32::from_le_bytes(data[offset..offset + COPTION_TAG_LENGTH].try_into().unwrap());
+    match tag {
+        0 => "NULL".to_string(),
+        _ => format!("'{}'", bs58::encode(&data[offset + COPTION_TAG_LENGTH..offset + COPTION_TAG_LENGTH + PUBKEY_BYTES]).into_string()),
+    }
+}
+
+/// Joins the external metadata pointers to the Metaplex metadata accounts they point to. It is created by the init of
+/// both the `token_mint` and `token_metadata_creators` handlers, once the tables of the two exist in the current schema
+pub const TOKEN2022_POINTER_METADATA_VIEW: &str = "
+    DO $$
+    BEGIN
+        IF to_regclass('token2022_metadata_pointer') IS NOT NULL AND EXISTS (
+            SELECT 1 FROM information_schema.columns
+            WHERE table_schema = current_schema() AND table_name = 'token_metadata' AND column_name = 'address'
+        ) THEN
+            CREATE OR REPLACE VIEW token2022_pointer_metadata AS
+            SELECT pointer.mint, pointer.metadata_address, metadata.mint AS metadata_mint, metadata.name, metadata.symbol, metadata.uri
+            FROM token2022_metadata_pointer pointer
+            JOIN token_metadata metadata ON metadata.address = pointer.metadata_address;
+        END IF;
+    END $$;
+";
+
+pub struct MintAccountHandler {
+    /// Whether the control characters of the embedded metadata are stripped
+    pub strip_control_characters: bool,
+    /// Whether the decreases of the supply are recorded in `token_burn`
+    pub index_burns: bool,
+    /// Appended to the condition of the update of the upsert, from `upsert_guards`
+    pub upsert_guard: String,
+}
+
+impl MintAccountHandler {
+    /// Records a burn when the supply is lower than the one stored at an earlier write of the mint
+    fn burn_update(&self, account: &DbAccountInfo, supply: u64) -> String {
+        if !self.index_burns {
+            return "".to_string();
+        }
+        format!(
+            "
+                INSERT INTO token_burn (mint, slot, write_version, previous_supply, supply) \
+                SELECT pubkey, {1}, {2}, supply, {3} FROM spl_mint \
+                WHERE pubkey='{0}' AND supply > {3} AND (slot, COALESCE(write_version, -1)) < ({1}, {2}) \
+                ON CONFLICT (mint, slot, write_version) DO NOTHING;
+            ",
+            account.pubkey_str(),
+            &account.slot,
+            &account.write_version,
+            supply,
+        )
+    }
+
+    /// Records the metadata pointer of a Token-2022 mint to another account, removing the one of a mint embedding its
+    /// metadata or no longer pointing to any
+    fn metadata_pointer_update(&self, account: &DbAccountInfo, metadata_address: Option<&[u8]>) -> String {
+        match metadata_address.filter(|address| *address != account.pubkey.as_slice()) {
+            Some(address) => format!(
+                "
+                    INSERT INTO token2022_metadata_pointer AS pointer (mint, metadata_address, slot, write_version) \
+                    VALUES ('{0}', '{3}', {1}, {2}) \
+                    ON CONFLICT (mint) DO UPDATE SET metadata_address=excluded.metadata_address, slot=excluded.slot, \
+                        write_version=excluded.write_version \
+                    WHERE (pointer.slot, pointer.write_version) <= (excluded.slot, excluded.write_version);
+                ",
+                account.pubkey_str(),
+                &account.slot,
+                &account.write_version,
+                bs58::encode(address).into_string(),
+            ),
+            None => format!(
+                "
+                    DELETE FROM token2022_metadata_pointer WHERE mint='{0}' AND (slot, write_version) <= ({1}, {2});
+                ",
+                account.pubkey_str(),
+                &account.slot,
+                &account.write_version,
+            ),
+        }
+    }
+
+    /// Writes the metadata embedded in a Token-2022 mint, whose metadata pointer is the mint itself
+    fn token_metadata_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        let Some(metadata) = token_extensions::find_extension(&account.data, EXTENSION_TOKEN_METADATA).and_then(token_extensions::token_metadata) else {
+            return AccountQuery::default();
+        };
+        let text = |bytes: &[u8]| sanitize_text(bytes, self.strip_control_characters);
+        // the pairs are kept in order, as [key, value] arrays
+        let additional_metadata = serde_json::Value::Array(metadata.additional_metadata.iter().map(|(key, value)| serde_json::json!([text(key), text(value)])).collect());
+        AccountQuery::statement(
+            "
+                INSERT INTO token22_metadata AS metadata (mint, update_authority, name, symbol, uri, additional_metadata, slot, write_version) \
+                VALUES ($1, $2, $3, $4, $5, $6::TEXT::JSONB, $7, $8) \
+                ON CONFLICT (mint) DO UPDATE SET update_authority=excluded.update_authority, name=excluded.name, symbol=excluded.symbol, \
+                    uri=excluded.uri, additional_metadata=excluded.additional_metadata, slot=excluded.slot, write_version=excluded.write_version \
+                WHERE (metadata.slot, metadata.write_version) < (excluded.slot, excluded.write_version);
+            "
+            .to_string(),
+            vec![
+                Box::new(account.pubkey_str().to_string()),
+                Box::new(metadata.update_authority.map(|authority| bs58::encode(authority).into_string())),
+                Box::new(text(metadata.name)),
+                Box::new(text(metadata.symbol)),
+                Box::new(text(metadata.uri)),
+                Box::new(additional_metadata.to_string()),
+                Box::new(account.slot),
+                Box::new(account.write_version),
+            ],
+        )
+    }
+}
+
+impl AccountHandler for MintAccountHandler {
+    fn init(&self, config: &crate::config::GeyserPluginPostgresConfig) -> String {
+        if !self.enabled(config) {
+            return "".to_string();
+        };
+        "
+            CREATE TABLE IF NOT EXISTS spl_mint (
+                pubkey VARCHAR(44) PRIMARY KEY,
+                mint_authority VARCHAR(44),
+                supply NUMERIC(20) NOT NULL,
+                decimals SMALLINT NOT NULL,
+                is_initialized BOOL NOT NULL,
+                freeze_authority VARCHAR(44),
+                slot BIGINT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS spl_mint_mint_authority ON spl_mint (mint_authority);
+            ALTER TABLE spl_mint ADD COLUMN IF NOT EXISTS transfer_fee_basis_points INTEGER;
+            ALTER TABLE spl_mint ADD COLUMN IF NOT EXISTS transfer_fee_maximum NUMERIC(20);
+            ALTER TABLE spl_mint ADD COLUMN IF NOT EXISTS permanent_delegate VARCHAR(44);
+            ALTER TABLE spl_mint ADD COLUMN IF NOT EXISTS non_transferable BOOL NOT NULL DEFAULT FALSE;
+            ALTER TABLE spl_mint ADD COLUMN IF NOT EXISTS write_version BIGINT;
+            ALTER TABLE spl_mint ADD COLUMN IF NOT EXISTS metadata_address VARCHAR(44);
+            CREATE TABLE IF NOT EXISTS token22_metadata (
+                mint VARCHAR(44) PRIMARY KEY,
+                update_authority VARCHAR(44),
+                name TEXT NOT NULL,
+                symbol TEXT NOT NULL,
+                uri TEXT NOT NULL,
+                additional_metadata JSONB NOT NULL,
+                slot BIGINT NOT NULL,
+                write_version BIGINT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS token_burn (
+                mint VARCHAR(44) NOT NULL,
+                slot BIGINT NOT NULL,
+                write_version BIGINT NOT NULL,
+                previous_supply NUMERIC(20) NOT NULL,
+                supply NUMERIC(20) NOT NULL,
+                PRIMARY KEY (mint, slot, write_version)
+            );
+            CREATE TABLE IF NOT EXISTS token2022_metadata_pointer (
+                mint VARCHAR(44) PRIMARY KEY,
+                metadata_address VARCHAR(44) NOT NULL,
+                slot BIGINT NOT NULL,
+                write_version BIGINT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS token2022_metadata_pointer_metadata_address ON token2022_metadata_pointer (metadata_address);
+        "
+        .to_string()
+            + TOKEN2022_POINTER_METADATA_VIEW
+    }
+
+    fn account_match(&self, account: &DbAccountInfo) -> bool {
+        TokenAccountHandler::mint_match(account)
+    }
+
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        if !self.account_match(account) {
+            return AccountQuery::default();
+        };
+        let data = &account.data;
+        // Token-2022 extensions restricting transfers, unknown extensions are skipped
+        let transfer_fee = token_extensions::find_extension(data, EXTENSION_TRANSFER_FEE_CONFIG).and_then(token_extensions::transfer_fee);
+        let permanent_delegate = token_extensions::find_extension(data, EXTENSION_PERMANENT_DELEGATE).and_then(token_extensions::optional_pubkey);
+        let non_transferable = token_extensions::find_extension(data, EXTENSION_NON_TRANSFERABLE).is_some();
+        let metadata_address = token_extensions::find_extension(data, EXTENSION_METADATA_POINTER).and_then(token_extensions::metadata_address);
+        let supply = u64::from_le_bytes(data[SPL_TOKEN_MINT_SUPPLY_OFFSET..SPL_TOKEN_MINT_SUPPLY_OFFSET + 8].try_into().unwrap());
+        // the burn is compared to the supply stored before the mint is updated
+        let mut query = AccountQuery::from(self.burn_update(account, supply));
+        query.append(AccountQuery::from(format!(
+            "
+                INSERT INTO spl_mint AS spl_mint_entry (pubkey, mint_authority, supply, decimals, is_initialized, freeze_authority, slot, \
+                    transfer_fee_basis_points, transfer_fee_maximum, permanent_delegate, non_transferable, write_version, metadata_address) \
+                VALUES ('{0}', {1}, {2}, {3}, {4}, {5}, {6}, {7}, {8}, {9}, {10}, {11}, {12}) \
+                ON CONFLICT (pubkey) \
+                DO UPDATE SET mint_authority=excluded.mint_authority, supply=excluded.supply, decimals=excluded.decimals, \
+                    is_initialized=excluded.is_initialized, freeze_authority=excluded.freeze_authority, slot=excluded.slot, \
+                    transfer_fee_basis_points=excluded.transfer_fee_basis_points, transfer_fee_maximum=excluded.transfer_fee_maximum, \
+                    permanent_delegate=excluded.permanent_delegate, non_transferable=excluded.non_transferable, write_version=excluded.write_version, \
+                    metadata_address=excluded.metadata_address \
+                WHERE (spl_mint_entry.slot, COALESCE(spl_mint_entry.write_version, -1)) <= (excluded.slot, excluded.write_version){13};
+            ",
+            account.pubkey_str(),
+            coption_pubkey(data, SPL_TOKEN_MINT_AUTHORITY_OFFSET),
+            supply,
+            data[SPL_TOKEN_MINT_DECIMALS_OFFSET],
+            data[SPL_TOKEN_MINT_IS_INITIALIZED_OFFSET] != 0,
+            coption_pubkey(data, SPL_TOKEN_MINT_FREEZE_AUTHORITY_OFFSET),
+            &account.slot,
+            transfer_fee.map_or("NULL".to_string(), |(basis_points, _)| basis_points.to_string()),
+            transfer_fee.map_or("NULL".to_string(), |(_, maximum_fee)| maximum_fee.to_string()),
+            permanent_delegate.map_or("NULL".to_string(), |delegate| format!("'{}'", bs58::encode(delegate).into_string())),
+            non_transferable,
+            &account.write_version,
+            metadata_address.map_or("NULL".to_string(), |address| format!("'{}'", bs58::encode(address).into_string())),
+            self.upsert_guard,
+        )));
+        query.append(AccountQuery::from(self.metadata_pointer_update(account, metadata_address)));
+        query.append(self.token_metadata_update(account));
+        query
+    }
+
+    fn account_delete(&self, account: &DbAccountInfo) -> AccountQuery {
+        format!(
+            "
+                DELETE FROM spl_mint WHERE pubkey='{0}' AND (slot, COALESCE(write_version, -1)) <= ({1}, {2});
+                DELETE FROM token22_metadata WHERE mint='{0}' AND (slot, write_version) <= ({1}, {2});
+                DELETE FROM token2022_metadata_pointer WHERE mint='{0}' AND (slot, write_version) <= ({1}, {2});
+            ",
+            account.pubkey_str(),
+            &account.slot,
+            &account.write_version,
+        )
+        .into()
+    }
+}
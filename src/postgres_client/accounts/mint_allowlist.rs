@@ -0,0 +1,50 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::locks;
+use log::*;
+use postgres::Client;
+
+/// Mints the `token_account` handler is restricted to, loaded from the `spl_token_mint_allowlist` table
+/// and reloaded every `refresh_interval` so rows added to the table get indexed without a restart
+#[derive(Clone)]
+pub struct MintAllowlist {
+    mints: Arc<RwLock<HashSet<String>>>,
+    refresh_interval: Duration,
+    last_refresh: Arc<RwLock<Option<Instant>>>,
+}
+
+impl MintAllowlist {
+    pub fn new(refresh_interval_secs: u64) -> Self {
+        Self {
+            mints: Arc::default(),
+            refresh_interval: Duration::from_secs(refresh_interval_secs),
+            last_refresh: Arc::default(),
+        }
+    }
+
+    pub fn contains(&self, mint: &str) -> bool {
+        locks::read(&self.mints, "mint_allowlist").contains(mint)
+    }
+
+    /// Whether the mints were never loaded or the refresh interval elapsed, checked before a connection is taken
+    pub fn is_refresh_due(&self) -> bool {
+        locks::read(&self.last_refresh, "mint_allowlist").is_none_or(|last_refresh| last_refresh.elapsed() >= self.refresh_interval)
+    }
+
+    /// Reloads the mints
+    pub fn refresh(&self, client: &mut Client) -> Result<(), postgres::Error> {
+        let mints = client
+            .query("SELECT mint FROM spl_token_mint_allowlist", &[])?
+            .iter()
+            .map(|row| row.get(0))
+            .collect::<HashSet<String>>();
+        debug!("[mint_allowlist] refreshed mints={}", mints.len());
+        *locks::write(&self.mints, "mint_allowlist") = mints;
+        *locks::write(&self.last_refresh, "mint_allowlist") = Some(Instant::now());
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use crate::postgres_client::cache::BoundedCache;
+
+/// Decimals of the mints seen by the `token_account` handler, shared by the workers so a token account
+/// can be told to be an NFT whichever worker wrote its mint. The least recently used mints are evicted
+/// beyond the `mint_decimals` capacity of the `caches` config
+#[derive(Clone)]
+pub struct MintDecimals {
+    decimals: Arc<BoundedCache<Vec<u8>, u8>>,
+}
+
+impl MintDecimals {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            decimals: Arc::new(BoundedCache::new("mint_decimals", capacity)),
+        }
+    }
+
+    pub fn get(&self, mint: &[u8]) -> Option<u8> {
+        self.decimals.get(&mint.to_vec())
+    }
+
+    pub fn insert(&self, mint: &[u8], decimals: u8) {
+        self.decimals.insert(mint.to_vec(), decimals);
+    }
+}
@@ -1,6 +1,20 @@
 pub mod account_handler;
+pub mod account_projection;
+pub mod account_query;
+pub mod address_lookup_table_handler;
+pub mod anchor_account_handler;
+pub mod candy_machine_handler;
 pub mod metadata_creators_account_handler;
+pub mod mint_account_handler;
+pub mod mint_allowlist;
+pub mod mint_decimals;
+pub mod owner_history_handler;
+pub mod program_census_handler;
+pub mod raw_account_handler;
+pub mod rent_epoch_history_handler;
+pub mod spl_governance_account_handler;
 pub mod token_account_handler;
+pub mod token_extensions;
 pub mod token_manager_handler;
 pub mod unknown_account_handler;
 
@@ -0,0 +1,70 @@
+use super::account_handler::AccountHandler;
+use super::account_query::AccountQuery;
+use super::DbAccountInfo;
+
+/*
+    The owner of an account is read and replaced in a single statement: the history row is only inserted when the
+    upsert applied, from the owner stored before it. Workers writing updates of the same account concurrently may
+    both read the owner before either commits, in which case the transition notified twice is deduplicated by the
+    unique index and a transition between the two updates may be recorded from the older owner
+*/
+#[derive(Clone, Copy)]
+pub struct OwnerHistoryHandler {}
+
+impl AccountHandler for OwnerHistoryHandler {
+    fn init(&self, config: &crate::config::GeyserPluginPostgresConfig) -> String {
+        if !self.enabled(config) {
+            return "".to_string();
+        };
+        "
+            CREATE TABLE IF NOT EXISTS account_current_owner (
+                pubkey VARCHAR(44) PRIMARY KEY,
+                owner VARCHAR(44) NOT NULL,
+                slot BIGINT NOT NULL,
+                write_version BIGINT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS account_owner_history (
+                pubkey VARCHAR(44) NOT NULL,
+                old_owner VARCHAR(44) NOT NULL,
+                new_owner VARCHAR(44) NOT NULL,
+                slot BIGINT NOT NULL
+            );
+            CREATE UNIQUE INDEX IF NOT EXISTS account_owner_history_transition ON account_owner_history (pubkey, slot, new_owner);
+        "
+        .to_string()
+    }
+
+    fn account_match(&self, _account: &DbAccountInfo) -> bool {
+        true
+    }
+
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        format!(
+            "
+                WITH previous AS (SELECT owner FROM account_current_owner WHERE pubkey='{0}'), \
+                updated AS (INSERT INTO account_current_owner AS entry (pubkey, owner, slot, write_version) VALUES ('{0}', '{1}', {2}, {3}) \
+                    ON CONFLICT (pubkey) DO UPDATE SET owner=excluded.owner, slot=excluded.slot, write_version=excluded.write_version \
+                    WHERE (entry.slot, entry.write_version) < (excluded.slot, excluded.write_version) \
+                    RETURNING owner) \
+                INSERT INTO account_owner_history (pubkey, old_owner, new_owner, slot) \
+                SELECT '{0}', previous.owner, updated.owner, {2} FROM previous, updated WHERE previous.owner != updated.owner \
+                ON CONFLICT (pubkey, slot, new_owner) DO NOTHING;
+            ",
+            account.pubkey_str(),
+            account.owner_str(),
+            &account.slot,
+            &account.write_version,
+        )
+        .into()
+    }
+
+    fn account_delete(&self, account: &DbAccountInfo) -> AccountQuery {
+        format!(
+            "DELETE FROM account_current_owner WHERE pubkey='{}' AND (slot, write_version) <= ({}, {});",
+            account.pubkey_str(),
+            &account.slot,
+            &account.write_version,
+        )
+        .into()
+    }
+}
@@ -0,0 +1,68 @@
+use super::account_handler::AccountHandler;
+use super::account_query::AccountQuery;
+use super::DbAccountInfo;
+use crate::accounts_selector::is_account_sampled;
+use crate::config::GeyserPluginPostgresConfig;
+
+/// Catalogs the accounts no handler is selected for with their owner and data length only,
+/// the `program_census` view counting them by owning program
+#[derive(Clone, Copy)]
+pub struct ProgramCensusHandler {
+    pub sampling_rate: Option<u64>,
+}
+
+impl AccountHandler for ProgramCensusHandler {
+    fn enabled(&self, config: &GeyserPluginPostgresConfig) -> bool {
+        config.program_census
+    }
+
+    fn init(&self, config: &GeyserPluginPostgresConfig) -> String {
+        if !self.enabled(config) {
+            return "".to_string();
+        };
+        "
+            CREATE TABLE IF NOT EXISTS program_account_census (
+                pubkey VARCHAR(44) PRIMARY KEY,
+                owner VARCHAR(44) NOT NULL,
+                data_len BIGINT NOT NULL,
+                slot BIGINT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS program_account_census_owner ON program_account_census (owner);
+            CREATE OR REPLACE VIEW program_census AS
+                SELECT owner, COUNT(*) AS account_count, SUM(data_len) AS total_data_len, MAX(slot) AS slot
+                FROM program_account_census GROUP BY owner;
+        "
+        .to_string()
+    }
+
+    fn account_match(&self, account: &DbAccountInfo) -> bool {
+        self.sampling_rate.is_none_or(|sampling_rate| is_account_sampled(&account.pubkey, sampling_rate))
+    }
+
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        if !self.account_match(account) {
+            return AccountQuery::default();
+        };
+        format!(
+            "
+                INSERT INTO program_account_census AS census (pubkey, owner, data_len, slot) \
+                VALUES ('{0}', '{1}', {2}, {3}) \
+                ON CONFLICT (pubkey) DO UPDATE SET owner=excluded.owner, data_len=excluded.data_len, slot=excluded.slot \
+                WHERE census.slot <= excluded.slot;
+            ",
+            account.pubkey_str(),
+            account.owner_str(),
+            account.data.len(),
+            &account.slot,
+        )
+        .into()
+    }
+
+    /// Closed accounts leave the census
+    fn account_delete(&self, account: &DbAccountInfo) -> AccountQuery {
+        if !self.account_match(account) {
+            return AccountQuery::default();
+        };
+        format!("DELETE FROM program_account_census WHERE pubkey='{0}' AND slot <= {1};", account.pubkey_str(), &account.slot).into()
+    }
+}
@@ -0,0 +1,65 @@
+use super::account_handler::AccountHandler;
+use super::account_query::AccountQuery;
+use super::DbAccountInfo;
+
+/// Stores the data of the selected accounts verbatim in base64, so they can be parsed offline by handlers
+/// written after they were notified
+#[derive(Clone)]
+pub struct RawAccountHandler {
+    /// Appended to the condition of the update of the upsert, from `upsert_guards`
+    pub upsert_guard: String,
+}
+
+impl AccountHandler for RawAccountHandler {
+    fn init(&self, config: &crate::config::GeyserPluginPostgresConfig) -> String {
+        if !self.enabled(config) {
+            return "".to_string();
+        };
+        "
+            CREATE TABLE IF NOT EXISTS raw_account (
+                pubkey VARCHAR(44) PRIMARY KEY,
+                owner VARCHAR(44) NOT NULL,
+                slot BIGINT NOT NULL,
+                write_version BIGINT NOT NULL,
+                lamports BIGINT NOT NULL,
+                data_base64 TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS raw_account_owner ON raw_account (owner);
+        "
+        .to_string()
+    }
+
+    fn account_match(&self, _account: &DbAccountInfo) -> bool {
+        true
+    }
+
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        format!(
+            "
+                INSERT INTO raw_account AS raw (pubkey, owner, slot, write_version, lamports, data_base64) \
+                VALUES ('{0}', '{1}', {2}, {3}, {4}, '{5}') \
+                ON CONFLICT (pubkey) DO UPDATE SET owner=excluded.owner, slot=excluded.slot, write_version=excluded.write_version, \
+                    lamports=excluded.lamports, data_base64=excluded.data_base64 \
+                WHERE (raw.slot, raw.write_version) < (excluded.slot, excluded.write_version){6};
+            ",
+            account.pubkey_str(),
+            account.owner_str(),
+            &account.slot,
+            &account.write_version,
+            &account.lamports,
+            base64::encode(&account.data),
+            self.upsert_guard,
+        )
+        .into()
+    }
+
+    fn account_delete(&self, account: &DbAccountInfo) -> AccountQuery {
+        format!(
+            "DELETE FROM raw_account WHERE pubkey='{}' AND (slot, write_version) <= ({}, {});",
+            account.pubkey_str(),
+            &account.slot,
+            &account.write_version,
+        )
+        .into()
+    }
+}
@@ -0,0 +1,69 @@
+use super::account_handler::AccountHandler;
+use super::account_query::AccountQuery;
+use super::DbAccountInfo;
+
+/*
+    As the owner history, the rent epoch of an account is read and replaced in a single statement, the history row
+    being only inserted when the upsert applied and changed it. The first update of an account only records its
+    rent epoch, there being no earlier one to compare with
+*/
+#[derive(Clone, Copy)]
+pub struct RentEpochHistoryHandler {}
+
+impl AccountHandler for RentEpochHistoryHandler {
+    fn init(&self, config: &crate::config::GeyserPluginPostgresConfig) -> String {
+        if !self.enabled(config) {
+            return "".to_string();
+        };
+        "
+            CREATE TABLE IF NOT EXISTS account_current_rent_epoch (
+                pubkey VARCHAR(44) PRIMARY KEY,
+                rent_epoch BIGINT NOT NULL,
+                slot BIGINT NOT NULL,
+                write_version BIGINT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS account_rent_epoch_history (
+                pubkey VARCHAR(44) NOT NULL,
+                old_epoch BIGINT NOT NULL,
+                new_epoch BIGINT NOT NULL,
+                slot BIGINT NOT NULL
+            );
+            CREATE UNIQUE INDEX IF NOT EXISTS account_rent_epoch_history_transition ON account_rent_epoch_history (pubkey, slot, new_epoch);
+        "
+        .to_string()
+    }
+
+    fn account_match(&self, _account: &DbAccountInfo) -> bool {
+        true
+    }
+
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        format!(
+            "
+                WITH previous AS (SELECT rent_epoch FROM account_current_rent_epoch WHERE pubkey='{0}'), \
+                updated AS (INSERT INTO account_current_rent_epoch AS entry (pubkey, rent_epoch, slot, write_version) VALUES ('{0}', {1}, {2}, {3}) \
+                    ON CONFLICT (pubkey) DO UPDATE SET rent_epoch=excluded.rent_epoch, slot=excluded.slot, write_version=excluded.write_version \
+                    WHERE (entry.slot, entry.write_version) < (excluded.slot, excluded.write_version) \
+                    RETURNING rent_epoch) \
+                INSERT INTO account_rent_epoch_history (pubkey, old_epoch, new_epoch, slot) \
+                SELECT '{0}', previous.rent_epoch, updated.rent_epoch, {2} FROM previous, updated WHERE previous.rent_epoch != updated.rent_epoch \
+                ON CONFLICT (pubkey, slot, new_epoch) DO NOTHING;
+            ",
+            account.pubkey_str(),
+            &account.rent_epoch,
+            &account.slot,
+            &account.write_version,
+        )
+        .into()
+    }
+
+    fn account_delete(&self, account: &DbAccountInfo) -> AccountQuery {
+        format!(
+            "DELETE FROM account_current_rent_epoch WHERE pubkey='{}' AND (slot, write_version) <= ({}, {});",
+            account.pubkey_str(),
+            &account.slot,
+            &account.write_version,
+        )
+        .into()
+    }
+}
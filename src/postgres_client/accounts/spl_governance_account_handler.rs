@@ -0,0 +1,327 @@
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use log::error;
+use solana_sdk::pubkey::Pubkey;
+
+use super::account_handler::AccountHandler;
+use super::account_query::AccountQuery;
+use super::DbAccountInfo;
+
+/*
+    The first byte of every SPL Governance account is its GovernanceAccountType,
+    only the realm, proposal and vote record types are indexed
+*/
+const REALM_V1_ACCOUNT_TYPE: u8 = 1;
+const PROPOSAL_V1_ACCOUNT_TYPE: u8 = 5;
+const VOTE_RECORD_V1_ACCOUNT_TYPE: u8 = 7;
+const VOTE_RECORD_V2_ACCOUNT_TYPE: u8 = 12;
+const PROPOSAL_V2_ACCOUNT_TYPE: u8 = 14;
+const REALM_V2_ACCOUNT_TYPE: u8 = 16;
+
+/// Leading fields of RealmV1 and RealmV2, which share their layout up to the name
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub struct Realm {
+    pub account_type: u8,
+    pub community_mint: Pubkey,
+    pub config: RealmConfig,
+    pub reserved: [u8; 6],
+    pub voting_proposal_count: u16,
+    pub authority: Option<Pubkey>,
+    pub name: String,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub struct RealmConfig {
+    pub legacy: [u8; 2],
+    pub reserved: [u8; 6],
+    pub min_community_weight_to_create_governance: u64,
+    pub community_mint_max_voter_weight_source: MintMaxVoterWeightSource,
+    pub council_mint: Option<Pubkey>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub enum MintMaxVoterWeightSource {
+    SupplyFraction(u64),
+    Absolute(u64),
+}
+
+/// Leading fields of ProposalV1, with yes/no vote tallies
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub struct ProposalV1 {
+    pub account_type: u8,
+    pub governance: Pubkey,
+    pub governing_token_mint: Pubkey,
+    pub state: u8,
+    pub token_owner_record: Pubkey,
+    pub signatories_count: u8,
+    pub signatories_signed_off_count: u8,
+    pub yes_votes_count: u64,
+    pub no_votes_count: u64,
+}
+
+/// Leading fields of ProposalV2, with per-option vote tallies
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub struct ProposalV2 {
+    pub account_type: u8,
+    pub governance: Pubkey,
+    pub governing_token_mint: Pubkey,
+    pub state: u8,
+    pub token_owner_record: Pubkey,
+    pub signatories_count: u8,
+    pub signatories_signed_off_count: u8,
+    pub vote_type: VoteType,
+    pub options: Vec<ProposalOption>,
+    pub deny_vote_weight: Option<u64>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub enum VoteType {
+    SingleChoice,
+    MultiChoice {
+        choice_type: u8,
+        min_voter_options: u8,
+        max_voter_options: u8,
+        max_winning_options: u8,
+    },
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub struct ProposalOption {
+    pub label: String,
+    pub vote_weight: u64,
+    pub vote_result: u8,
+    pub transactions_executed_count: u16,
+    pub transactions_count: u16,
+    pub transactions_next_index: u16,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub struct VoteRecordV1 {
+    pub account_type: u8,
+    pub proposal: Pubkey,
+    pub governing_token_owner: Pubkey,
+    pub is_relinquished: bool,
+    pub vote_weight: VoteWeightV1,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub enum VoteWeightV1 {
+    Yes(u64),
+    No(u64),
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub struct VoteRecordV2 {
+    pub account_type: u8,
+    pub proposal: Pubkey,
+    pub governing_token_owner: Pubkey,
+    pub is_relinquished: bool,
+    pub voter_weight: u64,
+    pub vote: Vote,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub enum Vote {
+    Approve(Vec<VoteChoice>),
+    Deny,
+    Abstain,
+    Veto,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub struct VoteChoice {
+    pub rank: u8,
+    pub weight_percentage: u8,
+}
+
+/// Indexes realms, proposals and vote records of SPL Governance (GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw).
+/// DAOs frequently run their own instance of the governance program, so accounts are matched on
+/// their account type and the program ids to index are chosen with the accounts selector owners.
+#[derive(Clone, Copy)]
+pub struct SplGovernanceAccountHandler {}
+
+impl SplGovernanceAccountHandler {
+    fn realm_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        let realm: Realm = match BorshDeserialize::deserialize(&mut account.data.as_ref()) {
+            Ok(r) => r,
+            Err(e) => {
+                error!("[account_update] Failed to deserialize realm pubkey=[{:?}] error=[{:?}]", account.pubkey, e);
+                return AccountQuery::default();
+            }
+        };
+        let realm_key: &Pubkey = bytemuck::from_bytes(&account.pubkey);
+        // the name is chosen by the realm creator, it is bound rather than rendered in the statement
+        AccountQuery::statement(
+            "
+            INSERT INTO spl_governance_realm AS acc (pubkey, community_mint, council_mint, authority, name, slot) \
+            VALUES ($1, $2, $3, $4, $5, $6) \
+            ON CONFLICT (pubkey) \
+            DO UPDATE SET council_mint=excluded.council_mint, authority=excluded.authority, name=excluded.name, slot=excluded.slot \
+            WHERE acc.slot < excluded.slot;
+            "
+            .to_string(),
+            vec![
+                Box::new(realm_key.to_string()),
+                Box::new(realm.community_mint.to_string()),
+                Box::new(realm.config.council_mint.map(|p| p.to_string())),
+                Box::new(realm.authority.map(|p| p.to_string())),
+                Box::new(realm.name.replace('\0', "")),
+                Box::new(account.slot),
+            ],
+        )
+    }
+
+    fn proposal_update(&self, account: &DbAccountInfo) -> String {
+        let data = &mut account.data.as_ref();
+        let proposal = match account.data[0] {
+            PROPOSAL_V1_ACCOUNT_TYPE => ProposalV1::deserialize(data).map(|p| (p.governance, p.governing_token_mint, p.state, p.token_owner_record, vec![p.yes_votes_count], Some(p.no_votes_count))),
+            _ => ProposalV2::deserialize(data).map(|p| {
+                (
+                    p.governance,
+                    p.governing_token_mint,
+                    p.state,
+                    p.token_owner_record,
+                    p.options.iter().map(|o| o.vote_weight).collect(),
+                    p.deny_vote_weight,
+                )
+            }),
+        };
+        let (governance, governing_token_mint, state, token_owner_record, option_vote_weights, deny_vote_weight) = match proposal {
+            Ok(p) => p,
+            Err(e) => {
+                error!("[account_update] Failed to deserialize proposal pubkey=[{:?}] error=[{:?}]", account.pubkey, e);
+                return "".to_string();
+            }
+        };
+        let proposal_key: &Pubkey = bytemuck::from_bytes(&account.pubkey);
+        format!(
+            "
+            INSERT INTO spl_governance_proposal AS acc (pubkey, governance, governing_token_mint, state, token_owner_record, option_vote_weights, deny_vote_weight, slot) \
+            VALUES ('{0}', '{1}', '{2}', {3}, '{4}', '{{{5}}}', {6}, {7}) \
+            ON CONFLICT (pubkey) \
+            DO UPDATE SET state=excluded.state, option_vote_weights=excluded.option_vote_weights, deny_vote_weight=excluded.deny_vote_weight, slot=excluded.slot \
+            WHERE acc.slot < excluded.slot;
+            ",
+            proposal_key,
+            governance,
+            governing_token_mint,
+            state,
+            token_owner_record,
+            option_vote_weights.iter().map(|w| w.to_string()).collect::<Vec<String>>().join(","),
+            deny_vote_weight.map_or("NULL".to_string(), |w| w.to_string()),
+            account.slot,
+        )
+    }
+
+    fn vote_record_update(&self, account: &DbAccountInfo) -> String {
+        let data = &mut account.data.as_ref();
+        let vote_record = match account.data[0] {
+            VOTE_RECORD_V1_ACCOUNT_TYPE => VoteRecordV1::deserialize(data).map(|v| match v.vote_weight {
+                VoteWeightV1::Yes(weight) => (v.proposal, v.governing_token_owner, v.is_relinquished, weight, "approve"),
+                VoteWeightV1::No(weight) => (v.proposal, v.governing_token_owner, v.is_relinquished, weight, "deny"),
+            }),
+            _ => VoteRecordV2::deserialize(data).map(|v| {
+                let vote = match v.vote {
+                    Vote::Approve(_) => "approve",
+                    Vote::Deny => "deny",
+                    Vote::Abstain => "abstain",
+                    Vote::Veto => "veto",
+                };
+                (v.proposal, v.governing_token_owner, v.is_relinquished, v.voter_weight, vote)
+            }),
+        };
+        let (proposal, voter, is_relinquished, voter_weight, vote) = match vote_record {
+            Ok(v) => v,
+            Err(e) => {
+                error!("[account_update] Failed to deserialize vote record pubkey=[{:?}] error=[{:?}]", account.pubkey, e);
+                return "".to_string();
+            }
+        };
+        let vote_record_key: &Pubkey = bytemuck::from_bytes(&account.pubkey);
+        format!(
+            "
+            INSERT INTO spl_governance_vote_record AS acc (pubkey, proposal, voter, is_relinquished, voter_weight, vote, slot) \
+            VALUES ('{0}', '{1}', '{2}', {3}, {4}, '{5}', {6}) \
+            ON CONFLICT (pubkey) \
+            DO UPDATE SET is_relinquished=excluded.is_relinquished, voter_weight=excluded.voter_weight, vote=excluded.vote, slot=excluded.slot \
+            WHERE acc.slot < excluded.slot;
+            ",
+            vote_record_key, proposal, voter, is_relinquished, voter_weight, vote, account.slot,
+        )
+    }
+}
+
+impl AccountHandler for SplGovernanceAccountHandler {
+    fn init(&self, config: &crate::config::GeyserPluginPostgresConfig) -> String {
+        if !self.enabled(config) {
+            return "".to_string();
+        };
+        "
+            CREATE TABLE IF NOT EXISTS spl_governance_realm (
+                pubkey VARCHAR(44) NOT NULL,
+                community_mint VARCHAR(44) NOT NULL,
+                council_mint VARCHAR(44),
+                authority VARCHAR(44),
+                name TEXT NOT NULL,
+                slot BIGINT NOT NULL,
+                PRIMARY KEY(pubkey)
+            );
+            CREATE TABLE IF NOT EXISTS spl_governance_proposal (
+                pubkey VARCHAR(44) NOT NULL,
+                governance VARCHAR(44) NOT NULL,
+                governing_token_mint VARCHAR(44) NOT NULL,
+                state SMALLINT NOT NULL,
+                token_owner_record VARCHAR(44) NOT NULL,
+                option_vote_weights NUMERIC[] NOT NULL,
+                deny_vote_weight NUMERIC,
+                slot BIGINT NOT NULL,
+                PRIMARY KEY(pubkey)
+            );
+            CREATE INDEX IF NOT EXISTS spl_governance_proposal_governance ON spl_governance_proposal (governance);
+            CREATE TABLE IF NOT EXISTS spl_governance_vote_record (
+                pubkey VARCHAR(44) NOT NULL,
+                proposal VARCHAR(44) NOT NULL,
+                voter VARCHAR(44) NOT NULL,
+                is_relinquished BOOL NOT NULL,
+                voter_weight NUMERIC NOT NULL,
+                vote VARCHAR(16) NOT NULL,
+                slot BIGINT NOT NULL,
+                PRIMARY KEY(pubkey)
+            );
+            CREATE INDEX IF NOT EXISTS spl_governance_vote_record_proposal ON spl_governance_vote_record (proposal);
+            CREATE INDEX IF NOT EXISTS spl_governance_vote_record_voter ON spl_governance_vote_record (voter);
+        "
+        .to_string()
+    }
+
+    fn account_match(&self, account: &DbAccountInfo) -> bool {
+        matches!(
+            account.data.first(),
+            Some(&REALM_V1_ACCOUNT_TYPE)
+                | Some(&REALM_V2_ACCOUNT_TYPE)
+                | Some(&PROPOSAL_V1_ACCOUNT_TYPE)
+                | Some(&PROPOSAL_V2_ACCOUNT_TYPE)
+                | Some(&VOTE_RECORD_V1_ACCOUNT_TYPE)
+                | Some(&VOTE_RECORD_V2_ACCOUNT_TYPE)
+        )
+    }
+
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        if !self.account_match(account) {
+            return AccountQuery::default();
+        };
+        match account.data[0] {
+            REALM_V1_ACCOUNT_TYPE | REALM_V2_ACCOUNT_TYPE => self.realm_update(account),
+            PROPOSAL_V1_ACCOUNT_TYPE | PROPOSAL_V2_ACCOUNT_TYPE => self.proposal_update(account).into(),
+            _ => self.vote_record_update(account).into(),
+        }
+    }
+}
@@ -1,14 +1,25 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
 use solana_sdk::pubkey;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::pubkey::PUBKEY_BYTES;
 
 use super::account_handler::AccountHandler;
+use super::account_query::AccountQuery;
+use super::mint_account_handler::coption_pubkey;
+use super::mint_allowlist::MintAllowlist;
+use super::mint_decimals::MintDecimals;
+use super::token_extensions;
+use super::token_extensions::EXTENSION_NON_TRANSFERABLE_ACCOUNT;
+use super::token_extensions::EXTENSION_TRANSFER_FEE_AMOUNT;
 use super::DbAccountInfo;
 
 pub static TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 pub static TOKENZ_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
 /*
-    /// The SPL token definition -- we care about only the mint and owner fields for now at offset 0 and 32 respectively
+    /// The SPL token definition
     spl_token::state::Account {
         mint: Pubkey,
         owner: Pubkey,
@@ -22,57 +33,419 @@ pub static TOKENZ_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHn
 */
 const SPL_TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
 const SPL_TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+const SPL_TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+const SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET: usize = 72;
+const SPL_TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+const SPL_TOKEN_ACCOUNT_DELEGATED_AMOUNT_OFFSET: usize = 121;
 const SPL_TOKEN_ACCOUNT_LENGTH: usize = 165;
 const SPL_TOKEN_ACCOUNT_DISCRIMINATOR: u8 = 2;
+/*
+    spl_token::state::Mint {
+        mint_authority: COption<Pubkey>,
+        supply: u64,
+        decimals: u8,
+        is_initialized: bool,
+        freeze_authority: COption<Pubkey>,
+    }
+*/
+const SPL_TOKEN_MINT_DECIMALS_OFFSET: usize = 44;
+const SPL_TOKEN_MINT_LENGTH: usize = 82;
+const SPL_TOKEN_MINT_DISCRIMINATOR: u8 = 1;
+
+/// A row of `spl_token_account`
+struct TokenAccountRow {
+    pubkey: String,
+    owner: String,
+    mint: String,
+    slot: i64,
+    write_version: i64,
+    /// The statements written before the row: the wallet of its owner and its state transition
+    statements: String,
+    /// The values of the row in the order of the columns of the insert
+    values: String,
+}
+
+#[derive(Clone)]
+pub struct TokenAccountHandler {
+    /// Only the accounts of these mints are indexed when set
+    pub mint_allowlist: Option<MintAllowlist>,
+    /// Owners whose token accounts are skipped, when `skip_high_cardinality_owners` is set
+    pub skipped_owners: HashSet<String>,
+    /// Decimals of the mints seen, set when only the token accounts of NFTs are indexed or `ui_amount` is stored
+    pub mint_decimals: Option<MintDecimals>,
+    /// Whether only the token accounts of NFTs are indexed
+    pub nft_only: bool,
+    /// Whether the amount in the mint's decimals is stored
+    pub store_ui_amount: bool,
+    /// Appended to the condition of the update of the upsert, from `upsert_guards`
+    pub upsert_guard: String,
+    /// Whether the owners are recorded in `wallet`
+    pub index_wallets: bool,
+}
+
+impl TokenAccountHandler {
+    /// Whether the account is a token account of the token program or of Token-2022, as classified for the writes
+    pub fn matches(&self, account: &DbAccountInfo) -> bool {
+        self.account_match(account)
+    }
+
+    /// Whether the account is a mint of the token program or of Token-2022, with or without extensions
+    pub fn mint_match(account: &DbAccountInfo) -> bool {
+        account.owner == TOKEN_PROGRAM_ID.as_ref() && account.data.len() == SPL_TOKEN_MINT_LENGTH
+            || account.owner == TOKENZ_PROGRAM_ID.as_ref() && (account.data.len() == SPL_TOKEN_MINT_LENGTH || SPL_TOKEN_MINT_DISCRIMINATOR == *account.data.get(SPL_TOKEN_ACCOUNT_LENGTH).unwrap_or(&0))
+    }
+
+    fn amount(account: &DbAccountInfo) -> u64 {
+        u64::from_le_bytes(account.data[SPL_TOKEN_ACCOUNT_AMOUNT_OFFSET..SPL_TOKEN_ACCOUNT_AMOUNT_OFFSET + 8].try_into().unwrap())
+    }
+
+    /// Whether the token account holds an NFT: a single token of a mint without decimals.
+    /// Accounts of mints not seen yet are only checked for their amount
+    fn is_nft(mint_decimals: &MintDecimals, mint: &Pubkey, account: &DbAccountInfo) -> bool {
+        Self::amount(account) == 1 && mint_decimals.get(mint.as_ref()).unwrap_or(0) == 0
+    }
+
+    /// Caches the decimals of the mint, setting the `ui_amount` of its token accounts written before it was seen
+    fn mint_update(&self, mint_decimals: &MintDecimals, account: &DbAccountInfo) -> String {
+        let decimals = account.data[SPL_TOKEN_MINT_DECIMALS_OFFSET];
+        let is_new = mint_decimals.get(&account.pubkey).is_none();
+        mint_decimals.insert(&account.pubkey, decimals);
+        if !self.store_ui_amount || !is_new {
+            return "".to_string();
+        }
+        format!(
+            "UPDATE spl_token_account SET ui_amount=amount / POWER(10::NUMERIC, {1}) WHERE mint='{0}' AND ui_amount IS NULL AND amount IS NOT NULL;",
+            account.pubkey_str(),
+            decimals,
+        )
+    }
+
+    /// The row of a token account and the statements written before it, None for the accounts not written
+    fn row(&self, account: &DbAccountInfo) -> Option<TokenAccountRow> {
+        if !self.account_match(account) {
+            return None;
+        };
+        let mint: &Pubkey = bytemuck::from_bytes(&account.data[SPL_TOKEN_ACCOUNT_MINT_OFFSET..SPL_TOKEN_ACCOUNT_MINT_OFFSET + PUBKEY_BYTES]);
+        let owner: &Pubkey = bytemuck::from_bytes(&account.data[SPL_TOKEN_ACCOUNT_OWNER_OFFSET..SPL_TOKEN_ACCOUNT_OWNER_OFFSET + PUBKEY_BYTES]);
+        let owner_key = bs58::encode(owner).into_string();
+        if self.skipped_owners.contains(&owner_key) {
+            return None;
+        }
+        if let Some(mint_decimals) = self.mint_decimals.as_ref().filter(|_| self.nft_only) {
+            if !Self::is_nft(mint_decimals, mint, account) {
+                return None;
+            }
+        }
+        let mint_key = bs58::encode(mint).into_string();
+        if let Some(mint_allowlist) = &self.mint_allowlist {
+            if !mint_allowlist.contains(&mint_key) {
+                return None;
+            }
+        }
+        let pubkey_bytes: [u8; 32] = account.pubkey[..].try_into().unwrap();
+        let pubkey = Pubkey::from(pubkey_bytes);
+        let slot = account.slot;
+        // Uninitialized=0, Initialized=1, Frozen=2
+        let state = account.data[SPL_TOKEN_ACCOUNT_STATE_OFFSET];
+        let amount = Self::amount(account);
+        // NULL until the decimals of the mint are known
+        let ui_amount = match (self.store_ui_amount, &self.mint_decimals) {
+            (true, Some(mint_decimals)) => mint_decimals.get(mint.as_ref()).map_or("NULL".to_string(), |decimals| ui_amount(amount, decimals)),
+            _ => "NULL".to_string(),
+        };
+        let delegated_amount = u64::from_le_bytes(
+            account.data[SPL_TOKEN_ACCOUNT_DELEGATED_AMOUNT_OFFSET..SPL_TOKEN_ACCOUNT_DELEGATED_AMOUNT_OFFSET + 8]
+                .try_into()
+                .unwrap(),
+        );
+        // Token-2022 extensions restricting transfers, unknown extensions are skipped
+        let non_transferable = token_extensions::find_extension(&account.data, EXTENSION_NON_TRANSFERABLE_ACCOUNT).is_some();
+        let transfer_fee_withheld = token_extensions::find_extension(&account.data, EXTENSION_TRANSFER_FEE_AMOUNT).and_then(token_extensions::amount);
+        let extensions = token_extensions::extensions(&account.data)
+            .map(|extension| format!("'{}'", token_extensions::extension_name(extension.extension_type)))
+            .collect::<Vec<String>>()
+            .join(",");
+        // the wallet is written first, the token account referencing it
+        let wallet = match self.index_wallets {
+            true => format!(
+                "
+                    INSERT INTO wallet AS wallet (pubkey, first_seen_slot, last_seen_slot) VALUES ('{0}', {1}, {1}) \
+                    ON CONFLICT (pubkey) DO UPDATE SET first_seen_slot=LEAST(wallet.first_seen_slot, excluded.first_seen_slot), \
+                        last_seen_slot=GREATEST(wallet.last_seen_slot, excluded.last_seen_slot) \
+                    WHERE excluded.first_seen_slot < wallet.first_seen_slot OR excluded.last_seen_slot > wallet.last_seen_slot;
+                ",
+                &owner_key, &slot,
+            ),
+            false => "".to_string(),
+        };
+        let pubkey_key = bs58::encode(pubkey).into_string();
+        // the transition is recorded once, including when a batch is applied again after an autocommitted failure
+        Some(TokenAccountRow {
+            statements: format!(
+                "{0}
+                    INSERT INTO spl_token_account_state_history (pubkey, old_state, new_state, slot) \
+                    SELECT pubkey, state, {2}, {3} FROM spl_token_account \
+                    WHERE pubkey='{1}' AND state IS NOT NULL AND state != {2} AND (slot, COALESCE(write_version, -1)) < ({3}, {4}) \
+                    AND NOT EXISTS (SELECT 1 FROM spl_token_account_state_history WHERE pubkey='{1}' AND slot={3} AND new_state={2}) \
+                    ORDER BY slot DESC LIMIT 1;
+                ",
+                wallet, &pubkey_key, &state, &slot, &account.write_version,
+            ),
+            values: format!(
+                "('{0}', '{1}', '{2}', {3}, {4}, {5}, {6}, {7}, {8}, ARRAY[{9}]::TEXT[], {10}, {11}, {12})",
+                &pubkey_key,
+                &owner_key,
+                &mint_key,
+                &slot,
+                &state,
+                amount,
+                ui_amount,
+                non_transferable,
+                transfer_fee_withheld.map_or("NULL".to_string(), |amount| amount.to_string()),
+                extensions,
+                coption_pubkey(&account.data, SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET),
+                delegated_amount,
+                &account.write_version,
+            ),
+            pubkey: pubkey_key,
+            owner: owner_key,
+            mint: mint_key,
+            slot: account.slot,
+            write_version: account.write_version,
+        })
+    }
+}
 
-#[derive(Clone, Copy)]
-pub struct TokenAccountHandler {}
+/// The amount in raw units as a decimal number of tokens, e.g. 1.5 for 1500000 units of a mint with 6 decimals
+pub fn ui_amount(amount: u64, decimals: u8) -> String {
+    let amount = format!("{:0>width$}", amount, width = decimals as usize + 1);
+    let (integer, fraction) = amount.split_at(amount.len() - decimals as usize);
+    match fraction.trim_end_matches('0') {
+        "" => integer.to_string(),
+        fraction => format!("{}.{}", integer, fraction),
+    }
+}
 
 impl AccountHandler for TokenAccountHandler {
     fn init(&self, config: &crate::config::GeyserPluginPostgresConfig) -> String {
         if !self.enabled(config) {
             return "".to_string();
         };
-        return "
+        // the owner index leaves out high cardinality owners, the index comment records which so it is rebuilt when they change
+        let owner_index_comment = match config.high_cardinality_owners.is_empty() {
+            true => "NULL".to_string(),
+            false => format!("'excluded owners: {}'", config.high_cardinality_owners.join(",").replace('\'', "''")),
+        };
+        // the owners of the accounts written before the wallets were indexed are missing, the reference is not validated
+        let wallet_reference = match config.index_wallets {
+            true => {
+                "
+                IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'spl_token_account_wallet' AND conrelid = 'spl_token_account'::regclass) THEN
+                    ALTER TABLE spl_token_account ADD CONSTRAINT spl_token_account_wallet FOREIGN KEY (owner) REFERENCES wallet (pubkey) NOT VALID;
+                END IF;
+            "
+            }
+            false => "ALTER TABLE spl_token_account DROP CONSTRAINT IF EXISTS spl_token_account_wallet;",
+        };
+        let owner_index_predicate = match config.high_cardinality_owners.is_empty() {
+            true => "".to_string(),
+            false => format!(
+                "WHERE owner NOT IN ({})",
+                config
+                    .high_cardinality_owners
+                    .iter()
+                    .map(|owner| format!("'{}'", owner.replace('\'', "''")))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        };
+        format!(
+            "
             CREATE TABLE IF NOT EXISTS spl_token_account (
                 pubkey VARCHAR(44) NOT NULL,
                 owner VARCHAR(44) NOT NULL,
                 mint VARCHAR(44) NOT NULL,
                 slot BIGINT NOT NULL
             );
-            CREATE INDEX IF NOT EXISTS spl_token_account_owner ON spl_token_account (owner);
+            DO $$
+            BEGIN
+                IF to_regclass('spl_token_account_owner') IS NOT NULL
+                    AND obj_description(to_regclass('spl_token_account_owner'), 'pg_class') IS DISTINCT FROM {0} THEN
+                    DROP INDEX spl_token_account_owner;
+                END IF;
+            END $$;
+            CREATE INDEX IF NOT EXISTS spl_token_account_owner ON spl_token_account (owner) {1};
+            COMMENT ON INDEX spl_token_account_owner IS {0};
             CREATE INDEX IF NOT EXISTS spl_token_account_mint ON spl_token_account (mint);
             CREATE UNIQUE INDEX IF NOT EXISTS spl_token_account_owner_pair ON spl_token_account (pubkey, owner, mint);
-        "
-        .to_string();
+            ALTER TABLE spl_token_account ADD COLUMN IF NOT EXISTS state SMALLINT;
+            ALTER TABLE spl_token_account ADD COLUMN IF NOT EXISTS amount NUMERIC(20);
+            ALTER TABLE spl_token_account ADD COLUMN IF NOT EXISTS ui_amount NUMERIC;
+            ALTER TABLE spl_token_account ADD COLUMN IF NOT EXISTS non_transferable BOOL NOT NULL DEFAULT FALSE;
+            ALTER TABLE spl_token_account ADD COLUMN IF NOT EXISTS transfer_fee_withheld NUMERIC(20);
+            ALTER TABLE spl_token_account ADD COLUMN IF NOT EXISTS extensions TEXT[] NOT NULL DEFAULT '{{}}';
+            ALTER TABLE spl_token_account ADD COLUMN IF NOT EXISTS delegate VARCHAR(44);
+            ALTER TABLE spl_token_account ADD COLUMN IF NOT EXISTS delegated_amount NUMERIC(20);
+            ALTER TABLE spl_token_account ADD COLUMN IF NOT EXISTS is_frozen BOOL GENERATED ALWAYS AS (state = 2) STORED;
+            ALTER TABLE spl_token_account ADD COLUMN IF NOT EXISTS write_version BIGINT;
+            CREATE TABLE IF NOT EXISTS spl_token_account_state_history (
+                pubkey VARCHAR(44) NOT NULL,
+                old_state SMALLINT NOT NULL,
+                new_state SMALLINT NOT NULL,
+                slot BIGINT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS spl_token_account_state_history_pubkey ON spl_token_account_state_history (pubkey, slot);
+            CREATE TABLE IF NOT EXISTS spl_token_mint_allowlist (
+                mint VARCHAR(44) PRIMARY KEY
+            );
+            CREATE TABLE IF NOT EXISTS account_close_log (
+                pubkey VARCHAR(44) NOT NULL,
+                closed_at_slot BIGINT NOT NULL,
+                closing_signature VARCHAR(88),
+                PRIMARY KEY (pubkey, closed_at_slot)
+            );
+            CREATE TABLE IF NOT EXISTS wallet (
+                pubkey VARCHAR(44) PRIMARY KEY,
+                first_seen_slot BIGINT NOT NULL,
+                last_seen_slot BIGINT NOT NULL
+            );
+            DO $$
+            BEGIN
+                {2}
+            END $$;
+        ",
+            owner_index_comment, owner_index_predicate, wallet_reference,
+        )
     }
 
     fn account_match(&self, account: &DbAccountInfo) -> bool {
         account.owner == TOKEN_PROGRAM_ID.as_ref() && account.data.len() == SPL_TOKEN_ACCOUNT_LENGTH
-            || account.owner == TOKENZ_PROGRAM_ID.as_ref() && SPL_TOKEN_ACCOUNT_DISCRIMINATOR == *account.data.get(SPL_TOKEN_ACCOUNT_LENGTH).unwrap_or(&0)
+            || account.owner == TOKENZ_PROGRAM_ID.as_ref()
+                && (account.data.len() == SPL_TOKEN_ACCOUNT_LENGTH || SPL_TOKEN_ACCOUNT_DISCRIMINATOR == *account.data.get(SPL_TOKEN_ACCOUNT_LENGTH).unwrap_or(&0))
     }
 
-    fn account_update(&self, account: &DbAccountInfo) -> String {
-        if !self.account_match(account) {
-            return "".to_string();
-        };
-        let mint: &Pubkey = bytemuck::from_bytes(&account.data[SPL_TOKEN_ACCOUNT_MINT_OFFSET..SPL_TOKEN_ACCOUNT_MINT_OFFSET + PUBKEY_BYTES]);
-        let owner: &Pubkey = bytemuck::from_bytes(&account.data[SPL_TOKEN_ACCOUNT_OWNER_OFFSET..SPL_TOKEN_ACCOUNT_OWNER_OFFSET + PUBKEY_BYTES]);
-        let pubkey_bytes: [u8; 32] = account.pubkey[..].try_into().unwrap();
-        let pubkey = Pubkey::from(pubkey_bytes);
-        let slot = account.slot;
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
+        self.account_update_batch(&[account])
+    }
+
+    fn account_update_batch(&self, accounts: &[&DbAccountInfo]) -> AccountQuery {
+        let mut statements = String::new();
+        // a batch can't upsert a row twice, only the latest update of each row is kept
+        let mut rows: HashMap<(String, String, String), TokenAccountRow> = HashMap::default();
+        for account in accounts {
+            if let Some(mint_decimals) = &self.mint_decimals {
+                if Self::mint_match(account) {
+                    statements.push_str(&self.mint_update(mint_decimals, account));
+                    continue;
+                }
+            }
+            let Some(row) = self.row(account) else {
+                continue;
+            };
+            match rows.entry((row.pubkey.clone(), row.owner.clone(), row.mint.clone())) {
+                Entry::Occupied(mut entry) if (entry.get().slot, entry.get().write_version) < (row.slot, row.write_version) => {
+                    entry.insert(row);
+                }
+                Entry::Occupied(_) => {}
+                Entry::Vacant(entry) => {
+                    entry.insert(row);
+                }
+            }
+        }
+        if rows.is_empty() {
+            return statements.into();
+        }
+        let mut rows = rows.into_values().collect::<Vec<TokenAccountRow>>();
+        rows.sort_by(|a, b| (&a.pubkey, a.slot, a.write_version).cmp(&(&b.pubkey, b.slot, b.write_version)));
+        for row in &rows {
+            statements.push_str(&row.statements);
+        }
+        // updates are ordered by (slot, write_version), rows written before write_version was stored coming first
         format!(
-            "
-                INSERT INTO spl_token_account AS spl_token_entry (pubkey, owner, mint, slot) \
-                VALUES ('{0}', '{1}', '{2}', {3}) \
+            "{0}
+                INSERT INTO spl_token_account AS spl_token_entry (pubkey, owner, mint, slot, state, amount, ui_amount, non_transferable, transfer_fee_withheld, extensions, \
+                    delegate, delegated_amount, write_version) \
+                VALUES {1} \
                 ON CONFLICT (pubkey, owner, mint) \
-                DO UPDATE SET slot=excluded.slot \
-                WHERE spl_token_entry.slot < excluded.slot;
+                DO UPDATE SET slot=excluded.slot, state=excluded.state, amount=excluded.amount, ui_amount=excluded.ui_amount, \
+                    non_transferable=excluded.non_transferable, transfer_fee_withheld=excluded.transfer_fee_withheld, \
+                    extensions=excluded.extensions, delegate=excluded.delegate, delegated_amount=excluded.delegated_amount, \
+                    write_version=excluded.write_version \
+                WHERE (spl_token_entry.slot, COALESCE(spl_token_entry.write_version, -1)) < (excluded.slot, excluded.write_version){2};
             ",
-            &bs58::encode(pubkey).into_string(),
-            &bs58::encode(owner).into_string(),
-            &bs58::encode(mint).into_string(),
-            &slot,
+            statements,
+            rows.iter().map(|row| row.values.as_str()).collect::<Vec<&str>>().join(", "),
+            self.upsert_guard,
         )
+        .into()
+    }
+
+    fn account_delete(&self, account: &DbAccountInfo) -> AccountQuery {
+        // closed token accounts are removed, logging the transaction closing them when notified with it. Those
+        // reassigned to the system program may have been any account, they are only logged if a row was removed
+        let is_token_owned = account.owner == TOKEN_PROGRAM_ID.as_ref() || account.owner == TOKENZ_PROGRAM_ID.as_ref();
+        format!(
+            "
+                WITH closed AS (DELETE FROM spl_token_account WHERE pubkey='{0}' AND (slot, COALESCE(write_version, -1)) <= ({1}, {4}) RETURNING pubkey) \
+                INSERT INTO account_close_log (pubkey, closed_at_slot, closing_signature) \
+                SELECT '{0}', {1}, {2} WHERE {3} OR EXISTS (SELECT 1 FROM closed) \
+                ON CONFLICT (pubkey, closed_at_slot) DO UPDATE SET closing_signature=COALESCE(excluded.closing_signature, account_close_log.closing_signature);
+            ",
+            account.pubkey_str(),
+            &account.slot,
+            account
+                .txn_signature
+                .as_ref()
+                .map_or("NULL".to_string(), |signature| format!("'{}'", bs58::encode(signature).into_string())),
+            is_token_owned,
+            &account.write_version,
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handler() -> TokenAccountHandler {
+        TokenAccountHandler {
+            mint_allowlist: None,
+            skipped_owners: HashSet::default(),
+            mint_decimals: None,
+            nft_only: false,
+            store_ui_amount: false,
+            index_wallets: false,
+            upsert_guard: String::new(),
+        }
+    }
+
+    fn account(owner: &Pubkey, data: &[u8]) -> DbAccountInfo {
+        DbAccountInfo::from_raw(&Pubkey::new_unique(), owner, data, 1)
+    }
+
+    #[test]
+    fn test_matches_token_account() {
+        assert!(handler().matches(&account(&TOKEN_PROGRAM_ID, &[0; SPL_TOKEN_ACCOUNT_LENGTH])));
+    }
+
+    #[test]
+    fn test_matches_account_of_another_owner() {
+        assert!(!handler().matches(&account(&Pubkey::new_unique(), &[0; SPL_TOKEN_ACCOUNT_LENGTH])));
+    }
+
+    #[test]
+    fn test_matches_token_2022_account_with_extensions() {
+        let mut data = vec![0; SPL_TOKEN_ACCOUNT_LENGTH + 1];
+        data[SPL_TOKEN_ACCOUNT_LENGTH] = SPL_TOKEN_ACCOUNT_DISCRIMINATOR;
+        assert!(handler().matches(&account(&TOKENZ_PROGRAM_ID, &data)));
+    }
+
+    #[test]
+    fn test_matches_token_2022_mint() {
+        assert!(!handler().matches(&account(&TOKENZ_PROGRAM_ID, &[0; SPL_TOKEN_MINT_LENGTH])));
+        let mut data = vec![0; SPL_TOKEN_ACCOUNT_LENGTH + 1];
+        data[SPL_TOKEN_ACCOUNT_LENGTH] = SPL_TOKEN_MINT_DISCRIMINATOR;
+        assert!(!handler().matches(&account(&TOKENZ_PROGRAM_ID, &data)));
     }
 }
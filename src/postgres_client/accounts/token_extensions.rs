@@ -0,0 +1,167 @@
+use solana_sdk::pubkey::PUBKEY_BYTES;
+
+/*
+    Token-2022 mints and accounts are padded to the 165 bytes of a token account, followed by their
+    account type byte and their extensions, each a 2-byte type, a 2-byte length and its value
+*/
+const EXTENSIONS_OFFSET: usize = 166;
+const EXTENSION_HEADER_LENGTH: usize = 4;
+
+pub const EXTENSION_UNINITIALIZED: u16 = 0;
+pub const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+pub const EXTENSION_TRANSFER_FEE_AMOUNT: u16 = 2;
+pub const EXTENSION_NON_TRANSFERABLE: u16 = 9;
+pub const EXTENSION_PERMANENT_DELEGATE: u16 = 12;
+pub const EXTENSION_NON_TRANSFERABLE_ACCOUNT: u16 = 13;
+pub const EXTENSION_METADATA_POINTER: u16 = 18;
+pub const EXTENSION_TOKEN_METADATA: u16 = 19;
+
+/*
+    TransferFeeConfig {
+        transfer_fee_config_authority: OptionalNonZeroPubkey,
+        withdraw_withheld_authority: OptionalNonZeroPubkey,
+        withheld_amount: u64,
+        older_transfer_fee: TransferFee,
+        newer_transfer_fee: TransferFee { epoch: u64, maximum_fee: u64, transfer_fee_basis_points: u16 },
+    }
+*/
+const TRANSFER_FEE_CONFIG_NEWER_MAXIMUM_FEE_OFFSET: usize = 98;
+const TRANSFER_FEE_CONFIG_NEWER_BASIS_POINTS_OFFSET: usize = 106;
+/*
+    MetadataPointer {
+        authority: OptionalNonZeroPubkey,
+        metadata_address: OptionalNonZeroPubkey,
+    }
+*/
+const METADATA_POINTER_METADATA_ADDRESS_OFFSET: usize = 32;
+/*
+    TokenMetadata, borsh serialized {
+        update_authority: OptionalNonZeroPubkey,
+        mint: Pubkey,
+        name: String,
+        symbol: String,
+        uri: String,
+        additional_metadata: Vec<(String, String)>,
+    }
+*/
+const TOKEN_METADATA_NAME_OFFSET: usize = 64;
+
+/// The metadata embedded in a Token-2022 mint, its strings as stored
+pub struct TokenMetadata<'a> {
+    pub update_authority: Option<&'a [u8]>,
+    pub name: &'a [u8],
+    pub symbol: &'a [u8],
+    pub uri: &'a [u8],
+    pub additional_metadata: Vec<(&'a [u8], &'a [u8])>,
+}
+
+/// An extension of a Token-2022 mint or account
+pub struct Extension<'a> {
+    pub extension_type: u16,
+    pub value: &'a [u8],
+}
+
+/// The extensions of a Token-2022 mint or account, none for accounts of the token program. The walk stops at
+/// the first uninitialized or truncated extension, extensions of unknown types are left to the caller to skip
+pub fn extensions(data: &[u8]) -> impl Iterator<Item = Extension<'_>> {
+    let mut offset = EXTENSIONS_OFFSET;
+    std::iter::from_fn(move || {
+        let header = data.get(offset..offset + EXTENSION_HEADER_LENGTH)?;
+        let extension_type = u16::from_le_bytes([header[0], header[1]]);
+        let length = u16::from_le_bytes([header[2], header[3]]) as usize;
+        if extension_type == EXTENSION_UNINITIALIZED {
+            return None;
+        }
+        let value = data.get(offset + EXTENSION_HEADER_LENGTH..offset + EXTENSION_HEADER_LENGTH + length)?;
+        offset += EXTENSION_HEADER_LENGTH + length;
+        Some(Extension { extension_type, value })
+    })
+}
+
+/// Name of an extension type, as in `ExtensionType` of the Token-2022 program, unknown types being named by their number
+pub fn extension_name(extension_type: u16) -> String {
+    let name = match extension_type {
+        1 => "transfer_fee_config",
+        2 => "transfer_fee_amount",
+        3 => "mint_close_authority",
+        4 => "confidential_transfer_mint",
+        5 => "confidential_transfer_account",
+        6 => "default_account_state",
+        7 => "immutable_owner",
+        8 => "memo_transfer",
+        9 => "non_transferable",
+        10 => "interest_bearing_config",
+        11 => "cpi_guard",
+        12 => "permanent_delegate",
+        13 => "non_transferable_account",
+        14 => "transfer_hook",
+        15 => "transfer_hook_account",
+        16 => "confidential_transfer_fee_config",
+        17 => "confidential_transfer_fee_amount",
+        18 => "metadata_pointer",
+        19 => "token_metadata",
+        20 => "group_pointer",
+        21 => "token_group",
+        22 => "group_member_pointer",
+        23 => "token_group_member",
+        _ => return extension_type.to_string(),
+    };
+    name.to_string()
+}
+
+/// The value of the first extension of the type, if any
+pub fn find_extension(data: &[u8], extension_type: u16) -> Option<&[u8]> {
+    extensions(data).find(|e| e.extension_type == extension_type).map(|e| e.value)
+}
+
+/// The current transfer fee of a `TransferFeeConfig`, as its basis points and maximum fee
+pub fn transfer_fee(value: &[u8]) -> Option<(u16, u64)> {
+    let maximum_fee = value.get(TRANSFER_FEE_CONFIG_NEWER_MAXIMUM_FEE_OFFSET..TRANSFER_FEE_CONFIG_NEWER_MAXIMUM_FEE_OFFSET + 8)?;
+    let basis_points = value.get(TRANSFER_FEE_CONFIG_NEWER_BASIS_POINTS_OFFSET..TRANSFER_FEE_CONFIG_NEWER_BASIS_POINTS_OFFSET + 2)?;
+    Some((u16::from_le_bytes(basis_points.try_into().unwrap()), u64::from_le_bytes(maximum_fee.try_into().unwrap())))
+}
+
+/// The pubkey of an `OptionalNonZeroPubkey`, all zeroes being None
+pub fn optional_pubkey(value: &[u8]) -> Option<&[u8]> {
+    value.get(0..PUBKEY_BYTES).filter(|key| key.iter().any(|b| *b != 0))
+}
+
+/// The metadata address of a `MetadataPointer`, the mint itself when the metadata is embedded
+pub fn metadata_address(value: &[u8]) -> Option<&[u8]> {
+    optional_pubkey(value.get(METADATA_POINTER_METADATA_ADDRESS_OFFSET..)?)
+}
+
+/// Decodes a `TokenMetadata`, None if truncated
+pub fn token_metadata(value: &[u8]) -> Option<TokenMetadata<'_>> {
+    let mut offset = TOKEN_METADATA_NAME_OFFSET;
+    let name = borsh_bytes(value, &mut offset)?;
+    let symbol = borsh_bytes(value, &mut offset)?;
+    let uri = borsh_bytes(value, &mut offset)?;
+    let count = u32::from_le_bytes(value.get(offset..offset + 4)?.try_into().unwrap());
+    offset += 4;
+    let mut additional_metadata = Vec::new();
+    for _ in 0..count {
+        let key = borsh_bytes(value, &mut offset)?;
+        additional_metadata.push((key, borsh_bytes(value, &mut offset)?));
+    }
+    Some(TokenMetadata {
+        update_authority: optional_pubkey(value),
+        name,
+        symbol,
+        uri,
+        additional_metadata,
+    })
+}
+
+/// The bytes of the borsh string at `offset`, a u32 length followed by them, moving `offset` past it
+fn borsh_bytes<'a>(value: &'a [u8], offset: &mut usize) -> Option<&'a [u8]> {
+    let length = u32::from_le_bytes(value.get(*offset..*offset + 4)?.try_into().unwrap()) as usize;
+    let bytes = value.get(*offset + 4..(*offset + 4).checked_add(length)?)?;
+    *offset += 4 + length;
+    Some(bytes)
+}
+
+/// The amount held by an extension starting with a u64, e.g. the withheld amount of a `TransferFeeAmount`
+pub fn amount(value: &[u8]) -> Option<u64> {
+    value.get(0..8).map(|amount| u64::from_le_bytes(amount.try_into().unwrap()))
+}
@@ -6,6 +6,7 @@ use solana_sdk::pubkey;
 use solana_sdk::pubkey::Pubkey;
 
 use super::account_handler::AccountHandler;
+use super::account_query::AccountQuery;
 use super::DbAccountInfo;
 
 pub static TOKEN_MANAGER_PROGRAM_ID: Pubkey = pubkey!("mgr99QFMYByTqGPWmNqunV7vBLmWWXdSrHUfV8Jf3JM");
@@ -72,16 +73,16 @@ impl AccountHandler for TokenManagerAccountHandler {
         account.owner == TOKEN_MANAGER_PROGRAM_ID.as_ref() && discriminator == *account.data.get(0..8).unwrap_or(&[0, 0, 0, 0, 0, 0, 0, 0])
     }
 
-    fn account_update(&self, account: &DbAccountInfo) -> String {
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
         if !self.account_match(account) {
-            return "".to_string();
+            return AccountQuery::default();
         };
 
         let token_manager: TokenManager = match BorshDeserialize::deserialize(&mut account.data[8..].as_ref()) {
             Ok(c) => c,
             Err(e) => {
                 error!("[account_update] Failed to deserialize token manager pubkey=[{:?}] error=[{:?}]", account.pubkey, e);
-                return "".to_string();
+                return AccountQuery::default();
             }
         };
         let token_manager_key: &Pubkey = bytemuck::from_bytes(&account.pubkey);
@@ -115,6 +116,6 @@ impl AccountHandler for TokenManagerAccountHandler {
             }).collect::<Vec<String>>()
             .join(",")),
             &slot
-        )
+        ).into()
     }
 }
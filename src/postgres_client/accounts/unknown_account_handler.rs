@@ -1,16 +1,34 @@
 use super::account_handler::AccountHandler;
+use super::account_projection::AccountProjections;
+use super::account_query::AccountQuery;
 use super::DbAccountInfo;
 use chrono::Utc;
+use solana_sdk::rent::Rent;
 
-#[derive(Clone, Copy)]
-pub struct UnknownAccountHandler {}
+#[derive(Clone)]
+pub struct UnknownAccountHandler {
+    pub raw_data_max_bytes: Option<usize>,
+    /// Rent of the cluster, `is_rent_exempt` is NULL when unknown
+    pub rent: Option<Rent>,
+    /// Fields of the account data written to their own columns
+    pub projections: AccountProjections,
+    /// Appended to the condition of the update of the upsert, from `upsert_guards`
+    pub upsert_guard: String,
+}
+
+impl UnknownAccountHandler {
+    /// Whether the data of the account is too large to be stored
+    fn is_data_omitted(&self, account: &DbAccountInfo) -> bool {
+        self.raw_data_max_bytes.is_some_and(|max_bytes| account.data.len() > max_bytes)
+    }
+}
 
 impl AccountHandler for UnknownAccountHandler {
     fn init(&self, config: &crate::config::GeyserPluginPostgresConfig) -> String {
         if !self.enabled(config) {
             return "".to_string();
         };
-        return "
+        let mut init = "
             CREATE TABLE IF NOT EXISTS account (
                 pubkey BYTEA PRIMARY KEY,
                 owner BYTEA,
@@ -25,28 +43,35 @@ impl AccountHandler for UnknownAccountHandler {
             );
             CREATE INDEX IF NOT EXISTS account_owner ON account (owner);
             CREATE INDEX IF NOT EXISTS account_slot ON account (slot);
+            ALTER TABLE account ADD COLUMN IF NOT EXISTS data_omitted BOOL NOT NULL DEFAULT FALSE;
+            ALTER TABLE account ADD COLUMN IF NOT EXISTS is_rent_exempt BOOL;
         "
         .to_string();
+        init.push_str(&self.projections.init());
+        init
     }
 
     fn account_match(&self, _account: &DbAccountInfo) -> bool {
         true
     }
 
-    fn account_update(&self, account: &DbAccountInfo) -> String {
+    fn account_update(&self, account: &DbAccountInfo) -> AccountQuery {
         if !self.account_match(account) {
-            return "".to_string();
+            return AccountQuery::default();
         };
+        let projected_columns = self.projections.columns().map(|name| format!(", {}", name)).collect::<String>();
+        let projected_values = self.projections.values(account).iter().map(|value| format!(", {}", value)).collect::<String>();
+        let projected_updates = self.projections.columns().map(|name| format!(", {0}=excluded.{0}", name)).collect::<String>();
         format!(
             "
-                INSERT INTO account AS acct (pubkey, slot, owner, lamports, executable, rent_epoch, data, write_version, updated_on, txn_signature) \
-                VALUES ('\\x{0}', {1}, '\\x{2}', {3}, {4}, {5}, '\\x{6}', {7}, '{8}', {9}) \
+                INSERT INTO account AS acct (pubkey, slot, owner, lamports, executable, rent_epoch, data, write_version, updated_on, txn_signature, data_omitted, is_rent_exempt{12}) \
+                VALUES ('\\x{0}', {1}, '\\x{2}', {3}, {4}, {5}, {6}, {7}, '{8}', {9}, {10}, {11}{13}) \
                 ON CONFLICT (pubkey) DO UPDATE SET
                     slot=excluded.slot, owner=excluded.owner, lamports=excluded.lamports, \
                     executable=excluded.executable, rent_epoch=excluded.rent_epoch, \
                     data=excluded.data, write_version=excluded.write_version, updated_on=excluded.updated_on, \
-                    txn_signature=excluded.txn_signature \
-                WHERE acct.slot < excluded.slot OR (acct.slot = excluded.slot AND acct.write_version < excluded.write_version);
+                    txn_signature=excluded.txn_signature, data_omitted=excluded.data_omitted, is_rent_exempt=excluded.is_rent_exempt{14} \
+                WHERE (acct.slot < excluded.slot OR (acct.slot = excluded.slot AND acct.write_version < excluded.write_version)){15};
             ",
             hex::encode(&account.pubkey),
             &account.slot,
@@ -54,10 +79,30 @@ impl AccountHandler for UnknownAccountHandler {
             &account.lamports,
             &account.executable,
             &account.rent_epoch,
-            hex::encode(&account.data),
+            match self.is_data_omitted(account) {
+                true => "NULL".to_string(),
+                false => format!("'\\x{}'", hex::encode(&account.data)),
+            },
             &account.write_version,
             &Utc::now().naive_utc(),
             account.txn_signature.as_deref().map_or("NULL".to_string(), |tx| format!("'\\x{}'", hex::encode(tx))),
+            self.is_data_omitted(account),
+            self.rent.map_or("NULL".to_string(), |rent| rent.is_exempt(account.lamports as u64, account.data.len()).to_string()),
+            projected_columns,
+            projected_values,
+            projected_updates,
+            self.upsert_guard,
+        )
+        .into()
+    }
+
+    fn account_delete(&self, account: &DbAccountInfo) -> AccountQuery {
+        format!(
+            "DELETE FROM account WHERE pubkey='\\x{0}' AND (slot < {1} OR (slot = {1} AND write_version < {2}));",
+            hex::encode(&account.pubkey),
+            &account.slot,
+            &account.write_version,
         )
+        .into()
     }
 }
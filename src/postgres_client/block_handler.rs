@@ -1,15 +1,21 @@
 use crate::config::GeyserPluginPostgresConfig;
+use crate::config::SynchronousCommit;
+use crate::config::WriteCategory;
 use crate::geyser_plugin_postgres::GeyserPluginPostgresError;
+use crate::postgres_client::pool::PooledClient;
+use crate::postgres_client::write_error;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
 use chrono::Utc;
-use log::*;
-use postgres::Client;
-use postgres::Statement;
+use postgres_types::ToSql;
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
 use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
 use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaBlockInfo;
 
 use super::transaction_handler::DbReward;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct DbBlockInfo {
     pub slot: i64,
     pub blockhash: String,
@@ -31,17 +37,23 @@ impl<'a> From<&ReplicaBlockInfo<'a>> for DbBlockInfo {
 }
 
 pub struct BlockHandler {
-    pub upsert_statement: Statement,
+    /// Prepared on each connection it is executed on
+    pub upsert_query: String,
+    /// Blocks are written in a database transaction committed at this level when set
+    pub synchronous_commit: Option<SynchronousCommit>,
 }
 
 impl BlockHandler {
-    pub fn new(client: &mut Client, _config: &GeyserPluginPostgresConfig) -> Result<BlockHandler, GeyserPluginError> {
+    pub fn new(client: &mut PooledClient, config: &GeyserPluginPostgresConfig) -> Result<BlockHandler, GeyserPluginError> {
         let stmt = "INSERT INTO block (slot, blockhash, rewards, block_time, block_height, updated_on) \
         VALUES ($1, $2, $3, $4, $5, $6) \
         ON CONFLICT (slot) DO UPDATE SET blockhash=excluded.blockhash, rewards=excluded.rewards, \
         block_time=excluded.block_time, block_height=excluded.block_height, updated_on=excluded.updated_on;";
-        match client.prepare(stmt) {
-            Ok(statement) => Ok(BlockHandler { upsert_statement: statement }),
+        match client.prepare_cached(stmt) {
+            Ok(_) => Ok(BlockHandler {
+                upsert_query: stmt.to_string(),
+                synchronous_commit: config.synchronous_commit.get(&WriteCategory::Block).copied(),
+            }),
             Err(err) => Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
                 msg: format!("[block_handler::new] error={}", err),
             }))),
@@ -51,7 +63,7 @@ impl BlockHandler {
     pub fn init(_config: &crate::config::GeyserPluginPostgresConfig) -> String {
         return "
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'RewardType') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'RewardType' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"RewardType\" AS ENUM (
                         'Fee',
                         'Rent',
@@ -62,7 +74,7 @@ impl BlockHandler {
             END $$;
             
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'Reward') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'Reward' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"Reward\" AS (
                         pubkey VARCHAR(44),
                         lamports BIGINT,
@@ -85,22 +97,26 @@ impl BlockHandler {
         .to_string();
     }
 
-    pub fn update(&self, client: &mut Client, block_info: DbBlockInfo) -> Result<(), GeyserPluginError> {
-        let result = client.query(
-            &self.upsert_statement,
-            &[
-                &block_info.slot,
-                &block_info.blockhash,
-                &block_info.rewards,
-                &block_info.block_time,
-                &block_info.block_height,
-                &Utc::now().naive_utc(),
-            ],
-        );
+    pub fn update(&self, client: &mut PooledClient, block_info: &DbBlockInfo) -> Result<(), GeyserPluginError> {
+        let updated_on = Utc::now().naive_utc();
+        let params: [&(dyn ToSql + Sync); 6] = [
+            &block_info.slot,
+            &block_info.blockhash,
+            &block_info.rewards,
+            &block_info.block_time,
+            &block_info.block_height,
+            &updated_on,
+        ];
+        let result = client.prepare_cached(&self.upsert_query).and_then(|statement| match &self.synchronous_commit {
+            Some(synchronous_commit) => client.transaction().and_then(|mut db_transaction| {
+                db_transaction.batch_execute(synchronous_commit.set_local())?;
+                db_transaction.query(&statement, &params)?;
+                db_transaction.commit()
+            }),
+            None => client.query(&statement, &params).map(|_| ()),
+        });
         if let Err(err) = result {
-            let msg = format!("Failed to persist the update of block metadata to the PostgreSQL database. Error: {:?}", err);
-            error!("{}", msg);
-            return Err(GeyserPluginError::AccountsUpdateError { msg });
+            return Err(write_error(format!("Failed to persist the update of block metadata to the PostgreSQL database. Error: {:?}", err), err));
         }
 
         Ok(())
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use crate::locks;
+use solana_metrics::datapoint_info;
+use solana_sdk::timing::AtomicInterval;
+
+const CACHE_REPORT_INTERVAL_MS: u64 = 30000;
+
+/// Size and efficiency of a cache since it was created
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    pub size: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+struct LruEntries<K, V> {
+    /// Value and last use of each key
+    values: HashMap<K, (V, u64)>,
+    /// Keys by last use, the least recently used first
+    recency: BTreeMap<u64, K>,
+    last_use: u64,
+}
+
+/// In-memory cache holding up to `capacity` entries, evicting the least recently used beyond.
+/// Its size, hits, misses and evictions are reported as the `geyser_plugin_cache` metric
+pub struct BoundedCache<K, V> {
+    name: &'static str,
+    capacity: usize,
+    entries: Mutex<LruEntries<K, V>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    last_report: AtomicInterval,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> BoundedCache<K, V> {
+    pub fn new(name: &'static str, capacity: usize) -> Self {
+        Self {
+            name,
+            capacity,
+            entries: Mutex::new(LruEntries {
+                values: HashMap::default(),
+                recency: BTreeMap::default(),
+                last_use: 0,
+            }),
+            hits: AtomicU64::default(),
+            misses: AtomicU64::default(),
+            evictions: AtomicU64::default(),
+            last_report: AtomicInterval::default(),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let value = {
+            let mut entries = locks::lock(&self.entries, "cache");
+            entries.last_use += 1;
+            let last_use = entries.last_use;
+            match entries.values.get_mut(key) {
+                Some((value, used)) => {
+                    let previous_use = std::mem::replace(used, last_use);
+                    let value = value.clone();
+                    entries.recency.remove(&previous_use);
+                    entries.recency.insert(last_use, key.clone());
+                    Some(value)
+                }
+                None => None,
+            }
+        };
+        match value {
+            Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+        self.report_if_due();
+        value
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        let mut evicted = 0;
+        {
+            let mut entries = locks::lock(&self.entries, "cache");
+            entries.last_use += 1;
+            let last_use = entries.last_use;
+            if let Some((_, previous_use)) = entries.values.insert(key.clone(), (value, last_use)) {
+                entries.recency.remove(&previous_use);
+            }
+            entries.recency.insert(last_use, key);
+            while entries.values.len() > self.capacity {
+                let Some((_, key)) = entries.recency.pop_first() else {
+                    break;
+                };
+                entries.values.remove(&key);
+                evicted += 1;
+            }
+        }
+        self.evictions.fetch_add(evicted, Ordering::Relaxed);
+        self.report_if_due();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            size: locks::lock(&self.entries, "cache").values.len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    fn report_if_due(&self) {
+        if !self.last_report.should_update(CACHE_REPORT_INTERVAL_MS) {
+            return;
+        }
+        let stats = self.stats();
+        datapoint_info!(
+            "geyser_plugin_cache",
+            ("cache", self.name, String),
+            ("size", stats.size, i64),
+            ("capacity", self.capacity, i64),
+            ("hits", stats.hits, i64),
+            ("misses", stats.misses, i64),
+            ("evictions", stats.evictions, i64),
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+use super::transaction_handler::DbCompiledInstruction;
+use super::transaction_handler::DbTransaction;
+
+pub static BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+pub static NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+// anchor discriminators, sha256("global:<instruction>")[..8]
+const MINT_V1_DISCRIMINATOR: [u8; 8] = [145, 98, 192, 118, 184, 147, 118, 104];
+const MINT_TO_COLLECTION_V1_DISCRIMINATOR: [u8; 8] = [153, 18, 178, 47, 197, 158, 86, 15];
+const TRANSFER_DISCRIMINATOR: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
+const BURN_DISCRIMINATOR: [u8; 8] = [116, 110, 29, 56, 107, 219, 42, 93];
+
+/*
+    transfer and burn take the leaf they update as arguments
+    {
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+    }
+*/
+const LEAF_ARGS_INDEX_OFFSET: usize = 8 + 3 * 32 + 8;
+
+/*
+    mints take the metadata only, the leaf is logged through the noop program
+    LeafSchemaEvent {
+        event_type: BubblegumEventType, // 1: LeafSchemaEvent
+        version: Version,               // 0: V1
+        schema: LeafSchema::V1 {        // 0: V1
+            id: Pubkey,
+            owner: Pubkey,
+            delegate: Pubkey,
+            nonce: u64,                 // the leaf index
+            data_hash: [u8; 32],
+            creator_hash: [u8; 32],
+        },
+        leaf_hash: [u8; 32],
+    }
+*/
+const LEAF_SCHEMA_EVENT_PREFIX: [u8; 3] = [1, 0, 0];
+const LEAF_SCHEMA_EVENT_NONCE_OFFSET: usize = 3 + 3 * 32;
+const LEAF_SCHEMA_EVENT_LENGTH: usize = LEAF_SCHEMA_EVENT_NONCE_OFFSET + 8 + 3 * 32;
+
+// account positions
+const MINT_LEAF_OWNER: usize = 1;
+const MINT_MERKLE_TREE: usize = 3;
+const TRANSFER_NEW_LEAF_OWNER: usize = 3;
+const TRANSFER_MERKLE_TREE: usize = 4;
+const BURN_MERKLE_TREE: usize = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DbCnftEvent {
+    pub event_type: &'static str,
+    pub tree: Vec<u8>,
+    pub leaf_index: i64,
+    /// None once burnt
+    pub new_owner: Option<Vec<u8>>,
+}
+
+pub struct CnftEventHandler {}
+
+impl CnftEventHandler {
+    pub fn init() -> String {
+        "
+            CREATE TABLE IF NOT EXISTS cnft_event (
+                tree VARCHAR(44) NOT NULL,
+                leaf_index BIGINT NOT NULL,
+                event_type VARCHAR(16) NOT NULL,
+                new_owner VARCHAR(44),
+                slot BIGINT NOT NULL,
+                signature BYTEA NOT NULL,
+                PRIMARY KEY (signature, tree, leaf_index, event_type)
+            );
+            CREATE INDEX IF NOT EXISTS cnft_event_leaf ON cnft_event (tree, leaf_index, slot);
+            CREATE INDEX IF NOT EXISTS cnft_event_new_owner ON cnft_event (new_owner);
+        "
+        .to_string()
+    }
+
+    /// Mint, transfer and burn events of the Bubblegum instructions of a successful transaction, including the ones invoked through CPI
+    pub fn events(transaction: &DbTransaction) -> Vec<DbCnftEvent> {
+        if transaction.meta.error.is_some() {
+            return Vec::new();
+        }
+        let (account_keys, instructions) = match (&transaction.legacy_message, &transaction.v0_loaded_message) {
+            (Some(message), _) => (message.account_keys.clone(), &message.instructions),
+            (_, Some(loaded_message)) => {
+                let mut account_keys = loaded_message.message.account_keys.clone();
+                account_keys.extend(loaded_message.loaded_addresses.writable.iter().cloned());
+                account_keys.extend(loaded_message.loaded_addresses.readonly.iter().cloned());
+                (account_keys, &loaded_message.message.instructions)
+            }
+            _ => return Vec::new(),
+        };
+        let mut events = Vec::new();
+        for (index, instruction) in instructions.iter().enumerate() {
+            // the instruction followed by the ones it invoked
+            let mut group = vec![instruction];
+            if let Some(inner_instructions) = transaction.meta.inner_instructions.as_ref().and_then(|inner| inner.iter().find(|i| i.index as usize == index)) {
+                group.extend(inner_instructions.instructions.iter());
+            }
+            for (position, instruction) in group.iter().enumerate() {
+                if let Some(event) = Self::event(&account_keys, instruction, &group[position + 1..]) {
+                    events.push(event);
+                }
+            }
+        }
+        events
+    }
+
+    fn event(account_keys: &[Vec<u8>], instruction: &DbCompiledInstruction, following: &[&DbCompiledInstruction]) -> Option<DbCnftEvent> {
+        let program_id = account_keys.get(instruction.program_id_index as usize)?;
+        if program_id != BUBBLEGUM_PROGRAM_ID.as_ref() || instruction.data.len() < 8 {
+            return None;
+        }
+        let account = |position: usize| -> Option<Vec<u8>> { account_keys.get(*instruction.accounts.get(position)? as usize).cloned() };
+        let leaf_args_index = || -> Option<i64> {
+            let index = instruction.data.get(LEAF_ARGS_INDEX_OFFSET..LEAF_ARGS_INDEX_OFFSET + 4)?;
+            Some(u32::from_le_bytes(index.try_into().unwrap()) as i64)
+        };
+        match instruction.data[..8].try_into().unwrap() {
+            MINT_V1_DISCRIMINATOR | MINT_TO_COLLECTION_V1_DISCRIMINATOR => Some(DbCnftEvent {
+                event_type: "mint",
+                tree: account(MINT_MERKLE_TREE)?,
+                leaf_index: Self::logged_leaf_index(account_keys, following)?,
+                new_owner: Some(account(MINT_LEAF_OWNER)?),
+            }),
+            TRANSFER_DISCRIMINATOR => Some(DbCnftEvent {
+                event_type: "transfer",
+                tree: account(TRANSFER_MERKLE_TREE)?,
+                leaf_index: leaf_args_index()?,
+                new_owner: Some(account(TRANSFER_NEW_LEAF_OWNER)?),
+            }),
+            BURN_DISCRIMINATOR => Some(DbCnftEvent {
+                event_type: "burn",
+                tree: account(BURN_MERKLE_TREE)?,
+                leaf_index: leaf_args_index()?,
+                new_owner: None,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Leaf index of the first leaf schema event logged through the noop program
+    fn logged_leaf_index(account_keys: &[Vec<u8>], instructions: &[&DbCompiledInstruction]) -> Option<i64> {
+        instructions
+            .iter()
+            .filter(|instruction| account_keys.get(instruction.program_id_index as usize).is_some_and(|program_id| program_id == NOOP_PROGRAM_ID.as_ref()))
+            .find(|instruction| instruction.data.len() == LEAF_SCHEMA_EVENT_LENGTH && instruction.data.starts_with(&LEAF_SCHEMA_EVENT_PREFIX))
+            .map(|instruction| u64::from_le_bytes(instruction.data[LEAF_SCHEMA_EVENT_NONCE_OFFSET..LEAF_SCHEMA_EVENT_NONCE_OFFSET + 8].try_into().unwrap()) as i64)
+    }
+
+    pub fn update(transaction: &DbTransaction, events: &[DbCnftEvent]) -> String {
+        events
+            .iter()
+            .map(|event| {
+                format!(
+                    "
+                        INSERT INTO cnft_event (tree, leaf_index, event_type, new_owner, slot, signature) \
+                        VALUES ('{0}', {1}, '{2}', {3}, {4}, decode('{5}', 'hex')) \
+                        ON CONFLICT DO NOTHING;
+                    ",
+                    bs58::encode(&event.tree).into_string(),
+                    event.leaf_index,
+                    event.event_type,
+                    event.new_owner.as_ref().map_or("NULL".to_string(), |owner| format!("'{}'", bs58::encode(owner).into_string())),
+                    transaction.slot,
+                    hex::encode(&transaction.signature),
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("")
+    }
+}
@@ -0,0 +1,90 @@
+use super::AccountQuery;
+
+/// A copy of the account handler tables in its own schema, only written once the slot of an update reaches
+/// its commitment, kept when `commitment_views` is set
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CommitmentView {
+    Confirmed,
+    Rooted,
+}
+
+impl CommitmentView {
+    pub const ALL: [CommitmentView; 2] = [CommitmentView::Confirmed, CommitmentView::Rooted];
+
+    pub fn schema(&self) -> &'static str {
+        match self {
+            CommitmentView::Confirmed => "confirmed",
+            CommitmentView::Rooted => "rooted",
+        }
+    }
+
+    /// Scopes the handler query `query` to the view's tables, for the transaction it is executed in
+    pub fn scoped(&self, query: AccountQuery) -> AccountQuery {
+        if query.is_empty() {
+            return query;
+        }
+        let mut scoped = AccountQuery::from(format!("SET LOCAL search_path TO {};", self.schema()));
+        scoped.append(query);
+        scoped.append(AccountQuery::from("SET LOCAL search_path TO DEFAULT;"));
+        scoped
+    }
+
+    /// Creates the handler tables of each view from `handler_init`, the init of the handlers, and the function
+    /// restoring the confirmed rows of abandoned slots
+    pub fn init(handler_init: &str) -> String {
+        let mut init = Self::in_views(handler_init);
+        // the rows of a table written at the abandoned slots are replaced by the rooted rows of the same key, that of its
+        // primary key or first unique index. The tables without one only have their rows of these slots removed
+        init.push_str(
+            "
+            CREATE OR REPLACE FUNCTION confirmed.abandon_slots(abandoned_slots BIGINT[]) RETURNS VOID AS $$
+            DECLARE
+                view_table TEXT;
+                key_columns TEXT;
+                columns TEXT;
+            BEGIN
+                FOR view_table IN SELECT table_name FROM information_schema.columns WHERE table_schema = 'confirmed' AND column_name = 'slot' LOOP
+                    SELECT string_agg(quote_ident(a.attname), ', ' ORDER BY k.position) INTO key_columns
+                    FROM (
+                        SELECT indrelid, indkey FROM pg_index
+                        WHERE indrelid = format('confirmed.%I', view_table)::regclass AND indisunique AND indpred IS NULL AND NOT 0 = ANY(indkey::INT2[])
+                        ORDER BY indisprimary DESC, indexrelid LIMIT 1
+                    ) i
+                    CROSS JOIN unnest(i.indkey::INT2[]) WITH ORDINALITY k(attnum, position)
+                    JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = k.attnum;
+                    IF key_columns IS NULL THEN
+                        EXECUTE format('DELETE FROM confirmed.%I WHERE slot = ANY($1)', view_table) USING abandoned_slots;
+                        CONTINUE;
+                    END IF;
+                    SELECT string_agg(quote_ident(column_name), ', ') INTO columns FROM information_schema.columns
+                    WHERE table_schema = 'confirmed' AND table_name = view_table AND is_generated = 'NEVER';
+                    EXECUTE format(
+                        'WITH abandoned AS (DELETE FROM confirmed.%1$I WHERE slot = ANY($1) RETURNING %2$s) '
+                        'INSERT INTO confirmed.%1$I (%3$s) SELECT %3$s FROM rooted.%1$I WHERE (%2$s) IN (SELECT %2$s FROM abandoned) '
+                        'ON CONFLICT DO NOTHING',
+                        view_table, key_columns, columns
+                    ) USING abandoned_slots;
+                END LOOP;
+            END $$ LANGUAGE plpgsql;
+        ",
+        );
+        init
+    }
+
+    /// Runs `query` in the schema of each view, created if missing
+    pub fn in_views(query: &str) -> String {
+        let mut scoped = String::new();
+        for view in Self::ALL {
+            scoped.push_str(&format!("CREATE SCHEMA IF NOT EXISTS {0}; SET search_path TO {0}; {1} RESET search_path;", view.schema(), query));
+        }
+        scoped
+    }
+
+    /// Restores the rows of the confirmed view written at `slots`, abandoned by a new root, to their rooted state
+    pub fn abandon_slots(slots: &[u64]) -> String {
+        format!(
+            "SELECT confirmed.abandon_slots(ARRAY[{}]::BIGINT[]);",
+            slots.iter().map(|s| s.to_string()).collect::<Vec<String>>().join(",")
+        )
+    }
+}
@@ -0,0 +1,80 @@
+//! Schema changes applied once to a database, in version order, recorded in `schema_migrations`
+
+use crate::geyser_plugin_postgres::GeyserPluginPostgresError;
+use log::*;
+use postgres::Client;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
+use std::collections::HashSet;
+
+/// A change of the tables of a handler the `IF NOT EXISTS` of its init cannot express, e.g. changing the type of a column
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Migration {
+    /// Unique across the handlers, the migrations being applied in ascending order
+    pub version: i32,
+    pub description: &'static str,
+    pub sql: String,
+}
+
+impl Migration {
+    pub fn new(version: i32, description: &'static str, sql: &str) -> Self {
+        Self {
+            version,
+            description,
+            sql: sql.to_string(),
+        }
+    }
+}
+
+const INIT_MIGRATIONS: &str = "
+    CREATE TABLE IF NOT EXISTS schema_migrations (
+        version INT PRIMARY KEY,
+        applied_at TIMESTAMP NOT NULL
+    );
+";
+
+/// Sorts the migrations by version, refusing two of the same version
+pub fn ordered(mut migrations: Vec<Migration>) -> Result<Vec<Migration>, GeyserPluginError> {
+    migrations.sort_by_key(|m| m.version);
+    let mut versions = HashSet::new();
+    if let Some(duplicate) = migrations.iter().find(|m| !versions.insert(m.version)) {
+        return Err(GeyserPluginError::ConfigFileReadError {
+            msg: format!("The migration version {} is used twice, the second by \"{}\"", duplicate.version, duplicate.description),
+        });
+    }
+    Ok(migrations)
+}
+
+/// Applies the migrations missing from `schema_migrations`, each in a transaction with its version. The table is locked
+/// for the transaction, plugin instances sharing the database applying each migration once. Returns the applied versions
+pub fn apply(client: &mut Client, migrations: &[Migration]) -> Result<Vec<i32>, GeyserPluginError> {
+    let error = |err: postgres::Error| {
+        GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+            msg: format!("[apply_migrations] error=[{}]", err),
+        }))
+    };
+    client.batch_execute(INIT_MIGRATIONS).map_err(error)?;
+    let mut applied = vec![];
+    for migration in migrations {
+        let mut transaction = client.transaction().map_err(error)?;
+        transaction.batch_execute("LOCK TABLE schema_migrations IN EXCLUSIVE MODE").map_err(error)?;
+        if transaction
+            .query_opt("SELECT 1 FROM schema_migrations WHERE version = $1", &[&migration.version])
+            .map_err(error)?
+            .is_some()
+        {
+            continue;
+        }
+        transaction.batch_execute(&migration.sql).map_err(|err| {
+            GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+                msg: format!("[apply_migrations] version={} description=\"{}\" error=[{}]", migration.version, migration.description, err),
+            }))
+        })?;
+        transaction
+            .execute("INSERT INTO schema_migrations (version, applied_at) VALUES ($1, now() AT TIME ZONE 'utc')", &[&migration.version])
+            .map_err(error)?;
+        transaction.commit().map_err(error)?;
+        info!("[apply_migrations] applied version={} description=\"{}\"", migration.version, migration.description);
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
@@ -1,50 +1,185 @@
 mod accounts;
 mod block_handler;
+pub mod cache;
+pub mod cnft_event_handler;
+pub mod commitment_view;
+pub mod migrations;
+pub mod pool;
+pub mod retry;
+pub mod schema_export;
+pub mod slot_completion;
 mod slot_handler;
+pub mod startup_integrity;
+pub mod startup_memory;
 mod transaction_handler;
 
-use crate::accounts_selector::AccountsSelectorConfig;
+use crate::accounts_selector::AccountHandlerConfig;
+use crate::accounts_selector::SharedAccountsSelector;
+use crate::config::redact_connection_str;
+use crate::config::BatchCommit;
 use crate::config::GeyserPluginPostgresConfig;
+use crate::config::SynchronousCommit;
+use crate::config::WriteCategory;
+use crate::config::ZeroLengthData;
 use crate::geyser_plugin_postgres::GeyserPluginPostgresError;
 use crate::parallel_client::ParallelClient;
 use crate::postgres_client::accounts::account_handler::all_account_handlers;
-use crate::postgres_client::accounts::account_handler::select_account_handlers;
+use crate::postgres_client::accounts::account_handler::closed_account_handlers;
+pub use crate::postgres_client::accounts::account_handler::select_account_handlers;
+pub use crate::postgres_client::accounts::account_handler::validate_account_handlers;
+use crate::postgres_client::accounts::account_handler::validate_handler_dependencies;
+use crate::postgres_client::accounts::account_handler::UPSERT_GUARD_HANDLERS;
+use crate::postgres_client::accounts::mint_allowlist::MintAllowlist;
 use crate::postgres_client::block_handler::BlockHandler;
 use crate::postgres_client::slot_handler::SlotHandler;
+use crate::telemetry::WriteSpan;
 use log::*;
+use openssl::pkey::PKey;
 use openssl::ssl::SslConnector;
+use openssl::ssl::SslConnectorBuilder;
 use openssl::ssl::SslFiletype;
 use openssl::ssl::SslMethod;
+use openssl::x509::X509;
+use postgres::error::SqlState;
 use postgres::Client;
 use postgres::NoTls;
 use postgres_openssl::MakeTlsConnector;
+use r2d2::PooledConnection;
 use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
 use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
 use solana_measure::measure::Measure;
 use solana_metrics::*;
+use solana_sdk::epoch_schedule::EpochSchedule;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::str::FromStr;
-use std::sync::Mutex;
+use std::sync::Arc;
 use std::thread;
+use std::thread::sleep;
+use std::time::Duration;
+use std::time::Instant;
 
-use self::accounts::account_handler::AccountHandler;
+pub use self::accounts::account_handler::register_account_handler;
+pub use self::accounts::account_handler::AccountHandler;
 pub use self::accounts::account_handler::AccountHandlerId;
 pub use self::accounts::account_handler::DbAccountInfo;
+pub use self::accounts::account_handler::DuplicateAccountHandlerId;
+pub use self::accounts::account_query::AccountQuery;
+pub use self::accounts::mint_decimals::MintDecimals;
+pub use self::accounts::token_account_handler::TokenAccountHandler;
 pub use self::block_handler::DbBlockInfo;
+use self::commitment_view::CommitmentView;
+use self::migrations::Migration;
+use self::pool::checkout;
+use self::pool::ConnectionManager;
+use self::pool::ConnectionPool;
+pub use self::pool::ConnectionPools;
+use self::pool::PooledClient;
+use self::retry::RetryPolicy;
+use self::slot_completion::SlotCompletion;
+pub use self::slot_handler::ForkEvent;
+use self::startup_memory::StartupMemory;
 pub use self::transaction_handler::build_db_transaction;
 pub use self::transaction_handler::DbTransaction;
 use self::transaction_handler::TransactionHandler;
 
+/// Routing key of slot writes in `handler_connections`
+pub const SLOT_TARGET: &str = "slot";
+/// Routing key of block metadata writes in `handler_connections`
+pub const BLOCK_TARGET: &str = "block";
+/// Routing key of transaction writes in `handler_connections`
+pub const TRANSACTION_TARGET: &str = "transaction";
+/// Timeout of the check of a connection after a failed query
+const CONNECTION_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Handler applied to the accounts no handler is selected for when `program_census` is set
+const PROGRAM_CENSUS_HANDLER_ID: &str = "program_census";
+
+/// The writes of a category grouped in a database transaction left open on a connection, kept to be replayed in a
+/// new one if it fails before being committed
+struct OpenCommit {
+    client: PooledConnection<ConnectionManager>,
+    /// Writes of the open transaction, emptied once it is committed or rolled back
+    queries: Vec<AccountQuery>,
+}
+
+/// A group dropped uncommitted, e.g. with its client, is rolled back before its connection returns to the pool
+impl Drop for OpenCommit {
+    fn drop(&mut self) {
+        if !self.queries.is_empty() {
+            // the connection may be gone, in which case the pool drops it
+            let _ = self.client.batch_execute("ROLLBACK;");
+        }
+    }
+}
+
+/// The flushes of a category grouped in a commit by `commit_every`, with their open transaction on each connection
+#[derive(Default)]
+struct CommitGroup {
+    flushes: usize,
+    /// Slots of the flushed writes, complete once committed
+    slots: Vec<u64>,
+    commits: HashMap<Option<String>, OpenCommit>,
+}
+
 pub struct SimplePostgresClient {
     batch_size: usize,
+    startup_slot_chunk_size: usize,
+    account_batch_commit: BatchCommit,
     slots_at_startup: HashSet<u64>,
-    pending_account_updates: Vec<DbAccountInfo>,
+    /// Account updates buffered at startup by handler id, an account selected for several handlers being
+    /// shared by their buffers and fully written once flushed from all of them
+    pending_account_updates: BTreeMap<String, Vec<Arc<DbAccountInfo>>>,
+    /// Number of accounts at the head of the buffer of each handler queued again after a failed flush, dropped if
+    /// their next flush fails too
+    requeued_account_updates: HashMap<String, usize>,
+    /// Handlers whose buffers are flushed before a handler's, by handler id
+    handler_dependencies: HashMap<String, Vec<String>>,
+    /// Unknown handler ids whose buffered accounts were dropped, logged the first time only
+    dropped_handler_ids: HashSet<String>,
+    transaction_batch_size: usize,
+    transaction_batch_max_bytes: usize,
+    pending_transactions: Vec<DbTransaction>,
+    pending_transactions_bytes: usize,
     block_handler: BlockHandler,
     transaction_handler: TransactionHandler,
     account_handlers: HashMap<AccountHandlerId, Box<dyn AccountHandler>>,
-    account_selector: Option<AccountsSelectorConfig>,
-    client: Mutex<Client>,
+    mint_allowlist: Option<MintAllowlist>,
+    program_census: bool,
+    zero_length_data: ZeroLengthData,
+    /// Whether the startup accounts are also written to the commitment views
+    commitment_views: bool,
+    /// Bytes of the account and slot queries written so far
+    bytes_written: u64,
+    /// Startup accounts written so far, checked by the startup integrity report
+    flushed_startup_accounts: usize,
+    /// Whether the startup accounts and slots were flushed, later calls to `notify_end_of_startup` being no-ops
+    is_startup_done: bool,
+    /// Shared by the workers to write the `slot_complete` markers, set when `write_slot_complete` is
+    slot_completion: Option<Arc<SlotCompletion>>,
+    /// Memory of the startup accounts not written yet, set when `startup_memory_budget_bytes` is
+    startup_memory: Option<Arc<StartupMemory>>,
+    /// Longest time the startup accounts stay buffered, `account_flush_interval_ms`
+    account_flush_interval: Option<Duration>,
+    /// When an account was buffered while the buffers were empty
+    accounts_pending_since: Option<Instant>,
+    /// Selector of the plugin, swapped when the config file is reloaded
+    account_selector: SharedAccountsSelector,
+    /// Connections checked out for each write, shared with the other workers
+    pools: ConnectionPools,
+    /// Retries of a write whose connection was lost, `write_reconnect_retries` times with the connection backoff
+    write_retry_policy: RetryPolicy,
+    /// Retries of a write failing on a serialization failure or a deadlock, `transient_error_retries` times
+    transient_retry_policy: RetryPolicy,
+    /// Number of flushes of a category grouped in a commit, by category
+    commit_every: HashMap<WriteCategory, usize>,
+    /// Flushes of the categories grouped by `commit_every` and not committed yet
+    commit_groups: HashMap<WriteCategory, CommitGroup>,
+    handler_connections: HashMap<String, String>,
+    synchronous_commit: HashMap<WriteCategory, SynchronousCommit>,
+    instance_id: Option<String>,
+    epoch_schedule: EpochSchedule,
 }
 
 pub trait PostgresClient {
@@ -60,247 +195,1258 @@ pub trait PostgresClient {
 
     fn log_transaction(&mut self, transaction_info: DbTransaction) -> Result<(), GeyserPluginError>;
 
+    fn flush_transactions(&mut self) -> Result<(), GeyserPluginError>;
+
     fn update_block_metadata(&mut self, block_info: DbBlockInfo) -> Result<(), GeyserPluginError>;
+
+    fn log_fork_event(&mut self, event: ForkEvent) -> Result<(), GeyserPluginError>;
+
+    /// Writes an account update to the tables of `view`, its slot having reached the view's commitment
+    fn update_account_view(&mut self, account: DbAccountInfo, view: CommitmentView) -> Result<(), GeyserPluginError>;
+
+    /// Restores the confirmed view of the accounts written at `slots`, abandoned by a new root
+    fn abandon_confirmed_slots(&mut self, slots: Vec<u64>) -> Result<(), GeyserPluginError>;
 }
 
 impl SimplePostgresClient {
-    pub fn new(config: &GeyserPluginPostgresConfig) -> Result<Self, GeyserPluginError> {
+    /// `mint_decimals` is shared by the clients when only the token accounts of NFTs are indexed
+    pub fn new(config: &GeyserPluginPostgresConfig, mint_decimals: Option<MintDecimals>) -> Result<Self, GeyserPluginError> {
+        Self::with_pools(config, mint_decimals, ConnectionPools::new(config)?)
+    }
+
+    /// A client writing through the connections of `pools`, shared with the other clients they are passed to
+    pub fn with_pools(config: &GeyserPluginPostgresConfig, mint_decimals: Option<MintDecimals>, pools: ConnectionPools) -> Result<Self, GeyserPluginError> {
         info!("[SimplePostgresClient] creating");
-        let mut client = Self::connect_to_db(config)?;
-        let block_handler = BlockHandler::new(&mut client, config)?;
-        let transaction_handler = TransactionHandler::new(&mut client, config)?;
+        let block_handler = BlockHandler::new(&mut *checkout(pools.routed(&config.handler_connections, BLOCK_TARGET))?, config)?;
+        let transaction_handler = TransactionHandler::new(&mut *checkout(pools.routed(&config.handler_connections, TRANSACTION_TARGET))?, config)?;
         let batch_size = config.batch_size;
+        let mint_allowlist = config.mint_allowlist_refresh_secs.map(MintAllowlist::new);
         Ok(Self {
             batch_size,
-            client: Mutex::new(client),
+            startup_slot_chunk_size: config.startup_slot_chunk_size,
+            account_batch_commit: config.account_batch_commit,
+            pools,
+            write_retry_policy: RetryPolicy {
+                retries: config.write_reconnect_retries,
+                ..RetryPolicy::new(config)
+            },
+            transient_retry_policy: RetryPolicy {
+                retries: config.transient_error_retries,
+                ..RetryPolicy::new(config)
+            },
+            commit_every: config.commit_every.clone(),
+            commit_groups: HashMap::default(),
+            handler_connections: config.handler_connections.clone(),
+            synchronous_commit: config.synchronous_commit.clone(),
             block_handler,
             transaction_handler,
-            pending_account_updates: Vec::with_capacity(batch_size),
-            account_handlers: all_account_handlers(),
-            account_selector: config.accounts_selector.clone(),
+            pending_account_updates: BTreeMap::default(),
+            requeued_account_updates: HashMap::default(),
+            handler_dependencies: config.handler_dependencies.clone(),
+            dropped_handler_ids: HashSet::default(),
+            transaction_batch_size: config.transaction_batch_size,
+            transaction_batch_max_bytes: config.transaction_batch_max_bytes,
+            pending_transactions: Vec::with_capacity(config.transaction_batch_size),
+            pending_transactions_bytes: 0,
+            account_handlers: all_account_handlers(config, mint_allowlist.clone(), mint_decimals)?,
+            mint_allowlist,
+            program_census: config.program_census,
+            zero_length_data: config.zero_length_data,
+            commitment_views: config.commitment_views,
+            bytes_written: 0,
+            flushed_startup_accounts: 0,
+            is_startup_done: false,
+            slot_completion: None,
+            startup_memory: None,
+            account_flush_interval: config.account_flush_interval_ms.map(Duration::from_millis),
+            accounts_pending_since: None,
+            account_selector: SharedAccountsSelector::new(config.accounts_selector.clone()),
             slots_at_startup: HashSet::default(),
+            instance_id: config.instance_id.clone(),
+            epoch_schedule: config.epoch_schedule(),
         })
     }
 
     pub fn connect_to_db(config: &GeyserPluginPostgresConfig) -> Result<Client, GeyserPluginError> {
-        let result = match config.use_ssl {
+        let connector = match config.use_ssl {
             Some(true) => {
-                if config.server_ca.is_none() {
-                    let msg = "\"server_ca\" must be specified when \"use_ssl\" is set".to_string();
+                if config.server_ca.is_none() && config.server_ca_pem.is_none() {
+                    let msg = "\"server_ca\" or \"server_ca_pem\" must be specified when \"use_ssl\" is set".to_string();
                     return Err(GeyserPluginError::ConfigFileReadError { msg });
                 }
-                if config.client_cert.is_none() {
-                    let msg = "\"client_cert\" must be specified when \"use_ssl\" is set".to_string();
-                    return Err(GeyserPluginError::ConfigFileReadError { msg });
-                }
-                if config.client_key.is_none() {
-                    let msg = "\"client_key\" must be specified when \"use_ssl\" is set".to_string();
+                // the client certificate is optional, the server authenticating the client by e.g. its password
+                if (config.client_cert.is_some() || config.client_cert_pem.is_some()) != (config.client_key.is_some() || config.client_key_pem.is_some()) {
+                    let msg = "\"client_cert\" and \"client_key\", or their \"_pem\" forms, must be specified together".to_string();
                     return Err(GeyserPluginError::ConfigFileReadError { msg });
                 }
                 let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
-                if let Err(err) = builder.set_ca_file(config.server_ca.as_ref().unwrap()) {
-                    let msg = format!(
-                        "Failed to set the server certificate specified by \"server_ca\": {}. Error: ({})",
-                        config.server_ca.as_ref().unwrap(),
-                        err
-                    );
+                // the inline contents are preferred to the files, e.g. injected from the environment rather than written to disk
+                let result = match (&config.server_ca_pem, &config.server_ca) {
+                    (Some(pem), _) => add_ca_pem(&mut builder, pem).map_err(|err| format!("Failed to set the server certificate of \"server_ca_pem\". Error: ({})", err)),
+                    (None, Some(path)) => builder
+                        .set_ca_file(path)
+                        .map_err(|err| format!("Failed to set the server certificate specified by \"server_ca\": {}. Error: ({})", path, err)),
+                    (None, None) => Ok(()),
+                };
+                let result = result.and_then(|_| match (&config.client_cert_pem, &config.client_cert) {
+                    (Some(pem), _) => set_certificate_pem(&mut builder, pem).map_err(|err| format!("Failed to set the client certificate of \"client_cert_pem\". Error: ({})", err)),
+                    (None, Some(path)) => builder
+                        .set_certificate_file(path, SslFiletype::PEM)
+                        .map_err(|err| format!("Failed to set the client certificate specified by \"client_cert\": {}. Error: ({})", path, err)),
+                    (None, None) => Ok(()),
+                });
+                let result = result.and_then(|_| match (&config.client_key_pem, &config.client_key) {
+                    (Some(pem), _) => PKey::private_key_from_pem(pem.as_bytes())
+                        .and_then(|key| builder.set_private_key(&key))
+                        .map_err(|err| format!("Failed to set the client key of \"client_key_pem\". Error: ({})", err)),
+                    (None, Some(path)) => builder
+                        .set_private_key_file(path, SslFiletype::PEM)
+                        .map_err(|err| format!("Failed to set the client key specified by \"client_key\": {}. Error: ({})", path, err)),
+                    (None, None) => Ok(()),
+                });
+                if let Err(msg) = result {
                     return Err(GeyserPluginError::ConfigFileReadError { msg });
                 }
-                if let Err(err) = builder.set_certificate_file(config.client_cert.as_ref().unwrap(), SslFiletype::PEM) {
-                    let msg = format!(
-                        "Failed to set the client certificate specified by \"client_cert\": {}. Error: ({})",
-                        config.client_cert.as_ref().unwrap(),
-                        err
-                    );
-                    return Err(GeyserPluginError::ConfigFileReadError { msg });
+
+                let mut connector = MakeTlsConnector::new(builder.build());
+                if !config.verify_hostname {
+                    warn!("[connect_to_db] the hostname of the server certificate is not verified");
+                    connector.set_callback(|connect_config, _domain| {
+                        connect_config.set_verify_hostname(false);
+                        Ok(())
+                    });
                 }
-                if let Err(err) = builder.set_private_key_file(config.client_key.as_ref().unwrap(), SslFiletype::PEM) {
-                    let msg = format!("Failed to set the client key specified by \"client_key\": {}. Error: ({})", config.client_key.as_ref().unwrap(), err);
-                    return Err(GeyserPluginError::ConfigFileReadError { msg });
+                Some(connector)
+            }
+            _ => None,
+        };
+        let mut pg_config: postgres::Config = config.connection_str.parse().map_err(|err| {
+            GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::ConnectionError {
+                msg: format!("[connect_to_db] connection_str={} error={}", redact_connection_str(&config.connection_str), err),
+            }))
+        })?;
+        // the schema is the default search path of the session, which the commitment views reset to
+        if let Some(schema) = &config.schema {
+            let options = pg_config.get_options().map_or(String::new(), |options| format!("{} ", options));
+            pg_config.options(&format!("{}-c search_path={}", options, schema));
+        }
+        let retry_policy = RetryPolicy::new(config);
+        let mut attempt = 0;
+        let result = loop {
+            let result = match &connector {
+                Some(connector) => pg_config.connect(connector.clone()),
+                None => pg_config.connect(NoTls),
+            };
+            match result {
+                Err(err) if attempt < retry_policy.retries => {
+                    let delay = retry_policy.delay(attempt);
+                    warn!("[connect_to_db] attempt={} retry_in_ms={} error={}", attempt + 1, delay.as_millis(), err);
+                    sleep(delay);
+                    attempt += 1;
                 }
+                result => break result,
+            }
+        };
+        let mut client = match result {
+            Err(err) => {
+                return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::ConnectionError {
+                    msg: format!("[connect_to_db] connection_str={} error={}", redact_connection_str(&config.connection_str), err),
+                })))
+            }
+            Ok(client) => client,
+        };
+        Self::set_session_parameters(&mut client, config)?;
+        Ok(client)
+    }
 
-                let mut connector = MakeTlsConnector::new(builder.build());
-                connector.set_callback(|connect_config, _domain| {
-                    connect_config.set_verify_hostname(false);
-                    Ok(())
+    /// Sets the `session_parameters` of the config on a new connection
+    fn set_session_parameters(client: &mut Client, config: &GeyserPluginPostgresConfig) -> Result<(), GeyserPluginError> {
+        if config.session_parameters.is_empty() {
+            return Ok(());
+        }
+        let mut query = String::new();
+        for (name, value) in &config.session_parameters {
+            // the names can't be quoted as values are, only plain and qualified ones are accepted
+            if !is_parameter_name(name) {
+                return Err(GeyserPluginError::ConfigFileReadError {
+                    msg: format!("\"session_parameters\" name \"{}\" is not a valid parameter name", name),
                 });
-                Client::connect(&config.connection_str, connector)
             }
-            _ => Client::connect(&config.connection_str, NoTls),
+            query.push_str(&format!("SET {} TO '{}';", name, value.replace('\'', "''")));
+        }
+        if let Err(err) = client.batch_execute(&query) {
+            return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::ConnectionError {
+                msg: format!("[connect_to_db] error setting session parameters=[{}]", err),
+            })));
+        }
+        Ok(())
+    }
+
+    /// Number of account updates buffered at startup and not written yet
+    pub fn pending_account_update_count(&self) -> usize {
+        self.pending_account_updates.values().map(|updates| updates.len()).sum()
+    }
+
+    /// Number of slots of the startup accounts not written yet
+    pub fn pending_startup_slot_count(&self) -> usize {
+        self.slots_at_startup.len()
+    }
+
+    /// Number of transactions buffered and not written yet
+    pub fn pending_transaction_count(&self) -> usize {
+        self.pending_transactions.len()
+    }
+
+    /// Number of accounts notified during startup and written
+    pub fn flushed_startup_account_count(&self) -> usize {
+        self.flushed_startup_accounts
+    }
+
+    /// Bytes of the account and slot queries written, as sent to the database
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Counts the bytes of queries written, reported as the `geyser-plugin-postgres-bytes-written` counter
+    fn add_bytes_written(&mut self, bytes: usize) {
+        self.bytes_written += bytes as u64;
+        inc_new_counter_info!("geyser-plugin-postgres-bytes-written", bytes);
+    }
+
+    pub fn set_slot_completion(&mut self, slot_completion: Arc<SlotCompletion>) {
+        self.slot_completion = Some(slot_completion);
+    }
+
+    pub fn set_accounts_selector(&mut self, account_selector: SharedAccountsSelector) {
+        self.account_selector = account_selector;
+    }
+
+    pub fn set_startup_memory(&mut self, startup_memory: Arc<StartupMemory>) {
+        self.startup_memory = Some(startup_memory);
+    }
+
+    /// Writes the buffered startup accounts of all handlers when their memory nears `startup_memory_budget_bytes`
+    pub fn flush_for_startup_memory(&mut self) -> Result<(), GeyserPluginError> {
+        match &self.startup_memory {
+            Some(startup_memory) if startup_memory.should_flush() => self.flush_account_updates("startup_memory_budget"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes the buffered startup accounts of all handlers once the oldest has waited `account_flush_interval_ms`,
+    /// so that the accounts of a buffer below the batch size are not delayed until the end of startup
+    pub fn flush_for_interval(&mut self) -> Result<(), GeyserPluginError> {
+        let (Some(interval), Some(pending_since)) = (self.account_flush_interval, self.accounts_pending_since) else {
+            return Ok(());
         };
-        match result {
-            Err(err) => Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::ConnectionError {
-                msg: format!("[connect_to_db] connection_str={} error={}", config.connection_str, err),
-            }))),
-            Ok(client) => Ok(client),
+        if self.pending_account_update_count() > 0 && pending_since.elapsed() < interval {
+            return Ok(());
+        }
+        let result = self.flush_account_updates("account_flush_interval");
+        // the accounts queued again after a failed flush wait for another interval
+        self.accounts_pending_since = (self.pending_account_update_count() > 0).then(Instant::now);
+        result
+    }
+
+    /// Writes the buffered startup accounts of all handlers
+    fn flush_account_updates(&mut self, context: &str) -> Result<(), GeyserPluginError> {
+        let handler_ids = self.pending_account_updates.keys().cloned().collect::<Vec<String>>();
+        for handler_id in handler_ids {
+            self.flush_handler_account_updates(&handler_id, context)?;
+        }
+        Ok(())
+    }
+
+    /// Records the writes of `slots` committed and marks the rooted slots left without in-flight writes complete
+    fn complete_writes(&mut self, slots: impl IntoIterator<Item = u64>) -> Result<(), GeyserPluginError> {
+        let Some(slot_completion) = self.slot_completion.clone() else {
+            return Ok(());
+        };
+        for slot in slots {
+            if slot_completion.end_write(slot) {
+                self.mark_slot_complete(slot)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks out a connection of the pool the writes of `target` are routed to through `handler_connections`
+    fn routed_connection(&self, target: &str) -> Result<PooledConnection<ConnectionManager>, GeyserPluginError> {
+        checkout(self.pools.routed(&self.handler_connections, target))
+    }
+
+    /// Runs `write` on a connection checked out of `pool`. A write failing because its connection is gone, e.g. after
+    /// a database restart, is retried on a new connection up to `write_reconnect_retries` times, and one failing on a
+    /// serialization failure or a deadlock up to `transient_error_retries` times, both with the connection backoff.
+    /// The other errors of a write whose connection is still up, like constraint violations, are returned as is
+    fn write_with_retries<T>(&self, pool: &ConnectionPool, context: &str, mut write: impl FnMut(&mut PooledClient) -> Result<T, GeyserPluginError>) -> Result<T, GeyserPluginError> {
+        let mut reconnects = 0;
+        let mut transient_retries = 0;
+        loop {
+            let err = match checkout(pool) {
+                Ok(mut client) => match write(&mut client) {
+                    Err(err) if is_transient_error(&err) && transient_retries < self.transient_retry_policy.retries => {
+                        let delay = self.transient_retry_policy.delay(transient_retries);
+                        warn!("[{}] transient error, attempt={} retry_in_ms={} error={}", context, transient_retries + 1, delay.as_millis(), err);
+                        inc_new_counter_info!("geyser-plugin-postgres-write-transient-retries", 1);
+                        drop(client);
+                        sleep(delay);
+                        transient_retries += 1;
+                        continue;
+                    }
+                    Err(err) if is_connection_lost(&mut client) => {
+                        client.discard();
+                        err
+                    }
+                    result => return result,
+                },
+                Err(err) => err,
+            };
+            if reconnects >= self.write_retry_policy.retries {
+                return Err(err);
+            }
+            let delay = self.write_retry_policy.delay(reconnects);
+            warn!("[{}] connection lost, attempt={} retry_in_ms={} error={}", context, reconnects + 1, delay.as_millis(), err);
+            inc_new_counter_info!("geyser-plugin-postgres-write-reconnects", 1);
+            // the lost connection is discarded, the pool replacing it with a new one
+            sleep(delay);
+            reconnects += 1;
+        }
+    }
+
+    /// Runs the slot query `query` on the connection slot writes are routed to, retried if the connection is lost
+    fn execute_slot_query(&mut self, query: &str, context: &str) -> Result<(), GeyserPluginError> {
+        if self.commit_every.contains_key(&WriteCategory::Slot) {
+            let connection = self.handler_connections.get(SLOT_TARGET).cloned();
+            self.grouped_write(WriteCategory::Slot, connection, AccountQuery::from(query), context)?;
+            return self.end_grouped_flush(WriteCategory::Slot, vec![]);
+        }
+        let synchronous_commit = self.synchronous_commit.get(&WriteCategory::Slot);
+        self.write_with_retries(self.pools.routed(&self.handler_connections, SLOT_TARGET), context, |client| {
+            execute_write(client, query, synchronous_commit).map_err(|err| write_error(format!("[{}] error=[{}]", context, err), err))
+        })?;
+        self.add_bytes_written(query.len());
+        Ok(())
+    }
+
+    fn mark_slot_complete(&mut self, slot: u64) -> Result<(), GeyserPluginError> {
+        debug!("[mark_slot_complete] slot=[{}]", slot);
+        self.execute_slot_query(&SlotHandler::complete(slot), "mark_slot_complete")
+    }
+
+    /// Connects to the connection named `name` in the `connections` config
+    pub fn connect_to_named_db(config: &GeyserPluginPostgresConfig, name: &str) -> Result<Client, GeyserPluginError> {
+        match config.connections.get(name) {
+            Some(connection_str) => Self::connect_to_db(&GeyserPluginPostgresConfig {
+                connection_str: connection_str.clone(),
+                ..config.clone()
+            }),
+            None => Err(GeyserPluginError::ConfigFileReadError {
+                msg: format!("\"handler_connections\" references unknown connection \"{}\"", name),
+            }),
+        }
+    }
+
+    /// The handlers selected for an account, the ones of all owners for an account closed and reassigned to the system
+    /// program, and the accounts no handler is selected for being only counted by the census
+    fn selected_handlers(&self, account: &DbAccountInfo, is_startup: bool) -> Vec<AccountHandlerConfig> {
+        let account_selector = self.account_selector.load();
+        let mut selected_handlers = select_account_handlers(&account_selector.config, account, is_startup);
+        if selected_handlers.is_empty() {
+            selected_handlers = closed_account_handlers(&account_selector.config, account);
+            if self.program_census {
+                selected_handlers.push(AccountHandlerConfig {
+                    handler_id: PROGRAM_CENSUS_HANDLER_ID.to_string(),
+                    skip_on_startup: None,
+                });
+            }
+        }
+        selected_handlers
+    }
+
+    /// The handler of `handler_id`, an error if the id is not one of the handlers, e.g. a selector not checked on load
+    fn account_handler(&self, handler_id: &str) -> Result<&dyn AccountHandler, GeyserPluginError> {
+        match AccountHandlerId::from_str(handler_id).ok().and_then(|id| self.account_handlers.get(&id)) {
+            Some(handler) => Ok(handler.as_ref()),
+            None => {
+                inc_new_counter_error!("geyser-plugin-postgres-unknown-handler-id", 1);
+                Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+                    msg: format!("[account_handler] the accounts selector references unknown handler_id \"{}\"", handler_id),
+                })))
+            }
+        }
+    }
+
+    /// Renders the query of a handler for accounts and appends it to the query of the connection the handler is routed to,
+    /// scoped to the tables of `view` if set. The updates are rendered as a batch, followed by the deletes
+    fn append_account_query(
+        &self,
+        handler_id: &str,
+        accounts: &[&DbAccountInfo],
+        view: Option<CommitmentView>,
+        queries: &mut BTreeMap<Option<String>, AccountQuery>,
+        span: &WriteSpan,
+    ) -> Result<(), GeyserPluginError> {
+        let handler_span = span.child("account_handler");
+        handler_span.set_attribute("handler_id", handler_id.to_string());
+        let handler = self.account_handler(handler_id)?;
+        let mut updates = Vec::with_capacity(accounts.len());
+        let mut deletes = AccountQuery::default();
+        for account in accounts {
+            match (account.lamports, account.data.is_empty(), self.zero_length_data) {
+                (0, _, _) | (_, true, ZeroLengthData::Delete) => deletes.append(handler.account_delete(account)),
+                (_, true, ZeroLengthData::Skip) => {}
+                _ => updates.push(*account),
+            }
+        }
+        let mut query = handler.account_update_batch(&updates);
+        query.append(deletes);
+        let query = match view {
+            Some(view) => view.scoped(query),
+            None => query,
+        };
+        queries.entry(self.handler_connections.get(handler_id).cloned()).or_default().append(query);
+        Ok(())
+    }
+
+    /// Writes the startup account updates buffered for a handler, after the ones of the handlers it depends on.
+    /// The accounts flushed from all their handlers' buffers are complete
+    fn flush_handler_account_updates(&mut self, handler_id: &str, context: &str) -> Result<(), GeyserPluginError> {
+        // the dependencies are acyclic, checked when the client is built
+        for dependency in self.handler_dependencies.get(handler_id).cloned().unwrap_or_default() {
+            self.flush_handler_account_updates(&dependency, context)?;
+        }
+        let pending_account_updates = self.pending_account_updates.remove(handler_id).unwrap_or_default();
+        if pending_account_updates.is_empty() {
+            return Ok(());
+        }
+        // the accounts buffered for an unknown handler can't be written, they are dropped rather than failing each flush
+        if !AccountHandlerId::from_str(handler_id).is_ok_and(|id| self.account_handlers.contains_key(&id)) {
+            if self.dropped_handler_ids.insert(handler_id.to_string()) {
+                error!("[{}] dropping the accounts buffered for unknown handler_id={}", context, handler_id);
+            }
+            inc_new_counter_error!("geyser-plugin-postgres-unknown-handler-id", pending_account_updates.len());
+            self.requeued_account_updates.remove(handler_id);
+            // the accounts dropped from all their handlers' buffers are done with
+            let written_slots = pending_account_updates.iter().filter(|a| Arc::strong_count(a) == 1).map(|a| a.slot as u64).collect::<Vec<u64>>();
+            let dropped_accounts = pending_account_updates.into_iter().filter_map(Arc::into_inner).collect::<Vec<DbAccountInfo>>();
+            self.release_startup_memory(&dropped_accounts);
+            return self.complete_writes(written_slots);
+        }
+        info!(
+            "[{}][flushing_accounts] handler_id={} length={}/{}",
+            context,
+            handler_id,
+            pending_account_updates.len(),
+            self.batch_size
+        );
+        let flush_span = WriteSpan::root("account_flush");
+        flush_span.set_attribute("handler_id", handler_id.to_string());
+        flush_span.set_attribute("accounts", pending_account_updates.len().to_string());
+        let mut queries = BTreeMap::default();
+        let build_span = flush_span.child("build_sql");
+        let accounts = pending_account_updates.iter().map(|a| a.as_ref()).collect::<Vec<&DbAccountInfo>>();
+        self.append_account_query(handler_id, &accounts, None, &mut queries, &build_span)?;
+        // the startup accounts are rooted
+        if self.commitment_views {
+            for view in CommitmentView::ALL {
+                self.append_account_query(handler_id, &accounts, Some(view), &mut queries, &build_span)?;
+            }
+        }
+        drop(build_span);
+        // the accounts flushed from all their handlers' buffers are complete once written
+        drop(accounts);
+        let written_slots = pending_account_updates.iter().filter(|a| Arc::strong_count(a) == 1).map(|a| a.slot as u64).collect::<Vec<u64>>();
+        let requeued = self.requeued_account_updates.remove(handler_id).unwrap_or_default();
+        if let Err(err) = self.batch_execute_routed(queries, &flush_span, written_slots) {
+            // the queries of each connection are rolled back as a whole, the accounts are queued again so the next
+            // flush writes them, the ones committed on other connections being upserted again. The accounts already
+            // queued again are dropped, so an account the database keeps rejecting does not fail all the next flushes
+            let mut pending_account_updates = pending_account_updates;
+            let dropped_accounts = pending_account_updates.drain(..requeued).filter_map(Arc::into_inner).collect::<Vec<DbAccountInfo>>();
+            if requeued > 0 {
+                error!("[{}] flush failed again, dropping the accounts queued again handler_id={} length={}", context, handler_id, requeued);
+            }
+            self.release_startup_memory(&dropped_accounts);
+            if !pending_account_updates.is_empty() {
+                warn!(
+                    "[{}] flush failed, queuing the accounts again handler_id={} length={}",
+                    context,
+                    handler_id,
+                    pending_account_updates.len()
+                );
+                self.requeued_account_updates.insert(handler_id.to_string(), pending_account_updates.len());
+                self.pending_account_updates.entry(handler_id.to_string()).or_default().splice(0..0, pending_account_updates);
+            }
+            return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+                msg: format!("[{}] error=[{}]", context, err),
+            })));
+        };
+        let flushed_accounts = pending_account_updates.into_iter().filter_map(Arc::into_inner).collect::<Vec<DbAccountInfo>>();
+        self.release_startup_memory(&flushed_accounts);
+        self.flushed_startup_accounts += flushed_accounts.len();
+        Ok(())
+    }
+
+    /// Releases the startup memory of the accounts flushed or dropped from all their handlers' buffers
+    fn release_startup_memory(&self, accounts: &[DbAccountInfo]) {
+        if let Some(startup_memory) = &self.startup_memory {
+            startup_memory.release(accounts.iter().map(StartupMemory::account_bytes).sum());
+        }
+    }
+
+    /// Executes each query in a transaction on the connection it is keyed by, `None` being the default connection,
+    /// the writes of `slots` being complete once committed. A query whose connection is lost midway is rolled back, so
+    /// it is retried as a whole on a new connection. With `account_batch_commit` set to autocommit, each statement is
+    /// committed on its own and the ones committed before a failure are applied again by the retry, the account writes
+    /// being idempotent. With `commit_every` set for the accounts, the queries are
+    /// grouped with the ones of the next flushes in a single commit
+    fn batch_execute_routed(&mut self, queries: BTreeMap<Option<String>, AccountQuery>, span: &WriteSpan, slots: Vec<u64>) -> Result<(), GeyserPluginError> {
+        let is_grouped = self.commit_every.contains_key(&WriteCategory::Account);
+        let mut bytes_written = 0;
+        for (connection, query) in queries {
+            if query.is_empty() {
+                continue;
+            }
+            let execute_span = span.child("execute");
+            execute_span.set_attribute("connection", connection.clone().unwrap_or_else(|| "default".to_string()));
+            if is_grouped {
+                self.grouped_write(WriteCategory::Account, connection, query, "batch_execute_routed")?;
+                continue;
+            }
+            let synchronous_commit = self.synchronous_commit.get(&WriteCategory::Account);
+            self.write_with_retries(self.pools.named(connection.as_ref()), "batch_execute_routed", |client| {
+                let result = match self.account_batch_commit {
+                    BatchCommit::Transaction => execute_account_query(client, &query, synchronous_commit),
+                    BatchCommit::Autocommit => query.execute(&mut **client),
+                };
+                result.map_err(|err| GeyserPluginError::Custom(Box::new(err)))
+            })?;
+            bytes_written += query.len();
+        }
+        if is_grouped {
+            return self.end_grouped_flush(WriteCategory::Account, slots);
+        }
+        self.add_bytes_written(bytes_written);
+        self.complete_writes(slots)
+    }
+
+    /// Executes `query` of `category` on `connection`, in the transaction left open there for the writes grouped by
+    /// `commit_every`. If the write fails, the transaction is rolled back and its writes are replayed with `query` in a
+    /// new transaction, committed right away
+    fn grouped_write(&mut self, category: WriteCategory, connection: Option<String>, query: AccountQuery, context: &str) -> Result<(), GeyserPluginError> {
+        let synchronous_commit = self.synchronous_commit.get(&category).copied();
+        let open_commit = self.commit_groups.entry(category).or_default().commits.remove(&connection);
+        let open_commit = match open_commit {
+            Some(open_commit) => Some(open_commit),
+            // a transaction failing to begin is replayed as any failed one
+            None => checkout(self.pools.named(connection.as_ref())).ok().and_then(|mut client| {
+                let begin = match synchronous_commit {
+                    Some(synchronous_commit) => format!("BEGIN; {}", synchronous_commit.set_local()),
+                    None => "BEGIN;".to_string(),
+                };
+                client.batch_execute(&begin).ok().map(|_| OpenCommit { client, queries: vec![] })
+            }),
+        };
+        match open_commit {
+            Some(mut open_commit) => match query.execute(&mut **open_commit.client) {
+                Ok(()) => {
+                    open_commit.queries.push(query);
+                    self.commit_groups.entry(category).or_default().commits.insert(connection, open_commit);
+                    Ok(())
+                }
+                Err(err) => {
+                    warn!("[{}] grouped write failed, replaying the group category={:?} error={}", context, category, err);
+                    let mut queries = rollback_open_commit(open_commit);
+                    queries.push(query);
+                    self.replay_commit(category, connection, queries, context)
+                }
+            },
+            None => self.replay_commit(category, connection, vec![query], context),
+        }
+    }
+
+    /// Commits the writes of `category` once `commit_every` flushes are grouped, the writes of `slots` being complete
+    /// once committed
+    fn end_grouped_flush(&mut self, category: WriteCategory, slots: Vec<u64>) -> Result<(), GeyserPluginError> {
+        let group = self.commit_groups.entry(category).or_default();
+        group.flushes += 1;
+        group.slots.extend(slots);
+        if group.flushes >= self.commit_every.get(&category).copied().unwrap_or(1) {
+            return self.commit_group(category);
+        }
+        Ok(())
+    }
+
+    /// Commits the writes of `category` grouped so far on each connection, the ones failing to commit being replayed.
+    /// A connection failing its replay doesn't keep the others from committing, the first error being returned once
+    /// they all did, and the slots of the group only being complete if every connection committed
+    fn commit_group(&mut self, category: WriteCategory) -> Result<(), GeyserPluginError> {
+        let Some(group) = self.commit_groups.remove(&category) else {
+            return Ok(());
+        };
+        debug!("[commit_group] category={:?} flushes={} connections={}", category, group.flushes, group.commits.len());
+        let mut first_err = None;
+        for (connection, mut open_commit) in group.commits {
+            match open_commit.client.batch_execute("COMMIT;") {
+                Ok(()) => {
+                    let queries = std::mem::take(&mut open_commit.queries);
+                    self.add_bytes_written(queries.iter().map(|query| query.len()).sum());
+                }
+                Err(err) => {
+                    warn!("[commit_group] commit failed, replaying the group category={:?} error={}", category, err);
+                    let queries = rollback_open_commit(open_commit);
+                    if let Err(err) = self.replay_commit(category, connection.clone(), queries, "commit_group") {
+                        error!(
+                            "[commit_group] replay failed, the group is lost category={:?} connection={} error={}",
+                            category,
+                            connection.as_deref().unwrap_or("default"),
+                            err
+                        );
+                        first_err.get_or_insert(err);
+                    }
+                }
+            }
+        }
+        match first_err {
+            Some(err) => Err(err),
+            None => self.complete_writes(group.slots),
+        }
+    }
+
+    /// Writes `queries` of `category` on `connection` in a single transaction committed right away
+    fn replay_commit(&mut self, category: WriteCategory, connection: Option<String>, queries: Vec<AccountQuery>, context: &str) -> Result<(), GeyserPluginError> {
+        let synchronous_commit = self.synchronous_commit.get(&category).copied();
+        let mut query = AccountQuery::default();
+        for q in queries {
+            query.append(q);
+        }
+        self.write_with_retries(self.pools.named(connection.as_ref()), context, |client| {
+            execute_account_query(client, &query, synchronous_commit.as_ref()).map_err(|err| write_error(format!("[{}] error=[{}]", context, err), err))
+        })?;
+        self.add_bytes_written(query.len());
+        Ok(())
+    }
+
+    /// Commits the writes grouped by `commit_every`, e.g. when the worker is idle or stops
+    pub fn commit_grouped_writes(&mut self) -> Result<(), GeyserPluginError> {
+        let categories = self.commit_groups.keys().copied().collect::<Vec<WriteCategory>>();
+        // the groups of the other categories are committed even if one fails
+        let mut result = Ok(());
+        for category in categories {
+            if let Err(err) = self.commit_group(category) {
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Rolls back the transaction of `open_commit` before its connection returns to the pool, and returns its writes
+fn rollback_open_commit(mut open_commit: OpenCommit) -> Vec<AccountQuery> {
+    // the connection may be gone, in which case the pool drops it
+    let _ = open_commit.client.batch_execute("ROLLBACK;");
+    std::mem::take(&mut open_commit.queries)
+}
+
+/// Checks the keys of `handler_connections` are account handler ids or write categories, a typo otherwise routing the
+/// writes it was meant for to the default connection
+fn validate_handler_connections(config: &GeyserPluginPostgresConfig) -> Result<(), GeyserPluginError> {
+    for target in config.handler_connections.keys() {
+        if ![SLOT_TARGET, BLOCK_TARGET, TRANSACTION_TARGET].contains(&target.as_str()) && AccountHandlerId::from_str(target).is_err() {
+            return Err(GeyserPluginError::ConfigFileReadError {
+                msg: format!(
+                    "\"handler_connections\" references \"{}\", which is neither an account handler id nor one of \"{}\", \"{}\" and \"{}\"",
+                    target, SLOT_TARGET, BLOCK_TARGET, TRANSACTION_TARGET
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the writes grouped by `commit_every` are the ones of the categories written by the client itself, the
+/// block and transaction handlers committing their own writes, and that the account writes are not autocommitted
+/// along with settings relying on their transaction
+fn validate_commit_every(config: &GeyserPluginPostgresConfig) -> Result<(), GeyserPluginError> {
+    // the autocommitted account writes are neither grouped nor committed at a level set for their transaction
+    if config.account_batch_commit == BatchCommit::Autocommit && (config.commit_every.contains_key(&WriteCategory::Account) || config.synchronous_commit.contains_key(&WriteCategory::Account)) {
+        return Err(GeyserPluginError::ConfigFileReadError {
+            msg: "\"account_batch_commit\" autocommit can't be combined with the \"commit_every\" or \"synchronous_commit\" of the accounts".to_string(),
+        });
+    }
+    // the writes of the commitment views are scoped to their schema by a `SET LOCAL` of the transaction, which an
+    // autocommitted statement runs outside of
+    if config.account_batch_commit == BatchCommit::Autocommit && config.commitment_views {
+        return Err(GeyserPluginError::ConfigFileReadError {
+            msg: "\"account_batch_commit\" autocommit can't be combined with \"commitment_views\"".to_string(),
+        });
+    }
+    for (category, count) in &config.commit_every {
+        if !matches!(category, WriteCategory::Account | WriteCategory::Slot) {
+            return Err(GeyserPluginError::ConfigFileReadError {
+                msg: format!("\"commit_every\" is only supported for the \"account\" and \"slot\" writes, not {:?}", category),
+            });
+        }
+        if *count == 0 {
+            return Err(GeyserPluginError::ConfigFileReadError {
+                msg: format!("\"commit_every\" of {:?} must be at least 1", category),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Splits `init` into its statements other than `CREATE INDEX` and its `CREATE INDEX` ones, with the comments on the
+/// indexes, the unique indexes, which upserts rely on, staying with the former. Statements end at the semicolons
+/// outside of quotes, dollar-quoted bodies and comments
+fn split_index_statements(init: &str) -> (String, String) {
+    let (mut tables, mut indexes) = (String::new(), String::new());
+    let (mut in_quote, mut in_dollar_quote, mut in_comment) = (false, false, false);
+    let mut statement = String::new();
+    let mut chars = init.chars().peekable();
+    while let Some(c) = chars.next() {
+        statement.push(c);
+        match c {
+            '\n' if in_comment => in_comment = false,
+            _ if in_comment => {}
+            '\'' if !in_dollar_quote => in_quote = !in_quote,
+            '$' if !in_quote && chars.peek() == Some(&'$') => {
+                statement.push(chars.next().unwrap());
+                in_dollar_quote = !in_dollar_quote;
+            }
+            '-' if !in_quote && !in_dollar_quote && chars.peek() == Some(&'-') => in_comment = true,
+            ';' if !in_quote && !in_dollar_quote => {
+                let upper = statement.trim_start().to_uppercase();
+                match upper.starts_with("CREATE INDEX") || upper.starts_with("COMMENT ON INDEX") {
+                    true => indexes.push_str(&statement),
+                    false => tables.push_str(&statement),
+                }
+                statement.clear();
+            }
+            _ => {}
+        }
+    }
+    tables.push_str(&statement);
+    (tables, indexes)
+}
+
+/// Checks the `schema` is a plain identifier, which the search path can be set to unquoted, and is not combined with
+/// the commitment views, whose schemas are shared
+fn validate_schema(config: &GeyserPluginPostgresConfig) -> Result<(), GeyserPluginError> {
+    let Some(schema) = &config.schema else {
+        return Ok(());
+    };
+    if schema.contains('.') || !is_parameter_name(schema) {
+        return Err(GeyserPluginError::ConfigFileReadError {
+            msg: format!("\"schema\" must be a plain identifier, not \"{}\"", schema),
+        });
+    }
+    if config.commitment_views {
+        return Err(GeyserPluginError::ConfigFileReadError {
+            msg: "\"schema\" is not supported with \"commitment_views\"".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks the `upsert_guards` are set for handlers supporting them, and that each predicate is a single expression:
+/// its quotes and parentheses are balanced and it holds no statement separator, comment, backslash or dollar quote
+/// through which it could end the upsert
+fn validate_upsert_guards(config: &GeyserPluginPostgresConfig) -> Result<(), GeyserPluginError> {
+    for (handler_id, predicate) in &config.upsert_guards {
+        if !AccountHandlerId::from_str(handler_id).is_ok_and(|id| UPSERT_GUARD_HANDLERS.contains(&id)) {
+            return Err(GeyserPluginError::ConfigFileReadError {
+                msg: format!("\"upsert_guards\" is not supported for the handler \"{}\", only for {:?}", handler_id, UPSERT_GUARD_HANDLERS),
+            });
+        }
+        if let Err(err) = check_predicate(predicate) {
+            return Err(GeyserPluginError::ConfigFileReadError {
+                msg: format!("The \"upsert_guards\" predicate of \"{}\" {}", handler_id, err),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_predicate(predicate: &str) -> Result<(), &'static str> {
+    if predicate.trim().is_empty() {
+        return Err("is empty");
+    }
+    let mut chars = predicate.chars().peekable();
+    let mut quote = None;
+    let mut depth = 0;
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            return Err("holds a backslash");
+        }
+        match quote {
+            // a doubled quote is an escaped one
+            Some(q) if c == q && chars.peek() == Some(&q) => {
+                chars.next();
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' => quote = Some(c),
+                '(' => depth += 1,
+                ')' if depth == 0 => return Err("closes a parenthesis it does not open"),
+                ')' => depth -= 1,
+                ';' => return Err("holds a statement separator"),
+                '$' => return Err("holds a dollar quote"),
+                '-' if chars.peek() == Some(&'-') => return Err("holds a comment"),
+                '/' if chars.peek() == Some(&'*') => return Err("holds a comment"),
+                _ => {}
+            },
+        }
+    }
+    match (quote, depth) {
+        (Some(_), _) => Err("leaves a quote open"),
+        (_, 0) => Ok(()),
+        _ => Err("leaves a parenthesis open"),
+    }
+}
+
+/// Adds the certificates of `pem` to the authorities the server certificate is verified against
+fn add_ca_pem(builder: &mut SslConnectorBuilder, pem: &str) -> Result<(), String> {
+    let certificates = X509::stack_from_pem(pem.as_bytes()).map_err(|err| err.to_string())?;
+    if certificates.is_empty() {
+        return Err("no PEM certificate found".to_string());
+    }
+    for certificate in certificates {
+        builder.cert_store_mut().add_cert(certificate).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+/// Sets the client certificate, the first of `pem`, followed by the chain of its issuers
+fn set_certificate_pem(builder: &mut SslConnectorBuilder, pem: &str) -> Result<(), String> {
+    let mut certificates = X509::stack_from_pem(pem.as_bytes()).map_err(|err| err.to_string())?.into_iter();
+    let certificate = certificates.next().ok_or_else(|| "no PEM certificate found".to_string())?;
+    builder.set_certificate(&certificate).map_err(|err| err.to_string())?;
+    for issuer in certificates {
+        builder.add_extra_chain_cert(issuer).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+/// Whether the name is that of a run-time parameter, e.g. `work_mem`, or of a custom one qualified by its extension,
+/// e.g. `auto_explain.log_min_duration`
+fn is_parameter_name(name: &str) -> bool {
+    name.len() <= 127
+        && name
+            .split('.')
+            .all(|part| part.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+}
+
+/// Whether the connection of `client` is gone, after a failed query
+fn is_connection_lost(client: &mut Client) -> bool {
+    client.is_closed() || client.is_valid(CONNECTION_CHECK_TIMEOUT).is_err()
+}
+
+/// A failed write, logged with `msg`. The database error is kept to tell the transient ones
+pub(crate) fn write_error(msg: String, err: postgres::Error) -> GeyserPluginError {
+    error!("{}", msg);
+    GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::WriteError { msg, source: err }))
+}
+
+/// Whether the write failed on a serialization failure or a deadlock, which the same write can succeed past once
+/// retried
+fn is_transient_error(err: &GeyserPluginError) -> bool {
+    let GeyserPluginError::Custom(err) = err else {
+        return false;
+    };
+    let db_error = match err.downcast_ref::<GeyserPluginPostgresError>() {
+        Some(GeyserPluginPostgresError::WriteError { source, .. }) => Some(source),
+        _ => err.downcast_ref::<postgres::Error>(),
+    };
+    matches!(db_error.and_then(|err| err.code()), Some(code) if *code == SqlState::T_R_SERIALIZATION_FAILURE || *code == SqlState::T_R_DEADLOCK_DETECTED)
+}
+
+/// Runs the statements of `query` in a transaction, committing all of them or none at the `synchronous_commit` level if set
+fn execute_in_transaction(client: &mut Client, query: &str, synchronous_commit: Option<&SynchronousCommit>) -> Result<(), postgres::Error> {
+    let mut transaction = client.transaction()?;
+    if let Some(synchronous_commit) = synchronous_commit {
+        transaction.batch_execute(synchronous_commit.set_local())?;
+    }
+    transaction.batch_execute(query)?;
+    transaction.commit()
+}
+
+/// Runs the statements of the account query `query` in a transaction, committing all of them or none at the
+/// `synchronous_commit` level if set
+fn execute_account_query(client: &mut Client, query: &AccountQuery, synchronous_commit: Option<&SynchronousCommit>) -> Result<(), postgres::Error> {
+    let mut transaction = client.transaction()?;
+    if let Some(synchronous_commit) = synchronous_commit {
+        transaction.batch_execute(synchronous_commit.set_local())?;
+    }
+    query.execute(&mut transaction)?;
+    transaction.commit()
+}
+
+/// Runs the statements of `query`, in a transaction committed at the `synchronous_commit` level if set
+fn execute_write(client: &mut Client, query: &str, synchronous_commit: Option<&SynchronousCommit>) -> Result<(), postgres::Error> {
+    match synchronous_commit {
+        Some(_) => execute_in_transaction(client, query, synchronous_commit),
+        None => client.batch_execute(query),
+    }
+}
+
+impl SynchronousCommit {
+    /// Statement setting the level for the current transaction only
+    fn set_local(&self) -> &'static str {
+        match self {
+            SynchronousCommit::On => "SET LOCAL synchronous_commit TO on;",
+            SynchronousCommit::Off => "SET LOCAL synchronous_commit TO off;",
+            SynchronousCommit::Local => "SET LOCAL synchronous_commit TO local;",
+            SynchronousCommit::RemoteWrite => "SET LOCAL synchronous_commit TO remote_write;",
+            SynchronousCommit::RemoteApply => "SET LOCAL synchronous_commit TO remote_apply;",
         }
     }
 }
 
 impl PostgresClient for SimplePostgresClient {
     fn update_account(&mut self, account: DbAccountInfo, is_startup: bool) -> Result<(), GeyserPluginError> {
-        let account_key = bs58::encode(&account.pubkey).into_string();
-        let owner_key = bs58::encode(&account.owner).into_string();
+        let account_key = account.pubkey_str();
+        let owner_key = account.owner_str();
         debug!("[update_account] account=[{}] owner=[{}] slot=[{}]", account_key, owner_key, account.slot,);
+        // a connection is only taken once the refresh is due, not for each account
+        if let Some(mint_allowlist) = self.mint_allowlist.as_ref().filter(|mint_allowlist| mint_allowlist.is_refresh_due()) {
+            let mut client = self.routed_connection("token_account")?;
+            if let Err(err) = mint_allowlist.refresh(&mut client) {
+                return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+                    msg: format!("[update_account] error loading mint allowlist=[{}]", err),
+                })));
+            }
+        }
 
-        let client = &mut self.client.get_mut().unwrap();
         if is_startup {
-            self.slots_at_startup.insert(account.slot as u64);
-            self.pending_account_updates.push(account);
-            // flush if batch size
-            if self.pending_account_updates.len() >= self.batch_size {
-                info!("[update_account_batch][flushing_accounts] length={}/{}", self.pending_account_updates.len(), self.batch_size);
-                let query = self
-                    .pending_account_updates
-                    .drain(..)
-                    .map(|a| {
-                        select_account_handlers(&self.account_selector, &a, true)
-                            .iter()
-                            // map feed through relevant handlers
-                            .map(|h| {
-                                self.account_handlers
-                                    .get(&AccountHandlerId::from_str(&h.handler_id).expect("Invalid account handler id"))
-                                    .expect("Invalid handler id")
-                                    .account_update(&a)
-                            })
-                            .collect::<Vec<String>>()
-                            .join("")
-                    })
-                    .collect::<Vec<String>>()
-                    .join("");
-
-                if let Err(err) = client.batch_execute(&query) {
-                    return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
-                        msg: format!("[update_account_batch] error=[{}]", err),
-                    })));
-                };
+            // the slots are only written by the startup flush, the ones notified after it being left out
+            if !self.is_startup_done {
+                self.slots_at_startup.insert(account.slot as u64);
             }
-            return Ok(());
+            let selected_handlers = self.selected_handlers(&account, true);
+            // nothing to write for the accounts without handler
+            if selected_handlers.is_empty() {
+                if let Some(startup_memory) = &self.startup_memory {
+                    startup_memory.release(StartupMemory::account_bytes(&account));
+                }
+                self.flushed_startup_accounts += 1;
+                return self.complete_writes([account.slot as u64]);
+            }
+            let account = Arc::new(account);
+            for h in &selected_handlers {
+                self.pending_account_updates.entry(h.handler_id.clone()).or_default().push(account.clone());
+            }
+            drop(account);
+            self.accounts_pending_since.get_or_insert_with(Instant::now);
+            self.flush_for_startup_memory()?;
+            // each handler's buffer is flushed at its own batch size
+            for h in selected_handlers {
+                if self.pending_account_updates.get(&h.handler_id).is_some_and(|updates| updates.len() >= self.batch_size) {
+                    self.flush_handler_account_updates(&h.handler_id, "update_account_batch")?;
+                }
+            }
+            return self.flush_for_interval();
         }
-        let query = select_account_handlers(&self.account_selector, &account, false)
-            .iter()
-            .map(|h| {
-                self.account_handlers
-                    .get(&AccountHandlerId::from_str(&h.handler_id).expect("Invalid account handler id"))
-                    .expect("Invalid handler id")
-                    .account_update(&account)
-            })
-            .collect::<Vec<String>>()
-            .join("");
-        if !query.is_empty() {
-            return match client.batch_execute(&query) {
-                Ok(_) => Ok(()),
-                Err(err) => Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
-                    msg: format!("[update_account] error=[{}]", err),
-                }))),
-            };
+        let flush_span = WriteSpan::root("account_flush");
+        flush_span.set_attribute("accounts", "1".to_string());
+        let mut queries = BTreeMap::default();
+        let build_span = flush_span.child("build_sql");
+        for h in self.selected_handlers(&account, false) {
+            self.append_account_query(&h.handler_id, &[&account], None, &mut queries, &build_span)?;
+        }
+        drop(build_span);
+        match self.batch_execute_routed(queries, &flush_span, vec![account.slot as u64]) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+                msg: format!("[update_account] error=[{}]", err),
+            }))),
         }
-        Ok(())
     }
 
     fn update_slot_status(&mut self, slot: u64, parent: Option<u64>, status: SlotStatus) -> Result<(), GeyserPluginError> {
         info!("[update_slot_status] slot=[{:?}] status=[{:?}]", slot, status);
-        let client = &mut self.client.get_mut().unwrap();
-        let query = SlotHandler::update(slot, parent, status);
+        let query = SlotHandler::update(slot, parent, status, self.epoch_schedule.get_epoch(slot), self.instance_id.as_deref());
         if !query.is_empty() {
-            return match client.batch_execute(&query) {
-                Ok(_) => Ok(()),
-                Err(err) => Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
-                    msg: format!("[update_slot_status] error=[{}]", err),
-                }))),
-            };
+            self.execute_slot_query(&query, "update_slot_status")?;
+        }
+        // the slot is complete once rooted if its writes have all been committed, otherwise the last one marks it
+        if status == SlotStatus::Rooted && self.slot_completion.as_ref().is_some_and(|slot_completion| slot_completion.root(slot)) {
+            return self.mark_slot_complete(slot);
         }
 
         Ok(())
     }
 
+    /// Writes the startup accounts and slots still buffered when the client stops before startup is done, so that
+    /// they are not lost with it
+    fn join(&mut self) -> thread::Result<()> {
+        if self.is_startup_done || (self.pending_account_update_count() == 0 && self.slots_at_startup.is_empty()) {
+            return Ok(());
+        }
+        info!("[join] flushing startup accounts={} slots={}", self.pending_account_update_count(), self.slots_at_startup.len());
+        self.notify_end_of_startup().map_err(|err| Box::new(err) as Box<dyn std::any::Any + Send>)
+    }
+
     fn notify_end_of_startup(&mut self) -> Result<(), GeyserPluginError> {
+        if self.is_startup_done {
+            warn!("[notify_end_of_startup] called again, startup already flushed");
+            return Ok(());
+        }
         // flush accounts
-        info!("[notify_end_of_startup][flushing_accounts] length={}/{}", self.pending_account_updates.len(), self.batch_size);
-        let client = &mut self.client.get_mut().unwrap();
-        let query = self
-            .pending_account_updates
-            .drain(..)
-            .map(|a| {
-                select_account_handlers(&self.account_selector, &a, true)
-                    .iter()
-                    // map feed through relevant handlers
-                    .map(|h| {
-                        self.account_handlers
-                            .get(&AccountHandlerId::from_str(&h.handler_id).expect("Invalid account handler id"))
-                            .expect("Invalid handler id")
-                            .account_update(&a)
-                    })
-                    .collect::<Vec<String>>()
-                    .join("")
-            })
-            .collect::<Vec<String>>()
-            .join("");
-        if let Err(err) = client.batch_execute(&query) {
-            return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
-                msg: format!("[notify_end_of_startup][flush_accounst_error] error=[{}]", err),
-            })));
-        };
+        self.flush_account_updates("notify_end_of_startup")?;
 
-        // flush slots sequentailly
+        // flush slots in chunks, a single statement for all of them being too large
         let mut measure = Measure::start("geyser-plugin-postgres-flush-slots-us");
-        for s in &self.slots_at_startup {
-            if let Err(err) = client.batch_execute(&SlotHandler::update(*s, None, SlotStatus::Rooted)) {
-                return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
-                    msg: format!("[notify_end_of_startup][flush_slots] error=[{}]", err),
-                })));
-            };
+        let mut slots = self.slots_at_startup.iter().map(|s| (*s, self.epoch_schedule.get_epoch(*s))).collect::<Vec<(u64, u64)>>();
+        slots.sort_unstable();
+        let chunks = slots.chunks(self.startup_slot_chunk_size.max(1)).collect::<Vec<&[(u64, u64)]>>();
+        for chunk in &chunks {
+            let query = SlotHandler::update_many(chunk, SlotStatus::Rooted, self.instance_id.as_deref());
+            self.execute_slot_query(&query, "notify_end_of_startup][flush_slots")?;
         }
-        // flush slots in batch (too large)
-        // let query = &self
-        //     .slots_at_startup
-        //     .drain()
-        //     .map(|s| SlotHandler::update(s, None, SlotStatus::Rooted))
-        //     .collect::<Vec<String>>()
-        //     .join("");
-        // if let Err(err) = client.batch_execute(&query) {
-        //     return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
-        //         msg: format!("[notify_end_of_startup][flush_slots] error=[{}]", err),
-        //     })));
-        // };
         measure.stop();
+        // released once written, the set being no longer needed after startup
+        let flushed_slots = std::mem::take(&mut self.slots_at_startup).len();
 
         datapoint_info!(
             "geyser_plugin_notify_account_restore_from_snapshot_summary",
             ("flush_slots-us", measure.as_us(), i64),
-            ("flush-slots-counts", self.slots_at_startup.len(), i64),
+            ("flush-slots-counts", flushed_slots, i64),
+            ("flush-slots-chunks", chunks.len(), i64),
         );
+        // the startup accounts are all committed once startup is done
+        self.commit_grouped_writes()?;
+        self.is_startup_done = true;
         Ok(())
     }
 
     fn log_transaction(&mut self, transaction_info: DbTransaction) -> Result<(), GeyserPluginError> {
-        self.transaction_handler.update(&mut self.client.get_mut().unwrap(), transaction_info)
+        let _span = WriteSpan::root("transaction_write");
+        if self.transaction_batch_size <= 1 {
+            let slot = transaction_info.slot as u64;
+            self.write_with_retries(self.pools.routed(&self.handler_connections, TRANSACTION_TARGET), "log_transaction", |client| {
+                self.transaction_handler.update(client, &transaction_info)
+            })?;
+            return self.complete_writes([slot]);
+        }
+        self.pending_transactions_bytes += transaction_info.estimated_size();
+        self.pending_transactions.push(transaction_info);
+        // flush if batch size or byte cap
+        if self.pending_transactions.len() >= self.transaction_batch_size || self.pending_transactions_bytes >= self.transaction_batch_max_bytes {
+            return self.flush_transactions();
+        }
+        Ok(())
+    }
+
+    fn flush_transactions(&mut self) -> Result<(), GeyserPluginError> {
+        if self.pending_transactions.is_empty() {
+            return Ok(());
+        }
+        info!(
+            "[flush_transactions] length={}/{} bytes={}/{}",
+            self.pending_transactions.len(),
+            self.transaction_batch_size,
+            self.pending_transactions_bytes,
+            self.transaction_batch_max_bytes
+        );
+        let mut pending_transactions = std::mem::replace(&mut self.pending_transactions, Vec::with_capacity(self.transaction_batch_size));
+        // written in block order whatever the order the workers received them in
+        pending_transactions.sort_by_key(|t| (t.slot, t.index));
+        self.pending_transactions_bytes = 0;
+        let slots = pending_transactions.iter().map(|t| t.slot as u64).collect::<Vec<u64>>();
+        self.write_with_retries(self.pools.routed(&self.handler_connections, TRANSACTION_TARGET), "flush_transactions", |client| {
+            self.transaction_handler.update_batch(client, &pending_transactions)
+        })?;
+        self.complete_writes(slots)
+    }
+
+    fn log_fork_event(&mut self, event: ForkEvent) -> Result<(), GeyserPluginError> {
+        self.execute_slot_query(&SlotHandler::fork_event(&event), "log_fork_event")
     }
 
     fn update_block_metadata(&mut self, block_info: DbBlockInfo) -> Result<(), GeyserPluginError> {
-        self.block_handler.update(&mut self.client.get_mut().unwrap(), block_info)
+        let slot = block_info.slot as u64;
+        self.write_with_retries(self.pools.routed(&self.handler_connections, BLOCK_TARGET), "update_block_metadata", |client| {
+            self.block_handler.update(client, &block_info)
+        })?;
+        self.complete_writes([slot])
+    }
+
+    fn update_account_view(&mut self, account: DbAccountInfo, view: CommitmentView) -> Result<(), GeyserPluginError> {
+        debug!("[update_account_view] account=[{}] slot=[{}] view=[{:?}]", account.pubkey_str(), account.slot, view);
+        let flush_span = WriteSpan::root("account_flush");
+        flush_span.set_attribute("accounts", "1".to_string());
+        flush_span.set_attribute("view", view.schema().to_string());
+        let mut queries = BTreeMap::default();
+        let build_span = flush_span.child("build_sql");
+        for h in self.selected_handlers(&account, false) {
+            self.append_account_query(&h.handler_id, &[&account], Some(view), &mut queries, &build_span)?;
+        }
+        drop(build_span);
+        match self.batch_execute_routed(queries, &flush_span, vec![account.slot as u64]) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+                msg: format!("[update_account_view] error=[{}]", err),
+            }))),
+        }
+    }
+
+    fn abandon_confirmed_slots(&mut self, slots: Vec<u64>) -> Result<(), GeyserPluginError> {
+        info!("[abandon_confirmed_slots] slots=[{:?}]", slots);
+        let query = CommitmentView::abandon_slots(&slots);
+        // the views of the handlers are on each connection they are routed to
+        let queries = self.pools.all().map(|(name, _)| (name.cloned(), AccountQuery::from(query.clone()))).collect();
+        if let Err(err) = self.batch_execute_routed(queries, &WriteSpan::root("abandon_confirmed_slots"), vec![]) {
+            return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+                msg: format!("[abandon_confirmed_slots] error=[{}]", err),
+            })));
+        }
+        Ok(())
     }
 }
 
 pub struct PostgresClientBuilder {}
 
+/// Statements creating the tables of a config
+struct InitQueries {
+    /// Creates the tables, without their non-unique indexes unless `create_indexes` is set
+    init_query: String,
+    /// Creates the tables and indexes of the exported schema, the commitment views left out
+    schema_init: String,
+    /// Creates the non-unique indexes left out of `init_query`
+    index_query: String,
+    /// Applied once after `init_query`, in version order
+    migrations: Vec<Migration>,
+}
+
 impl PostgresClientBuilder {
+    fn init_queries(config: &GeyserPluginPostgresConfig) -> Result<InitQueries, GeyserPluginError> {
+        let account_handlers = all_account_handlers(config, None, None)?;
+        let handler_init = account_handlers.values().map(|a| a.init(config)).collect::<Vec<String>>().join("");
+        let other_init = SlotHandler::init(config) + &BlockHandler::init(config) + &TransactionHandler::init(config);
+        let mut handler_migrations = account_handlers.values().flat_map(|a| a.migrations(config)).collect::<Vec<Migration>>();
+        let migrations = migrations::ordered(handler_migrations.iter().cloned().chain(SlotHandler::migrations()).collect())?;
+        let schema_init = handler_init.clone() + &other_init + &migrations.iter().map(|m| m.sql.as_str()).collect::<String>();
+        let ((handler_init, handler_indexes), (other_init, other_indexes)) = match config.create_indexes {
+            true => ((handler_init, String::new()), (other_init, String::new())),
+            false => (split_index_statements(&handler_init), split_index_statements(&other_init)),
+        };
+
+        let mut init_query = handler_init.clone();
+        let mut index_query = handler_indexes.clone();
+        // the tables of the commitment views are copies of the handler ones, left out of the exported schema
+        if config.commitment_views {
+            init_query.push_str(&CommitmentView::init(&handler_init));
+            if !handler_indexes.is_empty() {
+                index_query.push_str(&CommitmentView::in_views(&handler_indexes));
+            }
+        }
+        // the tables of the commitment views are migrated along the handler ones
+        let migrations = match config.commitment_views {
+            true => {
+                for migration in handler_migrations.iter_mut() {
+                    migration.sql.push_str(&CommitmentView::in_views(&migration.sql));
+                }
+                migrations::ordered(handler_migrations.into_iter().chain(SlotHandler::migrations()).collect())?
+            }
+            false => migrations,
+        };
+        init_query.push_str(&other_init);
+        index_query.push_str(&other_indexes);
+        if let Some(schema) = &config.schema {
+            init_query.insert_str(0, &format!("CREATE SCHEMA IF NOT EXISTS {};", schema));
+        }
+        Ok(InitQueries {
+            init_query,
+            schema_init,
+            index_query,
+            migrations,
+        })
+    }
+
+    /// Creates the non-unique indexes left out of the tables when `create_indexes` is not set, once the accounts restored
+    /// at startup are written, on the default connection and the named ones handlers are routed to, the ones the tables
+    /// were created on. A connection failing doesn't keep the others from getting their indexes, the first error being
+    /// returned once they all did
+    pub fn create_indexes(config: &GeyserPluginPostgresConfig) -> Result<(), GeyserPluginError> {
+        let InitQueries { index_query, .. } = Self::init_queries(config)?;
+        let mut measure = Measure::start("geyser-plugin-postgres-create-indexes");
+        let routed_names = config.handler_connections.values().collect::<BTreeSet<&String>>();
+        let mut result = Ok(());
+        for name in [None].into_iter().chain(routed_names.into_iter().map(Some)) {
+            let client = match name {
+                Some(name) => SimplePostgresClient::connect_to_named_db(config, name),
+                None => SimplePostgresClient::connect_to_db(config),
+            };
+            let created = client.and_then(|mut client| {
+                client.batch_execute(&index_query).map_err(|err| {
+                    GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+                        msg: format!("[create_indexes] connection={:?} error=[{}]", name, err),
+                    }))
+                })
+            });
+            if let Err(err) = created {
+                error!("[create_indexes] connection={:?} error={}", name, err);
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+        measure.stop();
+        info!("[create_indexes] created in {}ms", measure.as_ms());
+        result
+    }
+
     pub fn build_pararallel_postgres_client(config: &GeyserPluginPostgresConfig) -> Result<(ParallelClient, Option<u64>), GeyserPluginError> {
-        let mut client = SimplePostgresClient::connect_to_db(config)?;
+        validate_account_handlers(&config.accounts_selector)?;
+        validate_handler_dependencies(&config.handler_dependencies)?;
+        validate_commit_every(config)?;
+        validate_handler_connections(config)?;
+        validate_schema(config)?;
+        validate_upsert_guards(config)?;
+        // a single set of pools is shared by the workers, the schema being initialized once through them
+        let pools = ConnectionPools::new(config)?;
+        let mut client = checkout(pools.named(None))?;
 
-        let account_handlers = all_account_handlers();
-        let mut init_query = account_handlers.values().map(|a| a.init(config)).collect::<Vec<String>>().join("");
-        init_query.push_str(&SlotHandler::init(config));
-        init_query.push_str(&BlockHandler::init(config));
-        init_query.push_str(&TransactionHandler::init(config));
+        let InitQueries {
+            init_query, schema_init, migrations, ..
+        } = Self::init_queries(config)?;
         if let Err(err) = client.batch_execute(&init_query) {
             return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
                 msg: format!("[build_pararallel_postgres_client] error=[{}]", err),
             })));
         };
+        migrations::apply(&mut client, &migrations)?;
+        if let Some(path) = &config.schema_export_path {
+            let schema = schema_export::export_schema(&mut client, &schema_init).map_err(|err| err.to_string());
+            if let Err(err) = schema.and_then(|schema| std::fs::write(path, format!("{:#}", schema)).map_err(|err| err.to_string())) {
+                return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+                    msg: format!("[build_pararallel_postgres_client] failed to export the schema to {} error=[{}]", path, err),
+                })));
+            }
+            info!("[build_pararallel_postgres_client] schema exported path={}", path);
+        }
+
+        // routed handlers need their tables on every target connection
+        for (name, pool) in pools.all().filter_map(|(name, pool)| name.map(|name| (name, pool))) {
+            let mut client = checkout(pool)?;
+            if let Err(err) = client.batch_execute(&init_query) {
+                return Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+                    msg: format!("[build_pararallel_postgres_client] connection={} error=[{}]", name, err),
+                })));
+            };
+            migrations::apply(&mut client, &migrations)?;
+        }
 
+        drop(client);
         let batch_starting_slot = match config.skip_upsert_existing_accounts_at_startup {
             true => {
+                // the slots are read from the connection they are written to
+                let mut client = checkout(pools.routed(&config.handler_connections, SLOT_TARGET))?;
                 let batch_slot_bound = SlotHandler::get_highest_available_slot(&mut client)?.saturating_sub(config.safe_batch_starting_slot_cushion);
                 info!("[batch_starting_slot] bound={}", batch_slot_bound);
                 Some(batch_slot_bound)
@@ -308,6 +1454,6 @@ impl PostgresClientBuilder {
             false => None,
         };
 
-        ParallelClient::new(config).map(|v| (v, batch_starting_slot))
+        ParallelClient::new(config, pools).map(|v| (v, batch_starting_slot))
     }
 }
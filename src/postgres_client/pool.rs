@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use log::*;
+use postgres::Client;
+use postgres::Statement;
+use r2d2::ManageConnection;
+use r2d2::Pool;
+use r2d2::PooledConnection;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
+use solana_metrics::*;
+
+use crate::config::GeyserPluginPostgresConfig;
+use crate::geyser_plugin_postgres::GeyserPluginPostgresError;
+
+use super::SimplePostgresClient;
+
+/// A connection of a pool, with the statements prepared on it. A statement is only valid on the connection it was
+/// prepared on, so each connection prepares its own on first use
+pub struct PooledClient {
+    /// Taken when the connection fails its probe, to be closed in the background, or once it is found lost
+    client: Option<Client>,
+    statements: HashMap<String, Statement>,
+    /// When the connection was last returned to the pool
+    last_used: Instant,
+}
+
+impl PooledClient {
+    /// The statement of `query` prepared on this connection, preparing it on first use
+    pub fn prepare_cached(&mut self, query: &str) -> Result<Statement, postgres::Error> {
+        if let Some(statement) = self.statements.get(query) {
+            return Ok(statement.clone());
+        }
+        let statement = self.prepare(query)?;
+        self.statements.insert(query.to_string(), statement.clone());
+        Ok(statement)
+    }
+
+    /// Closes a connection found lost, the pool replacing it once it is returned. The sync client only notices the
+    /// connection is gone when it is used, which a checkout does not do
+    pub fn discard(&mut self) {
+        self.client.take();
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        self.client.as_ref().expect("connection closed after its failed probe")
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Client {
+        self.client.as_mut().expect("connection closed after its failed probe")
+    }
+}
+
+/// Checkouts of a pool since its utilization was last reported
+#[derive(Default)]
+pub struct PoolMetrics {
+    /// Checkouts waiting for a connection
+    waiting: AtomicUsize,
+    checkouts: AtomicU64,
+    wait_us: AtomicU64,
+    max_wait_us: AtomicU64,
+}
+
+impl PoolMetrics {
+    fn record_checkout(&self, wait_us: u64) {
+        self.checkouts.fetch_add(1, Ordering::Relaxed);
+        self.wait_us.fetch_add(wait_us, Ordering::Relaxed);
+        self.max_wait_us.fetch_max(wait_us, Ordering::Relaxed);
+    }
+}
+
+/// Utilization of a pool, the checkouts being counted since the previous report
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolUtilization {
+    /// Name of the connection of the pool, `None` for the default one
+    pub name: Option<String>,
+    pub size: u32,
+    pub in_use: u32,
+    pub idle: u32,
+    /// Checkouts waiting for a connection, all of them being in use
+    pub waiting: usize,
+    pub checkouts: u64,
+    /// Total time the checkouts waited for a connection
+    pub wait_us: u64,
+    pub max_wait_us: u64,
+}
+
+/// Opens the connections of a pool as `connect_to_db` does, to `connection_str` or one of the named `connections`
+pub struct ConnectionManager {
+    config: GeyserPluginPostgresConfig,
+    name: Option<String>,
+    /// Idle time past which a connection is probed when checked out, `idle_probe_ms`
+    idle_probe: Option<Duration>,
+    /// Timeout of the probe, `idle_probe_timeout_ms`
+    idle_probe_timeout: Duration,
+}
+
+impl ManageConnection for ConnectionManager {
+    type Connection = PooledClient;
+    type Error = GeyserPluginError;
+
+    fn connect(&self) -> Result<PooledClient, GeyserPluginError> {
+        let client = match &self.name {
+            Some(name) => SimplePostgresClient::connect_to_named_db(&self.config, name)?,
+            None => SimplePostgresClient::connect_to_db(&self.config)?,
+        };
+        Ok(PooledClient {
+            client: Some(client),
+            statements: HashMap::default(),
+            last_used: Instant::now(),
+        })
+    }
+
+    /// Checks a connection before it is checked out, without a round trip to the server unless `idle_probe_ms` is set:
+    /// the connections idle for longer are then probed, with a timeout, so that a connection whose server is gone is
+    /// replaced before a write waits on it. A connection lost otherwise fails its write, which is retried on a new one
+    fn is_valid(&self, connection: &mut PooledClient) -> Result<(), GeyserPluginError> {
+        let result = match self.idle_probe {
+            Some(idle_probe) if connection.last_used.elapsed() >= idle_probe => {
+                inc_new_counter_info!("geyser-plugin-postgres-idle-connection-probes", 1);
+                connection.is_valid(self.idle_probe_timeout).map_err(|err| {
+                    warn!(
+                        "[is_valid] idle connection failed its probe, replacing it idle_ms={} error={}",
+                        connection.last_used.elapsed().as_millis(),
+                        err
+                    );
+                    // closing the connection waits for the answer of the probe, which may never come
+                    if let Some(client) = connection.client.take() {
+                        std::thread::spawn(move || drop(client));
+                    }
+                    err.to_string()
+                })
+            }
+            _ => match connection.is_closed() {
+                true => Err("connection closed".to_string()),
+                false => Ok(()),
+            },
+        };
+        result.map_err(|err| {
+            GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::ConnectionError {
+                msg: format!("[is_valid] error=[{}]", err),
+            }))
+        })
+    }
+
+    /// Called when a connection is returned to the pool, from which it is idle
+    fn has_broken(&self, connection: &mut PooledClient) -> bool {
+        connection.last_used = Instant::now();
+        connection.client.as_ref().is_none_or(Client::is_closed)
+    }
+}
+
+/// A pool with the metrics of its checkouts
+#[derive(Clone)]
+pub struct ConnectionPool {
+    pool: Pool<ConnectionManager>,
+    metrics: Arc<PoolMetrics>,
+}
+
+impl Deref for ConnectionPool {
+    type Target = Pool<ConnectionManager>;
+
+    fn deref(&self) -> &Pool<ConnectionManager> {
+        &self.pool
+    }
+}
+
+/// The pools of `connection_str` and of the named `connections` handlers are routed to, shared by the workers.
+/// Cloning them shares the same connections
+#[derive(Clone)]
+pub struct ConnectionPools {
+    pool: ConnectionPool,
+    named_pools: HashMap<String, ConnectionPool>,
+}
+
+impl ConnectionPools {
+    /// Opens the pools of the config, each holding up to `pool_size` connections, by default one per worker and
+    /// category grouped by `commit_every`
+    pub fn new(config: &GeyserPluginPostgresConfig) -> Result<Self, GeyserPluginError> {
+        let pool = Self::build_pool(config, None)?;
+        let mut named_pools = HashMap::default();
+        for name in config.handler_connections.values() {
+            if !named_pools.contains_key(name) {
+                named_pools.insert(name.clone(), Self::build_pool(config, Some(name.clone()))?);
+            }
+        }
+        Ok(Self { pool, named_pools })
+    }
+
+    fn build_pool(config: &GeyserPluginPostgresConfig, name: Option<String>) -> Result<ConnectionPool, GeyserPluginError> {
+        let worker_count = config.threads.max(config.startup_worker_count.unwrap_or_default());
+        // a worker holds a connection for each category whose commits are grouped, on top of the one of its writes
+        let connections_per_worker = 1 + config.commit_every.len();
+        let pool_size = config.pool_size.unwrap_or((worker_count * connections_per_worker) as u32).max(1);
+        let manager = ConnectionManager {
+            config: config.clone(),
+            name,
+            idle_probe: config.idle_probe_ms.map(Duration::from_millis),
+            idle_probe_timeout: Duration::from_millis(config.idle_probe_timeout_ms),
+        };
+        // a pool only reports the errors of its connections once a checkout times out, the settings are checked
+        // with a first connection instead. The connections are then opened on demand and closed once idle
+        manager.connect()?;
+        Ok(ConnectionPool {
+            pool: Pool::builder().max_size(pool_size).min_idle(Some(0)).build_unchecked(manager),
+            metrics: Arc::new(PoolMetrics::default()),
+        })
+    }
+
+    /// The pool the writes of `target`, a handler id or a category, are routed to through `handler_connections`
+    pub fn routed(&self, handler_connections: &HashMap<String, String>, target: &str) -> &ConnectionPool {
+        self.named(handler_connections.get(target))
+    }
+
+    /// The pool of the named connection, `None` being the default connection
+    pub fn named(&self, name: Option<&String>) -> &ConnectionPool {
+        match name.and_then(|name| self.named_pools.get(name)) {
+            Some(pool) => pool,
+            None => &self.pool,
+        }
+    }
+
+    /// The pools of the default connection and of the named ones, by name
+    pub fn all(&self) -> impl Iterator<Item = (Option<&String>, &ConnectionPool)> {
+        std::iter::once((None, &self.pool)).chain(self.named_pools.iter().map(|(name, pool)| (Some(name), pool)))
+    }
+
+    /// The utilization of the pools, the checkouts counted since the previous report
+    pub fn utilization(&self) -> Vec<PoolUtilization> {
+        self.all()
+            .map(|(name, pool)| {
+                let state = pool.state();
+                let metrics = &pool.metrics;
+                PoolUtilization {
+                    name: name.cloned(),
+                    size: pool.max_size(),
+                    in_use: state.connections - state.idle_connections,
+                    idle: state.idle_connections,
+                    waiting: metrics.waiting.load(Ordering::Relaxed),
+                    checkouts: metrics.checkouts.load(Ordering::Relaxed),
+                    wait_us: metrics.wait_us.load(Ordering::Relaxed),
+                    max_wait_us: metrics.max_wait_us.load(Ordering::Relaxed),
+                }
+            })
+            .collect()
+    }
+
+    /// Reports the utilization of each pool as the `geyser_plugin_postgres_pool` metric, tagged with its connection,
+    /// then restarts counting the checkouts
+    pub fn report(&self) {
+        for utilization in self.utilization() {
+            datapoint_info!(
+                "geyser_plugin_postgres_pool",
+                "connection" => utilization.name.as_deref().unwrap_or("default"),
+                ("size", utilization.size, i64),
+                ("in-use", utilization.in_use, i64),
+                ("idle", utilization.idle, i64),
+                ("waiting", utilization.waiting, i64),
+                ("checkouts", utilization.checkouts, i64),
+                ("wait-us", utilization.wait_us, i64),
+                ("max-wait-us", utilization.max_wait_us, i64),
+            );
+        }
+        for (_, pool) in self.all() {
+            let metrics = &pool.metrics;
+            metrics.checkouts.store(0, Ordering::Relaxed);
+            metrics.wait_us.store(0, Ordering::Relaxed);
+            metrics.max_wait_us.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Checks a connection out of `pool` for the duration of an operation
+pub fn checkout(pool: &ConnectionPool) -> Result<PooledConnection<ConnectionManager>, GeyserPluginError> {
+    let metrics = &pool.metrics;
+    metrics.waiting.fetch_add(1, Ordering::Relaxed);
+    let waited = Instant::now();
+    let connection = pool.get();
+    metrics.waiting.fetch_sub(1, Ordering::Relaxed);
+    metrics.record_checkout(waited.elapsed().as_micros() as u64);
+    connection.map_err(|err| {
+        GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::ConnectionError {
+            msg: format!("[checkout] error=[{}]", err),
+        }))
+    })
+}
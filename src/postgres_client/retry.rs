@@ -0,0 +1,42 @@
+use crate::config::GeyserPluginPostgresConfig;
+use crate::config::RetryJitter;
+use rand::Rng;
+use std::time::Duration;
+
+/// Exponential backoff between connection attempts, with jitter so that workers
+/// losing their connection at the same time do not all retry at the same instants
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub jitter: RetryJitter,
+}
+
+impl RetryPolicy {
+    pub fn new(config: &GeyserPluginPostgresConfig) -> Self {
+        Self {
+            retries: config.connect_retries,
+            base_delay_ms: config.retry_base_delay_ms,
+            max_delay_ms: config.retry_max_delay_ms,
+            jitter: config.retry_jitter,
+        }
+    }
+
+    /// The backoff window of the retry following the failed `attempt`, starting at 0
+    pub fn backoff_ms(&self, attempt: u32) -> u64 {
+        let factor = 1_u64.checked_shl(attempt).unwrap_or(u64::MAX);
+        self.base_delay_ms.saturating_mul(factor).min(self.max_delay_ms)
+    }
+
+    /// The delay before the retry following the failed `attempt`, starting at 0
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.backoff_ms(attempt);
+        let delay = match self.jitter {
+            RetryJitter::None => backoff,
+            RetryJitter::Full => rand::thread_rng().gen_range(0..=backoff),
+            RetryJitter::Equal => backoff / 2 + rand::thread_rng().gen_range(0..=backoff - backoff / 2),
+        };
+        Duration::from_millis(delay)
+    }
+}
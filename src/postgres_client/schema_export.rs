@@ -0,0 +1,139 @@
+//! Machine-readable description of the tables the handlers of a config create, for external migration tools
+
+use postgres::Client;
+use serde_json::json;
+
+/// Schema the handler tables are created in to be described, rolled back once done
+const EXPORT_SCHEMA: &str = "geyser_schema_export";
+
+/// Describes the tables, views and types `init_query` creates as JSON. The statements run in a transaction rolled
+/// back once described, in a scratch schema searched before the current one, whose types the tables may use
+pub fn export_schema(client: &mut Client, init_query: &str) -> Result<serde_json::Value, postgres::Error> {
+    let mut transaction = client.transaction()?;
+    let current_schema: String = transaction.query_one("SELECT current_schema()", &[])?.get(0);
+    transaction.batch_execute(&format!("CREATE SCHEMA {0}; SET LOCAL search_path TO {0}, \"{1}\";", EXPORT_SCHEMA, current_schema))?;
+    transaction.batch_execute(init_query)?;
+    // the definitions are qualified with the scratch schema, which the exported ones are not
+    let unqualified = |definition: String| definition.replace(&format!("{}.", EXPORT_SCHEMA), "");
+
+    let mut tables = vec![];
+    for table in transaction.query(
+        "SELECT c.oid, c.relname FROM pg_class c WHERE c.relnamespace = $1::TEXT::regnamespace AND c.relkind IN ('r', 'p') ORDER BY c.relname",
+        &[&EXPORT_SCHEMA],
+    )? {
+        let oid: u32 = table.get(0);
+        let name: String = table.get(1);
+        let columns = transaction
+            .query(
+                "
+                    SELECT a.attname, format_type(a.atttypid, a.atttypmod), NOT a.attnotnull, pg_get_expr(d.adbin, d.adrelid)
+                    FROM pg_attribute a LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+                    WHERE a.attrelid = $1 AND a.attnum > 0 AND NOT a.attisdropped ORDER BY a.attnum
+                ",
+                &[&oid],
+            )?
+            .into_iter()
+            .map(|column| {
+                json!({
+                    "name": column.get::<_, String>(0),
+                    "type": column.get::<_, String>(1),
+                    "nullable": column.get::<_, bool>(2),
+                    "default": column.get::<_, Option<String>>(3),
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+        let indexes = transaction
+            .query(
+                "
+                    SELECT c.relname, i.indisunique, i.indisprimary, pg_get_indexdef(i.indexrelid)
+                    FROM pg_index i JOIN pg_class c ON c.oid = i.indexrelid WHERE i.indrelid = $1 ORDER BY c.relname
+                ",
+                &[&oid],
+            )?
+            .into_iter()
+            .map(|index| {
+                json!({
+                    "name": index.get::<_, String>(0),
+                    "unique": index.get::<_, bool>(1),
+                    "primary": index.get::<_, bool>(2),
+                    "definition": unqualified(index.get(3)),
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+        let constraints = transaction
+            .query(
+                "
+                    SELECT conname, CASE contype WHEN 'p' THEN 'primary_key' WHEN 'u' THEN 'unique' WHEN 'f' THEN 'foreign_key'
+                        WHEN 'c' THEN 'check' WHEN 'x' THEN 'exclusion' ELSE contype::TEXT END, pg_get_constraintdef(oid)
+                    FROM pg_constraint WHERE conrelid = $1 ORDER BY conname
+                ",
+                &[&oid],
+            )?
+            .into_iter()
+            .map(|constraint| {
+                json!({
+                    "name": constraint.get::<_, String>(0),
+                    "type": constraint.get::<_, String>(1),
+                    "definition": unqualified(constraint.get(2)),
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+        tables.push(json!({ "name": name, "columns": columns, "indexes": indexes, "constraints": constraints }));
+    }
+
+    let views = transaction
+        .query(
+            "SELECT c.relname, pg_get_viewdef(c.oid) FROM pg_class c WHERE c.relnamespace = $1::TEXT::regnamespace AND c.relkind = 'v' ORDER BY c.relname",
+            &[&EXPORT_SCHEMA],
+        )?
+        .into_iter()
+        .map(|view| json!({ "name": view.get::<_, String>(0), "definition": unqualified(view.get(1)) }))
+        .collect::<Vec<serde_json::Value>>();
+
+    // the enums and composite types the columns use, through arrays and the attributes of composite types
+    let mut types = vec![];
+    for user_type in transaction.query(
+        "
+            WITH RECURSIVE used(oid) AS (
+                SELECT a.atttypid FROM pg_attribute a JOIN pg_class c ON c.oid = a.attrelid
+                WHERE c.relnamespace = $1::TEXT::regnamespace AND a.attnum > 0 AND NOT a.attisdropped
+                UNION
+                SELECT element.oid FROM used JOIN pg_type t ON t.oid = used.oid CROSS JOIN LATERAL (
+                    SELECT t.typelem WHERE t.typelem <> 0
+                    UNION ALL
+                    SELECT a.atttypid FROM pg_attribute a WHERE t.typrelid <> 0 AND a.attrelid = t.typrelid AND a.attnum > 0 AND NOT a.attisdropped
+                ) element(oid)
+            )
+            SELECT t.oid, t.typname, t.typtype::TEXT, t.typrelid FROM used JOIN pg_type t ON t.oid = used.oid
+            LEFT JOIN pg_class c ON c.oid = t.typrelid
+            WHERE t.typtype = 'e' OR (t.typtype = 'c' AND c.relkind = 'c') ORDER BY t.typname
+        ",
+        &[&EXPORT_SCHEMA],
+    )? {
+        let name: String = user_type.get(1);
+        let user_type = match user_type.get::<_, String>(2).as_str() {
+            "e" => {
+                let values = transaction
+                    .query("SELECT enumlabel::TEXT FROM pg_enum WHERE enumtypid = $1 ORDER BY enumsortorder", &[&user_type.get::<_, u32>(0)])?
+                    .into_iter()
+                    .map(|value| value.get::<_, String>(0))
+                    .collect::<Vec<String>>();
+                json!({ "name": name, "kind": "enum", "values": values })
+            }
+            _ => {
+                let attributes = transaction
+                    .query(
+                        "SELECT attname, format_type(atttypid, atttypmod) FROM pg_attribute WHERE attrelid = $1 AND attnum > 0 AND NOT attisdropped ORDER BY attnum",
+                        &[&user_type.get::<_, u32>(3)],
+                    )?
+                    .into_iter()
+                    .map(|attribute| json!({ "name": attribute.get::<_, String>(0), "type": attribute.get::<_, String>(1) }))
+                    .collect::<Vec<serde_json::Value>>();
+                json!({ "name": name, "kind": "composite", "attributes": attributes })
+            }
+        };
+        types.push(user_type);
+    }
+    transaction.rollback()?;
+    Ok(json!({ "tables": tables, "views": views, "types": types }))
+}
@@ -0,0 +1,49 @@
+use crate::locks;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Default)]
+struct SlotProgress {
+    /// Writes dispatched to the workers and not committed yet
+    pending_writes: usize,
+    /// Whether the slot rooted, after which no more writes are dispatched for it
+    rooted: bool,
+}
+
+/// Tracks the in-flight writes of each slot across the workers to tell when a rooted slot is fully committed.
+/// A slot only has an entry while some of its writes are in flight.
+#[derive(Default)]
+pub struct SlotCompletion {
+    slots: Mutex<HashMap<u64, SlotProgress>>,
+}
+
+impl SlotCompletion {
+    /// Records a write of `slot` dispatched to a worker
+    pub fn begin_write(&self, slot: u64) {
+        locks::lock(&self.slots, "slot_completion").entry(slot).or_default().pending_writes += 1;
+    }
+
+    /// Records a committed write of `slot`, returns true if it was the last one of a rooted slot
+    pub fn end_write(&self, slot: u64) -> bool {
+        let mut slots = locks::lock(&self.slots, "slot_completion");
+        let Some(progress) = slots.get_mut(&slot) else {
+            return false;
+        };
+        progress.pending_writes = progress.pending_writes.saturating_sub(1);
+        if progress.pending_writes > 0 {
+            return false;
+        }
+        slots.remove(&slot).is_some_and(|progress| progress.rooted)
+    }
+
+    /// Records `slot` rooted, returns true if none of its writes are in flight
+    pub fn root(&self, slot: u64) -> bool {
+        match locks::lock(&self.slots, "slot_completion").get_mut(&slot) {
+            Some(progress) => {
+                progress.rooted = true;
+                false
+            }
+            None => true,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use super::migrations::Migration;
 use chrono::Utc;
 use postgres::Client;
 use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
@@ -5,30 +6,129 @@ use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
 
 pub struct SlotHandler {}
 
+/// Slots abandoned when a later slot rooted without them among its ancestors
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ForkEvent {
+    pub rooted_slot: u64,
+    /// Abandoned slots, in ascending order
+    pub slots: Vec<u64>,
+    /// Transactions held back until their slot rooted, dropped with it
+    pub dropped_transactions: usize,
+}
+
 impl SlotHandler {
-    pub fn init(_config: &crate::config::GeyserPluginPostgresConfig) -> String {
-        return "
+    /// Changes of the slot tables of earlier versions, applied once after the init
+    pub fn migrations() -> Vec<Migration> {
+        vec![Migration::new(
+            1,
+            "widen slot.status to the names of the statuses of later interface versions, e.g. first_shred_received",
+            "ALTER TABLE slot ALTER COLUMN status TYPE VARCHAR(32);",
+        )]
+    }
+
+    pub fn init(config: &crate::config::GeyserPluginPostgresConfig) -> String {
+        let mut init = "
             CREATE TABLE IF NOT EXISTS slot (
                 slot BIGINT PRIMARY KEY,
                 parent BIGINT,
-                status VARCHAR(16) NOT NULL,
+                status VARCHAR(32) NOT NULL,
                 updated_on TIMESTAMP NOT NULL
             );
+            ALTER TABLE slot ADD COLUMN IF NOT EXISTS instance_id VARCHAR(64);
+            ALTER TABLE slot ADD COLUMN IF NOT EXISTS epoch BIGINT;
+            CREATE INDEX IF NOT EXISTS slot_epoch ON slot (epoch);
         "
         .to_string();
+        if config.write_slot_complete {
+            init.push_str(
+                "
+                CREATE TABLE IF NOT EXISTS slot_complete (
+                    slot BIGINT PRIMARY KEY,
+                    completed_at TIMESTAMP NOT NULL
+                );
+            ",
+            );
+        }
+        if config.write_fork_events {
+            init.push_str(
+                "
+                CREATE TABLE IF NOT EXISTS fork_event (
+                    id BIGSERIAL PRIMARY KEY,
+                    rooted_slot BIGINT NOT NULL,
+                    first_slot BIGINT NOT NULL,
+                    last_slot BIGINT NOT NULL,
+                    slots BIGINT[] NOT NULL,
+                    dropped_transactions BIGINT NOT NULL,
+                    detected_on TIMESTAMP NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS fork_event_rooted_slot ON fork_event (rooted_slot);
+            ",
+            );
+        }
+        init
     }
 
-    pub fn update(slot: u64, parent: Option<u64>, status: SlotStatus) -> String {
+    /// `instance_id` identifies the plugin instance, and so the validator, which observed the slot. The status is
+    /// stored by its name, whatever the variant
+    pub fn update(slot: u64, parent: Option<u64>, status: SlotStatus, epoch: u64, instance_id: Option<&str>) -> String {
         format!(
             "
-                INSERT INTO slot (slot, parent, status, updated_on) \
-                VALUES ({0}, {1}, '{2}', '{3}') \
-                ON CONFLICT (slot) DO UPDATE SET parent=excluded.parent, status=excluded.status, updated_on=excluded.updated_on;
+                INSERT INTO slot (slot, parent, status, updated_on, instance_id, epoch) \
+                VALUES ({0}, {1}, '{2}', '{3}', {4}, {5}) \
+                ON CONFLICT (slot) DO UPDATE SET parent=excluded.parent, status=excluded.status, updated_on=excluded.updated_on, instance_id=excluded.instance_id, epoch=excluded.epoch;
             ",
             &slot,
             parent.map_or("NULL".to_string(), |p| p.to_string()),
             &status.as_str(),
-            &Utc::now().naive_utc()
+            &Utc::now().naive_utc(),
+            instance_id.map_or("NULL".to_string(), |id| format!("'{}'", id.replace('\'', "''"))),
+            &epoch,
+        )
+    }
+
+    /// Writes the slots, given with their epoch, with a single statement
+    pub fn update_many(slots: &[(u64, u64)], status: SlotStatus, instance_id: Option<&str>) -> String {
+        let updated_on = Utc::now().naive_utc();
+        let instance_id = instance_id.map_or("NULL".to_string(), |id| format!("'{}'", id.replace('\'', "''")));
+        format!(
+            "
+                INSERT INTO slot (slot, parent, status, updated_on, instance_id, epoch) \
+                VALUES {0} \
+                ON CONFLICT (slot) DO UPDATE SET parent=excluded.parent, status=excluded.status, updated_on=excluded.updated_on, instance_id=excluded.instance_id, epoch=excluded.epoch;
+            ",
+            slots
+                .iter()
+                .map(|(slot, epoch)| format!("({}, NULL, '{}', '{}', {}, {})", slot, status.as_str(), updated_on, instance_id, epoch))
+                .collect::<Vec<String>>()
+                .join(", "),
+        )
+    }
+
+    /// Marks `slot` fully written: rooted and all its account, transaction and block writes committed
+    pub fn complete(slot: u64) -> String {
+        format!(
+            "
+                INSERT INTO slot_complete (slot, completed_at) \
+                VALUES ({0}, '{1}') \
+                ON CONFLICT (slot) DO NOTHING;
+            ",
+            &slot,
+            &Utc::now().naive_utc(),
+        )
+    }
+
+    pub fn fork_event(event: &ForkEvent) -> String {
+        format!(
+            "
+                INSERT INTO fork_event (rooted_slot, first_slot, last_slot, slots, dropped_transactions, detected_on) \
+                VALUES ({0}, {1}, {2}, ARRAY[{3}]::BIGINT[], {4}, '{5}');
+            ",
+            &event.rooted_slot,
+            event.slots.first().unwrap_or(&0),
+            event.slots.last().unwrap_or(&0),
+            event.slots.iter().map(|s| s.to_string()).collect::<Vec<String>>().join(","),
+            &event.dropped_transactions,
+            &Utc::now().naive_utc(),
         )
     }
 
@@ -46,3 +146,16 @@ impl SlotHandler {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_status_name() {
+        for status in [SlotStatus::Processed, SlotStatus::Confirmed, SlotStatus::Rooted] {
+            let query = SlotHandler::update(1, None, status, 0, None);
+            assert!(query.contains(&format!("'{}'", status.as_str())), "Status {:?} not stored by its name", status);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use log::*;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
+use solana_metrics::*;
+
+use super::SimplePostgresClient;
+use super::SLOT_TARGET;
+use crate::config::GeyserPluginPostgresConfig;
+use crate::geyser_plugin_postgres::GeyserPluginPostgresError;
+
+/// Accounts notified during startup, checked against what the workers flushed once startup ends
+pub struct StartupIntegrity {
+    config: GeyserPluginPostgresConfig,
+    notified_accounts: usize,
+    slots: HashSet<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct StartupIntegrityReport {
+    pub notified_accounts: usize,
+    pub flushed_accounts: usize,
+    /// Distinct slots of the startup accounts, each of them is written rooted at the end of startup
+    pub expected_rooted_slots: usize,
+    pub rooted_slots: usize,
+}
+
+impl StartupIntegrityReport {
+    pub fn is_complete(&self) -> bool {
+        self.notified_accounts == self.flushed_accounts && self.expected_rooted_slots == self.rooted_slots
+    }
+}
+
+impl StartupIntegrity {
+    pub fn new(config: &GeyserPluginPostgresConfig) -> Self {
+        Self {
+            config: config.clone(),
+            notified_accounts: 0,
+            slots: HashSet::default(),
+        }
+    }
+
+    pub fn record_account(&mut self, slot: u64) {
+        self.notified_accounts += 1;
+        self.slots.insert(slot);
+    }
+
+    /// Counts the rooted startup slots in the database and records the report in `startup_integrity_report`
+    pub fn report(&self, flushed_accounts: usize) -> Result<StartupIntegrityReport, GeyserPluginError> {
+        let mut client = match self.config.handler_connections.get(SLOT_TARGET) {
+            Some(name) => SimplePostgresClient::connect_to_named_db(&self.config, name)?,
+            None => SimplePostgresClient::connect_to_db(&self.config)?,
+        };
+        let slots = self.slots.iter().map(|slot| *slot as i64).collect::<Vec<i64>>();
+        let rooted_slots: i64 = client
+            .query_one("SELECT COUNT(*) FROM slot WHERE slot = ANY($1) AND status = 'rooted'", &[&slots])
+            .map_err(|err| report_error(&err))?
+            .get(0);
+        let report = StartupIntegrityReport {
+            notified_accounts: self.notified_accounts,
+            flushed_accounts,
+            expected_rooted_slots: self.slots.len(),
+            rooted_slots: rooted_slots as usize,
+        };
+        client
+            .batch_execute(&format!(
+                "
+                    CREATE TABLE IF NOT EXISTS startup_integrity_report (
+                        reported_on TIMESTAMP NOT NULL,
+                        notified_accounts BIGINT NOT NULL,
+                        flushed_accounts BIGINT NOT NULL,
+                        expected_rooted_slots BIGINT NOT NULL,
+                        rooted_slots BIGINT NOT NULL,
+                        instance_id VARCHAR(64)
+                    );
+                    INSERT INTO startup_integrity_report (reported_on, notified_accounts, flushed_accounts, expected_rooted_slots, rooted_slots, instance_id) \
+                    VALUES ('{0}', {1}, {2}, {3}, {4}, {5});
+                ",
+                &Utc::now().naive_utc(),
+                report.notified_accounts,
+                report.flushed_accounts,
+                report.expected_rooted_slots,
+                report.rooted_slots,
+                self.config.instance_id.as_ref().map_or("NULL".to_string(), |id| format!("'{}'", id.replace('\'', "''"))),
+            ))
+            .map_err(|err| report_error(&err))?;
+
+        match report.is_complete() {
+            true => info!("[startup_integrity] complete report=[{:?}]", report),
+            false => warn!("[startup_integrity] discrepancy report=[{:?}]", report),
+        }
+        datapoint_info!(
+            "geyser_plugin_startup_integrity",
+            ("notified-accounts", report.notified_accounts, i64),
+            ("flushed-accounts", report.flushed_accounts, i64),
+            ("missing-accounts", report.notified_accounts.saturating_sub(report.flushed_accounts), i64),
+            ("expected-rooted-slots", report.expected_rooted_slots, i64),
+            ("missing-rooted-slots", report.expected_rooted_slots.saturating_sub(report.rooted_slots), i64),
+        );
+        Ok(report)
+    }
+}
+
+fn report_error(err: &postgres::Error) -> GeyserPluginError {
+    GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
+        msg: format!("[startup_integrity] error=[{}]", err),
+    }))
+}
@@ -0,0 +1,63 @@
+use std::mem::size_of;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use solana_metrics::datapoint_info;
+use solana_sdk::timing::AtomicInterval;
+
+use super::DbAccountInfo;
+
+/// Interval of the `geyser_plugin_startup_memory` metric
+const STARTUP_MEMORY_REPORT_MS: u64 = 1000;
+
+/// Estimate of the memory held by the startup accounts notified and not written yet, in the channel to the workers
+/// or their buffers, bounded by `startup_memory_budget_bytes`. The workers flush their buffers once the estimate
+/// reaches the flush threshold, a share of the budget, and the notifications wait while it is over the budget.
+pub struct StartupMemory {
+    budget: usize,
+    bytes: AtomicUsize,
+    last_report: AtomicInterval,
+}
+
+impl StartupMemory {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            bytes: AtomicUsize::default(),
+            last_report: AtomicInterval::default(),
+        }
+    }
+
+    /// Estimated memory of an account, its fields and its data
+    pub fn account_bytes(account: &DbAccountInfo) -> usize {
+        size_of::<DbAccountInfo>() + account.pubkey.len() + account.owner.len() + account.data.len() + account.txn_signature.as_ref().map_or(0, |signature| signature.len())
+    }
+
+    /// Records an account notified at startup
+    pub fn reserve(&self, bytes: usize) {
+        let total = self.bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        if self.last_report.should_update(STARTUP_MEMORY_REPORT_MS) {
+            datapoint_info!("geyser_plugin_startup_memory", ("bytes", total, i64), ("budget", self.budget, i64));
+        }
+    }
+
+    /// Records accounts written or dropped
+    pub fn release(&self, bytes: usize) {
+        let _ = self.bytes.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |total| Some(total.saturating_sub(bytes)));
+    }
+
+    /// Current estimate
+    pub fn bytes(&self) -> usize {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Whether the buffered accounts should be flushed, the estimate having reached 3/4 of the budget
+    pub fn should_flush(&self) -> bool {
+        self.bytes() >= self.budget / 4 * 3
+    }
+
+    /// Whether the notifications should wait for the workers to write the accounts
+    pub fn is_exceeded(&self) -> bool {
+        self.bytes() >= self.budget
+    }
+}
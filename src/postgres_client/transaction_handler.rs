@@ -1,14 +1,33 @@
 use crate::config::GeyserPluginPostgresConfig;
+use crate::config::MemoIndex;
+use crate::config::SynchronousCommit;
+use crate::config::TransactionSlotConflict;
+use crate::config::WriteCategory;
 use crate::geyser_plugin_postgres::GeyserPluginPostgresError;
+use crate::postgres_client::cnft_event_handler::CnftEventHandler;
+use crate::postgres_client::pool::PooledClient;
+use crate::postgres_client::write_error;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytes::BytesMut;
 use chrono::Utc;
 use log::*;
-use postgres::Client;
+use postgres::GenericClient;
 use postgres::Statement;
+use postgres_types::to_sql_checked;
 use postgres_types::FromSql;
+use postgres_types::IsNull;
 use postgres_types::ToSql;
+use postgres_types::Type;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use rayon::ThreadPoolBuilder;
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
 use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
 use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
 use solana_runtime::bank::RewardType;
+use solana_sdk::epoch_schedule::EpochSchedule;
 use solana_sdk::instruction::CompiledInstruction;
 use solana_sdk::message::v0::LoadedAddresses;
 use solana_sdk::message::v0::MessageAddressTableLookup;
@@ -16,15 +35,23 @@ use solana_sdk::message::v0::{self};
 use solana_sdk::message::Message;
 use solana_sdk::message::MessageHeader;
 use solana_sdk::message::SanitizedMessage;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::transaction::TransactionError;
+use solana_sdk::transaction::TransactionVersion;
 use solana_transaction_status::InnerInstructions;
 use solana_transaction_status::Reward;
 use solana_transaction_status::TransactionStatusMeta;
 use solana_transaction_status::TransactionTokenBalance;
+use std::collections::BTreeMap;
+use std::error::Error;
 
 const MAX_TRANSACTION_STATUS_LEN: usize = 256;
 
-#[derive(Clone, Debug, FromSql, ToSql)]
+pub static MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+pub static MEMO_V1_PROGRAM_ID: Pubkey = pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+
+#[derive(Clone, Debug, PartialEq, FromSql, ToSql, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "CompiledInstruction")]
 pub struct DbCompiledInstruction {
     pub program_id_index: i16,
@@ -32,14 +59,14 @@ pub struct DbCompiledInstruction {
     pub data: Vec<u8>,
 }
 
-#[derive(Clone, Debug, FromSql, ToSql)]
+#[derive(Clone, Debug, PartialEq, FromSql, ToSql, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "InnerInstructions")]
 pub struct DbInnerInstructions {
     pub index: i16,
     pub instructions: Vec<DbCompiledInstruction>,
 }
 
-#[derive(Clone, Debug, FromSql, ToSql)]
+#[derive(Clone, Debug, PartialEq, FromSql, ToSql, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "TransactionTokenBalance")]
 pub struct DbTransactionTokenBalance {
     pub account_index: i16,
@@ -48,7 +75,7 @@ pub struct DbTransactionTokenBalance {
     pub owner: String,
 }
 
-#[derive(Clone, Debug, Eq, FromSql, ToSql, PartialEq)]
+#[derive(Clone, Debug, Eq, FromSql, ToSql, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "RewardType")]
 pub enum DbRewardType {
     Fee,
@@ -57,7 +84,7 @@ pub enum DbRewardType {
     Voting,
 }
 
-#[derive(Clone, Debug, FromSql, ToSql)]
+#[derive(Clone, Debug, PartialEq, FromSql, ToSql, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "Reward")]
 pub struct DbReward {
     pub pubkey: String,
@@ -67,7 +94,7 @@ pub struct DbReward {
     pub commission: Option<i16>,
 }
 
-#[derive(Clone, Debug, FromSql, ToSql)]
+#[derive(Clone, Debug, PartialEq, FromSql, ToSql, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "TransactionStatusMeta")]
 pub struct DbTransactionStatusMeta {
     pub error: Option<DbTransactionError>,
@@ -81,7 +108,7 @@ pub struct DbTransactionStatusMeta {
     pub rewards: Option<Vec<DbReward>>,
 }
 
-#[derive(Clone, Debug, FromSql, ToSql)]
+#[derive(Clone, Debug, PartialEq, FromSql, ToSql, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "TransactionMessageHeader")]
 pub struct DbTransactionMessageHeader {
     pub num_required_signatures: i16,
@@ -89,7 +116,7 @@ pub struct DbTransactionMessageHeader {
     pub num_readonly_unsigned_accounts: i16,
 }
 
-#[derive(Clone, Debug, FromSql, ToSql)]
+#[derive(Clone, Debug, PartialEq, FromSql, ToSql, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "TransactionMessage")]
 pub struct DbTransactionMessage {
     pub header: DbTransactionMessageHeader,
@@ -98,7 +125,7 @@ pub struct DbTransactionMessage {
     pub instructions: Vec<DbCompiledInstruction>,
 }
 
-#[derive(Clone, Debug, FromSql, ToSql)]
+#[derive(Clone, Debug, PartialEq, FromSql, ToSql, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "TransactionMessageAddressTableLookup")]
 pub struct DbTransactionMessageAddressTableLookup {
     pub account_key: Vec<u8>,
@@ -106,7 +133,7 @@ pub struct DbTransactionMessageAddressTableLookup {
     pub readonly_indexes: Vec<i16>,
 }
 
-#[derive(Clone, Debug, FromSql, ToSql)]
+#[derive(Clone, Debug, PartialEq, FromSql, ToSql, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "TransactionMessageV0")]
 pub struct DbTransactionMessageV0 {
     pub header: DbTransactionMessageHeader,
@@ -116,25 +143,28 @@ pub struct DbTransactionMessageV0 {
     pub address_table_lookups: Vec<DbTransactionMessageAddressTableLookup>,
 }
 
-#[derive(Clone, Debug, FromSql, ToSql)]
+#[derive(Clone, Debug, PartialEq, FromSql, ToSql, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "LoadedAddresses")]
 pub struct DbLoadedAddresses {
     pub writable: Vec<Vec<u8>>,
     pub readonly: Vec<Vec<u8>>,
 }
 
-#[derive(Clone, Debug, FromSql, ToSql)]
+#[derive(Clone, Debug, PartialEq, FromSql, ToSql, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "LoadedMessageV0")]
 pub struct DbLoadedMessageV0 {
     pub message: DbTransactionMessageV0,
     pub loaded_addresses: DbLoadedAddresses,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct DbTransaction {
     pub signature: Vec<u8>,
     pub is_vote: bool,
     pub slot: i64,
     pub message_type: i16,
+    /// Message version, "legacy" or the version number, e.g. "0"
+    pub version: String,
     pub legacy_message: Option<DbTransactionMessage>,
     pub v0_loaded_message: Option<DbLoadedMessageV0>,
     pub message_hash: Vec<u8>,
@@ -147,6 +177,21 @@ pub struct DbTransaction {
     pub index: i64,
 }
 
+impl DbTransaction {
+    /// Rough size in bytes of the transaction row, used to cap the pending transactions buffer
+    pub fn estimated_size(&self) -> usize {
+        let instructions_size = |instructions: &Vec<DbCompiledInstruction>| -> usize { instructions.iter().map(|instruction| instruction.data.len() + 2 * instruction.accounts.len()).sum() };
+        let (account_keys, instructions_size) = match (&self.legacy_message, &self.v0_loaded_message) {
+            (Some(message), _) => (message.account_keys.len(), instructions_size(&message.instructions)),
+            (_, Some(loaded_message)) => (loaded_message.message.account_keys.len(), instructions_size(&loaded_message.message.instructions)),
+            _ => (0, 0),
+        };
+        let log_messages_size: usize = self.meta.log_messages.as_ref().map_or(0, |logs| logs.iter().map(String::len).sum());
+        let balances_size = 8 * (self.meta.pre_balances.len() + self.meta.post_balances.len());
+        64 * self.signatures.len() + 32 * account_keys + instructions_size + log_messages_size + balances_size
+    }
+}
+
 impl From<&MessageAddressTableLookup> for DbTransactionMessageAddressTableLookup {
     fn from(address_table_lookup: &MessageAddressTableLookup) -> Self {
         Self {
@@ -254,7 +299,7 @@ impl From<&Reward> for DbReward {
     }
 }
 
-#[derive(Clone, Debug, Eq, FromSql, ToSql, PartialEq)]
+#[derive(Clone, Debug, Eq, FromSql, ToSql, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "TransactionErrorCode")]
 pub enum DbTransactionErrorCode {
     AccountInUse,
@@ -331,7 +376,7 @@ impl From<&TransactionError> for DbTransactionErrorCode {
     }
 }
 
-#[derive(Clone, Debug, Eq, FromSql, ToSql, PartialEq)]
+#[derive(Clone, Debug, Eq, FromSql, ToSql, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[postgres(name = "TransactionError")]
 pub struct DbTransactionError {
     error_code: DbTransactionErrorCode,
@@ -396,6 +441,7 @@ pub fn build_db_transaction(slot: u64, transaction_info: &ReplicaTransactionInfo
             SanitizedMessage::Legacy(_) => 0,
             SanitizedMessage::V0(_) => 1,
         },
+        version: transaction_version(transaction_info.transaction.message()),
         legacy_message: match transaction_info.transaction.message() {
             SanitizedMessage::Legacy(legacy_message) => Some(DbTransactionMessage::from(legacy_message.message.as_ref())),
             _ => None,
@@ -408,22 +454,121 @@ pub fn build_db_transaction(slot: u64, transaction_info: &ReplicaTransactionInfo
         message_hash: transaction_info.transaction.message_hash().as_ref().to_vec(),
         meta: DbTransactionStatusMeta::from(transaction_info.transaction_status_meta),
         write_version: transaction_write_version as i64,
-        index: 0,
+        index: transaction_info.index as i64,
+    }
+}
+
+/// Version of a message as stored in the `version` column: "legacy" or the version number
+fn transaction_version(message: &SanitizedMessage) -> String {
+    let version = match message {
+        SanitizedMessage::Legacy(_) => TransactionVersion::LEGACY,
+        SanitizedMessage::V0(_) => TransactionVersion::Number(0),
+    };
+    match version {
+        TransactionVersion::Legacy(_) => "legacy".to_string(),
+        TransactionVersion::Number(number) => number.to_string(),
+    }
+}
+
+/// Text of the memo-program instructions of a transaction joined by "; ".
+/// Invalid UTF-8 sequences are replaced and null bytes, which text columns reject, are dropped.
+pub fn transaction_memo(transaction_info: &DbTransaction) -> Option<String> {
+    let (account_keys, instructions) = match (&transaction_info.legacy_message, &transaction_info.v0_loaded_message) {
+        (Some(message), _) => (&message.account_keys, &message.instructions),
+        (_, Some(loaded_message)) => (&loaded_message.message.account_keys, &loaded_message.message.instructions),
+        _ => return None,
+    };
+    let memos = instructions
+        .iter()
+        .filter(|instruction| {
+            account_keys
+                .get(instruction.program_id_index as usize)
+                .is_some_and(|program_id| program_id == MEMO_PROGRAM_ID.as_ref() || program_id == MEMO_V1_PROGRAM_ID.as_ref())
+        })
+        .map(|instruction| String::from_utf8_lossy(&instruction.data).replace('\0', ""))
+        .collect::<Vec<String>>();
+    if memos.is_empty() {
+        return None;
     }
+    Some(memos.join("; "))
 }
 
 pub struct TransactionHandler {
-    pub upsert_statement: Statement,
+    /// Prepared on each connection it is executed on
+    pub upsert_query: String,
+    pub store_memos: bool,
+    pub epoch_schedule: EpochSchedule,
+    pub index_cnft_events: bool,
+    /// Transactions are written in a database transaction committed at this level when set
+    pub synchronous_commit: Option<SynchronousCommit>,
+    /// Encodes the rows of a batch in parallel, set when `transaction_build_threads` is above 1
+    pub build_pool: Option<ThreadPool>,
+    pub write_slot_tx_stats: bool,
+}
+
+/// A parameter encoded ahead of the statement's execution, for the type of the statement parameter it is bound to
+#[derive(Debug)]
+struct EncodedParam(Option<BytesMut>);
+
+impl ToSql for EncodedParam {
+    fn to_sql(&self, _ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match &self.0 {
+            Some(bytes) => {
+                out.extend_from_slice(bytes);
+                Ok(IsNull::No)
+            }
+            None => Ok(IsNull::Yes),
+        }
+    }
+
+    // checked against the statement's types when encoded
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
+}
+
+/// The transactions of a slot written by a batch
+#[derive(Default)]
+struct SlotTxStats {
+    tx_count: i64,
+    failed_count: i64,
+    total_fee: i64,
+}
+
+impl SlotTxStats {
+    fn add(&mut self, transaction_info: &DbTransaction) {
+        self.tx_count += 1;
+        self.failed_count += transaction_info.meta.error.is_some() as i64;
+        self.total_fee += transaction_info.meta.fee;
+    }
+}
+
+/// The row of a transaction encoded for the upsert statement, with the query of its compressed NFT events
+struct EncodedTransaction {
+    params: Vec<EncodedParam>,
+    cnft_query: Option<String>,
 }
 
 impl TransactionHandler {
-    pub fn new(client: &mut Client, _config: &GeyserPluginPostgresConfig) -> Result<TransactionHandler, GeyserPluginError> {
-        let stmt = "
+    pub fn new(client: &mut PooledClient, config: &GeyserPluginPostgresConfig) -> Result<TransactionHandler, GeyserPluginError> {
+        let (memo_column, memo_value, memo_update) = match config.store_memos {
+            true => (", memo", ", $15", "memo=excluded.memo, "),
+            false => ("", "", ""),
+        };
+        // with a row per signature the highest slot wins, the transaction of a lower (fork) slot is not stored over it
+        let (conflict_target, conflict_update) = match config.transaction_slot_conflict {
+            TransactionSlotConflict::KeepAll => ("(slot, signature)", ""),
+            TransactionSlotConflict::Highest => ("(signature)", ", slot=GREATEST(txn.slot, excluded.slot) WHERE txn.slot <= excluded.slot"),
+        };
+        let stmt = &format!(
+            "
             INSERT INTO transaction AS txn (signature, is_vote, slot, message_type, \
                 legacy_message, v0_loaded_message, signatures, message_hash, meta, \
-                write_version, index, updated_on) \
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) \
-            ON CONFLICT (slot, signature) DO UPDATE SET is_vote=excluded.is_vote, \
+                write_version, index, updated_on, epoch, version{0}) \
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14{1}) \
+            ON CONFLICT {3} DO UPDATE SET {2}is_vote=excluded.is_vote, \
                 message_type=excluded.message_type, \
                 legacy_message=excluded.legacy_message, \
                 v0_loaded_message=excluded.v0_loaded_message, \
@@ -432,20 +577,67 @@ impl TransactionHandler {
                 meta=excluded.meta, \
                 write_version=excluded.write_version, \
                 index=excluded.index,
-                updated_on=excluded.updated_on;
-        ";
-        match client.prepare(stmt) {
-            Ok(statement) => Ok(TransactionHandler { upsert_statement: statement }),
+                updated_on=excluded.updated_on,
+                epoch=excluded.epoch,
+                version=excluded.version{4} \
+            RETURNING (xmax = 0) AS inserted;
+        ",
+            memo_column, memo_value, memo_update, conflict_target, conflict_update
+        );
+        match client.prepare_cached(stmt) {
+            Ok(_) => Ok(TransactionHandler {
+                build_pool: Self::build_pool(config)?,
+                upsert_query: stmt.to_string(),
+                store_memos: config.store_memos,
+                epoch_schedule: config.epoch_schedule(),
+                index_cnft_events: config.index_cnft_events,
+                synchronous_commit: config.synchronous_commit.get(&WriteCategory::Transaction).copied(),
+                write_slot_tx_stats: config.write_slot_tx_stats,
+            }),
             Err(err) => Err(GeyserPluginError::Custom(Box::new(GeyserPluginPostgresError::DataSchemaError {
                 msg: format!("[transction_handler::new] error=[{}]", err),
             }))),
         }
     }
 
-    pub fn init(_config: &crate::config::GeyserPluginPostgresConfig) -> String {
+    pub fn init(config: &crate::config::GeyserPluginPostgresConfig) -> String {
+        let mut init = Self::init_tables();
+        init.push_str("ALTER TABLE transaction ADD COLUMN IF NOT EXISTS epoch BIGINT; CREATE INDEX IF NOT EXISTS transaction_epoch ON transaction (epoch);");
+        init.push_str("ALTER TABLE transaction ADD COLUMN IF NOT EXISTS version VARCHAR(8);");
+        if config.transaction_slot_conflict == TransactionSlotConflict::Highest {
+            init.push_str("CREATE UNIQUE INDEX IF NOT EXISTS transaction_signature ON transaction (signature);");
+        }
+        if config.write_slot_tx_stats {
+            init.push_str(
+                "
+                CREATE TABLE IF NOT EXISTS slot_tx_stats (
+                    slot BIGINT PRIMARY KEY,
+                    tx_count BIGINT NOT NULL,
+                    failed_count BIGINT NOT NULL,
+                    total_fee BIGINT NOT NULL,
+                    updated_on TIMESTAMP NOT NULL
+                );
+            ",
+            );
+        }
+        if config.index_cnft_events {
+            init.push_str(&CnftEventHandler::init());
+        }
+        if config.store_memos {
+            init.push_str("ALTER TABLE transaction ADD COLUMN IF NOT EXISTS memo TEXT;");
+            init.push_str(match config.memo_index {
+                MemoIndex::None => "",
+                MemoIndex::FullText => "CREATE INDEX IF NOT EXISTS transaction_memo ON transaction USING GIN (to_tsvector('simple', memo));",
+                MemoIndex::Trigram => "CREATE EXTENSION IF NOT EXISTS pg_trgm; CREATE INDEX IF NOT EXISTS transaction_memo_trgm ON transaction USING GIN (memo gin_trgm_ops);",
+            });
+        }
+        init
+    }
+
+    fn init_tables() -> String {
         return "
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionErrorCode') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionErrorCode' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"TransactionErrorCode\" AS ENUM (
                         'AccountInUse',
                         'AccountLoadedTwice',
@@ -485,7 +677,7 @@ impl TransactionHandler {
             END $$;
 
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionError') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionError' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"TransactionError\" AS (
                         error_code \"TransactionErrorCode\",
                         error_detail VARCHAR(256)
@@ -495,7 +687,7 @@ impl TransactionHandler {
 
             
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'CompiledInstruction') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'CompiledInstruction' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"CompiledInstruction\" AS (
                         program_id_index SMALLINT,
                         accounts SMALLINT[],
@@ -505,7 +697,7 @@ impl TransactionHandler {
             END $$;
 
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'InnerInstructions') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'InnerInstructions' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"InnerInstructions\" AS (
                         index SMALLINT,
                         instructions \"CompiledInstruction\"[]
@@ -514,7 +706,7 @@ impl TransactionHandler {
             END $$;
 
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionTokenBalance') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionTokenBalance' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"TransactionTokenBalance\" AS (
                         account_index SMALLINT,
                         mint VARCHAR(44),
@@ -525,7 +717,7 @@ impl TransactionHandler {
             END $$;
             
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'RewardType') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'RewardType' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"RewardType\" AS ENUM (
                         'Fee',
                         'Rent',
@@ -536,7 +728,7 @@ impl TransactionHandler {
             END $$;
 
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'Reward') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'Reward' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"Reward\" AS (
                         pubkey VARCHAR(44),
                         lamports BIGINT,
@@ -548,7 +740,7 @@ impl TransactionHandler {
             END $$;
 
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionStatusMeta') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionStatusMeta' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"TransactionStatusMeta\" AS (
                         error \"TransactionError\",
                         fee BIGINT,
@@ -564,7 +756,7 @@ impl TransactionHandler {
             END $$;
 
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionMessageHeader') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionMessageHeader' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"TransactionMessageHeader\" AS (
                         num_required_signatures SMALLINT,
                         num_readonly_signed_accounts SMALLINT,
@@ -574,7 +766,7 @@ impl TransactionHandler {
             END $$;
 
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionMessage') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionMessage' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"TransactionMessage\" AS (
                         header \"TransactionMessageHeader\",
                         account_keys BYTEA[],
@@ -585,7 +777,7 @@ impl TransactionHandler {
             END $$;
 
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionMessageAddressTableLookup') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionMessageAddressTableLookup' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"TransactionMessageAddressTableLookup\" AS (
                         account_key BYTEA,
                         writable_indexes SMALLINT[],
@@ -595,7 +787,7 @@ impl TransactionHandler {
             END $$;
 
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionMessageV0') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'TransactionMessageV0' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"TransactionMessageV0\" AS (
                         header \"TransactionMessageHeader\",
                         account_keys BYTEA[],
@@ -607,7 +799,7 @@ impl TransactionHandler {
             END $$;
 
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'LoadedAddresses') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'LoadedAddresses' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"LoadedAddresses\" AS (
                         writable BYTEA[],
                         readonly BYTEA[]
@@ -616,7 +808,7 @@ impl TransactionHandler {
             END $$;
 
             DO $$ BEGIN
-                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'LoadedMessageV0') THEN
+                IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'LoadedMessageV0' AND typnamespace = current_schema()::regnamespace) THEN
                     CREATE TYPE \"LoadedMessageV0\" AS (
                         message \"TransactionMessageV0\",
                         loaded_addresses \"LoadedAddresses\"
@@ -643,31 +835,168 @@ impl TransactionHandler {
         .to_string();
     }
 
-    pub fn update(&self, client: &mut Client, transaction_info: DbTransaction) -> Result<(), GeyserPluginError> {
-        let result = client.query(
-            &self.upsert_statement,
-            &[
-                &transaction_info.signature,
-                &transaction_info.is_vote,
-                &transaction_info.slot,
-                &transaction_info.message_type,
-                &transaction_info.legacy_message,
-                &transaction_info.v0_loaded_message,
-                &transaction_info.signatures,
-                &transaction_info.message_hash,
-                &transaction_info.meta,
-                &transaction_info.write_version,
-                &transaction_info.index,
-                &Utc::now().naive_utc(),
-            ],
+    fn build_pool(config: &GeyserPluginPostgresConfig) -> Result<Option<ThreadPool>, GeyserPluginError> {
+        if config.transaction_build_threads <= 1 {
+            return Ok(None);
+        }
+        match ThreadPoolBuilder::new().num_threads(config.transaction_build_threads).build() {
+            Ok(pool) => Ok(Some(pool)),
+            Err(err) => Err(GeyserPluginError::ConfigFileReadError {
+                msg: format!("Failed to start the \"transaction_build_threads\" threads. Error: ({})", err),
+            }),
+        }
+    }
+
+    pub fn update(&self, client: &mut PooledClient, transaction_info: &DbTransaction) -> Result<(), GeyserPluginError> {
+        // the slot stats are incremented in the database transaction writing the transaction
+        if self.synchronous_commit.is_none() && !self.write_slot_tx_stats {
+            let statement = self.upsert_statement(client)?;
+            let encoded = self.encode_batch(&statement, std::slice::from_ref(transaction_info))?;
+            return self.upsert(&mut **client, &statement, &encoded[0]).map(|_| ());
+        }
+        self.update_batch(client, std::slice::from_ref(transaction_info))
+    }
+
+    /// Writes a batch of transactions within a single database transaction, their rows being encoded beforehand
+    pub fn update_batch(&self, client: &mut PooledClient, transactions: &[DbTransaction]) -> Result<(), GeyserPluginError> {
+        let statement = self.upsert_statement(client)?;
+        let encoded = self.encode_batch(&statement, transactions)?;
+        let batch_error = |err| {
+            write_error(
+                format!("Failed to persist the batch of {} transactions to the PostgreSQL database. Error: {:?}", transactions.len(), err),
+                err,
+            )
+        };
+        let mut db_transaction = client.transaction().map_err(batch_error)?;
+        if let Some(synchronous_commit) = &self.synchronous_commit {
+            db_transaction.batch_execute(synchronous_commit.set_local()).map_err(batch_error)?;
+        }
+        // the database transaction is rolled back when dropped on error, the whole batch being retried or reported
+        let mut stats = BTreeMap::<i64, SlotTxStats>::new();
+        for (transaction_info, encoded_transaction) in transactions.iter().zip(&encoded) {
+            // a transaction written again, e.g. on a retry of its batch, is counted once
+            if self.upsert(&mut db_transaction, &statement, encoded_transaction)? && self.write_slot_tx_stats {
+                stats.entry(transaction_info.slot).or_default().add(transaction_info);
+            }
+        }
+        if !stats.is_empty() {
+            Self::increment_slot_tx_stats(&mut db_transaction, &stats)?;
+        }
+        db_transaction.commit().map_err(batch_error)
+    }
+
+    /// Adds the transactions of each slot to its row of `slot_tx_stats`, created by the first ones written
+    fn increment_slot_tx_stats(client: &mut impl GenericClient, stats: &BTreeMap<i64, SlotTxStats>) -> Result<(), GeyserPluginError> {
+        let updated_on = Utc::now().naive_utc();
+        let mut params: Vec<&(dyn ToSql + Sync)> = Vec::with_capacity(stats.len() * 5);
+        let mut values = Vec::with_capacity(stats.len());
+        for (i, (slot, slot_stats)) in stats.iter().enumerate() {
+            values.push(format!("(${}, ${}, ${}, ${}, ${})", i * 5 + 1, i * 5 + 2, i * 5 + 3, i * 5 + 4, i * 5 + 5));
+            params.extend_from_slice(&[slot, &slot_stats.tx_count, &slot_stats.failed_count, &slot_stats.total_fee, &updated_on]);
+        }
+        let query = format!(
+            "
+            INSERT INTO slot_tx_stats AS stats (slot, tx_count, failed_count, total_fee, updated_on) VALUES {} \
+            ON CONFLICT (slot) DO UPDATE SET tx_count=stats.tx_count + excluded.tx_count, \
+                failed_count=stats.failed_count + excluded.failed_count, \
+                total_fee=stats.total_fee + excluded.total_fee, \
+                updated_on=excluded.updated_on;
+        ",
+            values.join(", ")
         );
-        if let Err(err) = result {
-            let msg = format!("Failed to persist the update of transaction info to the PostgreSQL database. Error: {:?}", err);
+        match client.execute(&query, &params) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(write_error(format!("Failed to persist the slot transaction stats to the PostgreSQL database. Error: {:?}", err), err)),
+        }
+    }
+
+    /// The upsert statement prepared on the connection of `client`
+    fn upsert_statement(&self, client: &mut PooledClient) -> Result<Statement, GeyserPluginError> {
+        client
+            .prepare_cached(&self.upsert_query)
+            .map_err(|err| write_error(format!("Failed to prepare the transaction upsert statement. Error: {:?}", err), err))
+    }
+
+    /// Encodes the rows of the transactions for `statement`, on the `build_pool` threads if set. The rows are in
+    /// the order of the transactions either way
+    fn encode_batch(&self, statement: &Statement, transactions: &[DbTransaction]) -> Result<Vec<EncodedTransaction>, GeyserPluginError> {
+        let result: Result<Vec<EncodedTransaction>, Box<dyn Error + Sync + Send>> = match &self.build_pool {
+            Some(pool) => pool.install(|| transactions.par_iter().map(|transaction_info| self.encode(statement, transaction_info)).collect()),
+            None => transactions.iter().map(|transaction_info| self.encode(statement, transaction_info)).collect(),
+        };
+        result.map_err(|err| {
+            let msg = format!("Failed to encode the transaction info for the PostgreSQL database. Error: {:?}", err);
             error!("{}", msg);
-            return Err(GeyserPluginError::AccountsUpdateError { msg });
+            GeyserPluginError::AccountsUpdateError { msg }
+        })
+    }
+
+    fn encode(&self, statement: &Statement, transaction_info: &DbTransaction) -> Result<EncodedTransaction, Box<dyn Error + Sync + Send>> {
+        let updated_on = Utc::now().naive_utc();
+        let memo = self.store_memos.then(|| transaction_memo(transaction_info)).flatten();
+        let epoch = self.epoch_schedule.get_epoch(transaction_info.slot as u64) as i64;
+        let mut params: Vec<&(dyn ToSql + Sync)> = vec![
+            &transaction_info.signature,
+            &transaction_info.is_vote,
+            &transaction_info.slot,
+            &transaction_info.message_type,
+            &transaction_info.legacy_message,
+            &transaction_info.v0_loaded_message,
+            &transaction_info.signatures,
+            &transaction_info.message_hash,
+            &transaction_info.meta,
+            &transaction_info.write_version,
+            &transaction_info.index,
+            &updated_on,
+            &epoch,
+            &transaction_info.version,
+        ];
+        if self.store_memos {
+            params.push(&memo);
+        }
+        let params = params
+            .iter()
+            .zip(statement.params())
+            .map(|(param, ty)| {
+                let mut bytes = BytesMut::new();
+                Ok(match param.to_sql_checked(ty, &mut bytes)? {
+                    IsNull::Yes => EncodedParam(None),
+                    IsNull::No => EncodedParam(Some(bytes)),
+                })
+            })
+            .collect::<Result<Vec<EncodedParam>, Box<dyn Error + Sync + Send>>>()?;
+        let events = match self.index_cnft_events {
+            true => CnftEventHandler::events(transaction_info),
+            false => vec![],
+        };
+        Ok(EncodedTransaction {
+            params,
+            cnft_query: (!events.is_empty()).then(|| CnftEventHandler::update(transaction_info, &events)),
+        })
+    }
+
+    /// Upserts the transaction, returning whether its row was inserted rather than updated
+    fn upsert(&self, client: &mut impl GenericClient, statement: &Statement, encoded_transaction: &EncodedTransaction) -> Result<bool, GeyserPluginError> {
+        let params = encoded_transaction.params.iter().map(|param| param as &(dyn ToSql + Sync)).collect::<Vec<&(dyn ToSql + Sync)>>();
+        let inserted = match client.query(statement, &params) {
+            Ok(rows) => rows.first().is_some_and(|row| row.get::<_, bool>(0)),
+            Err(err) => {
+                return Err(write_error(
+                    format!("Failed to persist the update of transaction info to the PostgreSQL database. Error: {:?}", err),
+                    err,
+                ))
+            }
+        };
+        if let Some(cnft_query) = &encoded_transaction.cnft_query {
+            if let Err(err) = client.batch_execute(cnft_query) {
+                return Err(write_error(
+                    format!("Failed to persist the compressed NFT events of the transaction to the PostgreSQL database. Error: {:?}", err),
+                    err,
+                ));
+            }
         }
 
-        Ok(())
+        Ok(inserted)
     }
 }
 
@@ -676,6 +1005,7 @@ pub(crate) mod tests {
     use super::*;
     use solana_account_decoder::parse_token::UiTokenAmount;
     use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::Instruction;
     use solana_sdk::message::VersionedMessage;
     use solana_sdk::pubkey::Pubkey;
     use solana_sdk::signature::Keypair;
@@ -1151,10 +1481,12 @@ pub(crate) mod tests {
         match transaction.transaction.message() {
             SanitizedMessage::Legacy(message) => {
                 assert_eq!(db_transaction.message_type, 0);
+                assert_eq!(db_transaction.version, "legacy");
                 check_transaction_message_equality(message.message.as_ref(), db_transaction.legacy_message.as_ref().unwrap());
             }
             SanitizedMessage::V0(message) => {
                 assert_eq!(db_transaction.message_type, 1);
+                assert_eq!(db_transaction.version, "0");
                 check_loaded_message_v0_equality(message, db_transaction.v0_loaded_message.as_ref().unwrap());
             }
         }
@@ -1243,4 +1575,38 @@ pub(crate) mod tests {
         let db_transaction = build_db_transaction(slot, &transaction_info, 1);
         check_transaction(slot, &transaction_info, &db_transaction);
     }
+
+    #[test]
+    fn test_transaction_memo() {
+        let keypair = Keypair::new();
+        let instructions = [
+            Instruction::new_with_bytes(MEMO_PROGRAM_ID, b"invoice 42", vec![]),
+            Instruction::new_with_bytes(MEMO_V1_PROGRAM_ID, &[b'r', 0xff, 0, b'f'], vec![]),
+        ];
+        let message = Message::new(&instructions, Some(&keypair.pubkey()));
+        let transaction = Transaction::new(&[&keypair], message, Hash::default());
+        let transaction = SanitizedTransaction::from_transaction_for_tests(transaction);
+
+        let transaction_status_meta = build_transaction_status_meta();
+        let transaction_info = ReplicaTransactionInfoV2 {
+            index: 0,
+            signature: transaction.signature(),
+            is_vote: false,
+            transaction: &transaction,
+            transaction_status_meta: &transaction_status_meta,
+        };
+        let db_transaction = build_db_transaction(54, &transaction_info, 1);
+        assert_eq!(transaction_memo(&db_transaction), Some("invoice 42; r\u{fffd}f".to_string()));
+
+        let transaction = SanitizedTransaction::from_transaction_for_tests(build_test_transaction_legacy());
+        let transaction_info = ReplicaTransactionInfoV2 {
+            index: 0,
+            signature: transaction.signature(),
+            is_vote: false,
+            transaction: &transaction,
+            transaction_status_meta: &transaction_status_meta,
+        };
+        let db_transaction = build_db_transaction(54, &transaction_info, 1);
+        assert_eq!(transaction_memo(&db_transaction), None);
+    }
 }
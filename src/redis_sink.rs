@@ -0,0 +1,187 @@
+//! Latest state of the selected accounts mirrored to Redis for low-latency reads. Accounts are only
+//! written when the plugin is built with the `redis` feature.
+
+use crate::accounts_selector::SharedAccountsSelector;
+use crate::config::GeyserPluginPostgresConfig;
+use crate::postgres_client::retry::RetryPolicy;
+use crate::postgres_client::select_account_handlers;
+use crate::postgres_client::DbAccountInfo;
+use crate::serializer::Serializer;
+use log::*;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::net::TcpStream;
+use std::thread::sleep;
+
+/*
+    Keeps the account of the highest (slot, write_version), as the PostgreSQL handlers do, whichever worker
+    writes last. The version is zero-padded so that versions compare as strings
+    KEYS[1]: account key
+    ARGV[1]: version, ARGV[2]: serialized account, ARGV[3]: TTL in seconds, 0 for none
+*/
+const UPSERT_ACCOUNT_SCRIPT: &str = "
+    local version = redis.call('HGET', KEYS[1], 'version')
+    if version and version >= ARGV[1] then
+        return 0
+    end
+    redis.call('HSET', KEYS[1], 'version', ARGV[1], 'account', ARGV[2])
+    if tonumber(ARGV[3]) > 0 then
+        redis.call('EXPIRE', KEYS[1], ARGV[3])
+    end
+    return 1
+";
+
+/// Key of the Redis hash holding the latest state of an account
+pub fn account_key(pubkey: &[u8]) -> String {
+    format!("account:{}", bs58::encode(pubkey).into_string())
+}
+
+/// Version of an account update, ordered as strings by slot then write version
+pub fn account_version(account: &DbAccountInfo) -> String {
+    format!("{:020}:{:020}", account.slot, account.write_version)
+}
+
+pub struct RedisSink {
+    address: String,
+    /// Dropped when it fails, the next flush connecting again
+    connection: Option<(TcpStream, BufReader<TcpStream>)>,
+    /// Commands buffered until the next flush, sent in a single pipeline
+    pending: Vec<u8>,
+    pending_count: usize,
+    batch_size: usize,
+    retry_policy: RetryPolicy,
+    serializer: Box<dyn Serializer>,
+    ttl_secs: u64,
+    account_selector: SharedAccountsSelector,
+}
+
+impl RedisSink {
+    /// Connects to `redis_address`, no sink being used when it is not set
+    #[cfg(feature = "redis")]
+    pub fn connect(config: &GeyserPluginPostgresConfig) -> Result<Option<Self>, GeyserPluginError> {
+        use crate::serializer::serializer;
+
+        let Some(address) = &config.redis_address else {
+            return Ok(None);
+        };
+        let connection = Self::open(address).map_err(|err| GeyserPluginError::Custom(Box::new(err)))?;
+        info!("[redis_sink] connected address={}", address);
+        Ok(Some(Self {
+            address: address.clone(),
+            connection: Some(connection),
+            pending: Vec::new(),
+            pending_count: 0,
+            batch_size: config.batch_size.max(1),
+            // a lost connection is retried as the PostgreSQL writes are
+            retry_policy: RetryPolicy {
+                retries: config.write_reconnect_retries,
+                ..RetryPolicy::new(config)
+            },
+            serializer: serializer(config.sink_serialization),
+            ttl_secs: config.redis_ttl_secs.unwrap_or_default(),
+            account_selector: SharedAccountsSelector::new(config.accounts_selector.clone()),
+        }))
+    }
+
+    #[cfg(not(feature = "redis"))]
+    pub fn connect(config: &GeyserPluginPostgresConfig) -> Result<Option<Self>, GeyserPluginError> {
+        if config.redis_address.is_some() {
+            warn!("[redis_sink] \"redis_address\" is set but the plugin was built without the \"redis\" feature");
+        }
+        Ok(None)
+    }
+
+    fn open(address: &str) -> std::io::Result<(TcpStream, BufReader<TcpStream>)> {
+        let stream = TcpStream::connect(address)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        Ok((stream, reader))
+    }
+
+    pub fn set_accounts_selector(&mut self, account_selector: SharedAccountsSelector) {
+        self.account_selector = account_selector;
+    }
+
+    /// Buffers the write of the account when a handler is selected for it, flushing once `batch_size` are buffered.
+    /// The serialized `DbAccountInfo` is stored rather than the rows of its handlers, its data holding the token or
+    /// mint state in its canonical layout and being deserialized by the readers with the `sink_serialization` format
+    pub fn update_account(&mut self, account: &DbAccountInfo, is_startup: bool) -> Result<(), GeyserPluginError> {
+        if select_account_handlers(&self.account_selector.load().config, account, is_startup).is_empty() {
+            return Ok(());
+        }
+        let value = self.serializer.serialize_account(account).map_err(|err| GeyserPluginError::AccountsUpdateError {
+            msg: format!("Failed to serialize the account for Redis: ({})", err),
+        })?;
+        let key = account_key(&account.pubkey);
+        let version = account_version(account);
+        let ttl_secs = self.ttl_secs.to_string();
+        let args: [&[u8]; 7] = [b"EVAL", UPSERT_ACCOUNT_SCRIPT.as_bytes(), b"1", key.as_bytes(), version.as_bytes(), &value, ttl_secs.as_bytes()];
+        encode_command(&mut self.pending, &args);
+        self.pending_count += 1;
+        if self.pending_count >= self.batch_size {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Sends the buffered commands in a single pipeline. The whole pipeline is sent again on a new connection when
+    /// the connection is lost, the upsert script ignoring the accounts already stored
+    pub fn flush(&mut self) -> Result<(), GeyserPluginError> {
+        if self.pending_count == 0 {
+            return Ok(());
+        }
+        let mut reconnects = 0;
+        let result = loop {
+            let err = match self.send_pending() {
+                Ok(result) => break result,
+                Err(err) => err,
+            };
+            self.connection = None;
+            if reconnects >= self.retry_policy.retries {
+                break Err(err);
+            }
+            let delay = self.retry_policy.delay(reconnects);
+            warn!("[redis_sink] connection lost, attempt={} retry_in_ms={} error={}", reconnects + 1, delay.as_millis(), err);
+            sleep(delay);
+            reconnects += 1;
+        };
+        let count = self.pending_count;
+        self.pending.clear();
+        self.pending_count = 0;
+        result.map_err(|err| GeyserPluginError::AccountsUpdateError {
+            msg: format!("Failed to write {} accounts to Redis: ({})", count, err),
+        })
+    }
+
+    /// Writes the pending commands and reads their replies. The outer error is an I/O error of the connection,
+    /// the inner one the first error replied by Redis
+    fn send_pending(&mut self) -> std::io::Result<std::io::Result<()>> {
+        let (stream, reader) = match &mut self.connection {
+            Some(connection) => connection,
+            connection => connection.insert(Self::open(&self.address)?),
+        };
+        stream.write_all(&self.pending)?;
+        let mut result = Ok(());
+        for _ in 0..self.pending_count {
+            let mut reply = String::new();
+            reader.read_line(&mut reply)?;
+            match reply.chars().next() {
+                Some('-') if result.is_ok() => result = Err(std::io::Error::other(reply.trim_end().to_string())),
+                Some(_) => {}
+                None => return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "connection closed")),
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Appends a command in the RESP protocol
+fn encode_command(buffer: &mut Vec<u8>, args: &[&[u8]]) {
+    buffer.extend_from_slice(format!("*{}\r\n", args.len()).as_bytes());
+    for arg in args {
+        buffer.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        buffer.extend_from_slice(arg);
+        buffer.extend_from_slice(b"\r\n");
+    }
+}
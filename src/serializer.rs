@@ -0,0 +1,215 @@
+//! Encoding of the plugin's records shared by the sinks other than PostgreSQL, so they all write
+//! the same schema whatever the transport
+
+use crate::postgres_client::DbAccountInfo;
+use crate::postgres_client::DbBlockInfo;
+use crate::postgres_client::DbTransaction;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_derive::Deserialize;
+use serde_json::json;
+use serde_json::Value;
+use thiserror::Error;
+
+/// Encoding of the records written by the sinks
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde_derive::Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SerializationFormat {
+    /// Self-describing JSON objects
+    Json,
+    /// Compact borsh encoding
+    Borsh,
+    /// JSON change events in the envelope of Debezium, as consumed by CDC pipelines
+    Debezium,
+}
+
+#[derive(Error, Debug)]
+pub enum SerializerError {
+    #[error("JSON serialization error: ({0})")]
+    Json(#[from] serde_json::Error),
+    #[error("Borsh serialization error: ({0})")]
+    Borsh(#[from] std::io::Error),
+    #[error("Debezium envelope without record")]
+    EmptyEnvelope,
+}
+
+pub trait Serializer: Send + Sync {
+    fn serialize_account(&self, account: &DbAccountInfo) -> Result<Vec<u8>, SerializerError>;
+
+    fn deserialize_account(&self, bytes: &[u8]) -> Result<DbAccountInfo, SerializerError>;
+
+    /// The change of an account from its previous state when known, the formats which do not describe changes
+    /// serializing its new state only
+    fn serialize_account_change(&self, _before: Option<&DbAccountInfo>, after: &DbAccountInfo) -> Result<Vec<u8>, SerializerError> {
+        self.serialize_account(after)
+    }
+
+    fn serialize_transaction(&self, transaction: &DbTransaction) -> Result<Vec<u8>, SerializerError>;
+
+    fn deserialize_transaction(&self, bytes: &[u8]) -> Result<DbTransaction, SerializerError>;
+
+    fn serialize_block(&self, block: &DbBlockInfo) -> Result<Vec<u8>, SerializerError>;
+
+    fn deserialize_block(&self, bytes: &[u8]) -> Result<DbBlockInfo, SerializerError>;
+}
+
+/// Encoding of any record, every codec being a `Serializer`
+trait Codec: Send + Sync {
+    fn encode<T: Serialize + BorshSerialize>(&self, value: &T) -> Result<Vec<u8>, SerializerError>;
+
+    fn decode<T: DeserializeOwned + BorshDeserialize>(&self, bytes: &[u8]) -> Result<T, SerializerError>;
+}
+
+impl<C: Codec> Serializer for C {
+    fn serialize_account(&self, account: &DbAccountInfo) -> Result<Vec<u8>, SerializerError> {
+        self.encode(account)
+    }
+
+    fn deserialize_account(&self, bytes: &[u8]) -> Result<DbAccountInfo, SerializerError> {
+        self.decode(bytes)
+    }
+
+    fn serialize_transaction(&self, transaction: &DbTransaction) -> Result<Vec<u8>, SerializerError> {
+        self.encode(transaction)
+    }
+
+    fn deserialize_transaction(&self, bytes: &[u8]) -> Result<DbTransaction, SerializerError> {
+        self.decode(bytes)
+    }
+
+    fn serialize_block(&self, block: &DbBlockInfo) -> Result<Vec<u8>, SerializerError> {
+        self.encode(block)
+    }
+
+    fn deserialize_block(&self, bytes: &[u8]) -> Result<DbBlockInfo, SerializerError> {
+        self.decode(bytes)
+    }
+}
+
+pub struct JsonSerializer {}
+
+impl Codec for JsonSerializer {
+    fn encode<T: Serialize + BorshSerialize>(&self, value: &T) -> Result<Vec<u8>, SerializerError> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn decode<T: DeserializeOwned + BorshDeserialize>(&self, bytes: &[u8]) -> Result<T, SerializerError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+pub struct BorshSerializer {}
+
+impl Codec for BorshSerializer {
+    fn encode<T: Serialize + BorshSerialize>(&self, value: &T) -> Result<Vec<u8>, SerializerError> {
+        Ok(value.try_to_vec()?)
+    }
+
+    fn decode<T: DeserializeOwned + BorshDeserialize>(&self, bytes: &[u8]) -> Result<T, SerializerError> {
+        Ok(T::try_from_slice(bytes)?)
+    }
+}
+
+/// Operation of a Debezium change event
+#[derive(Clone, Copy)]
+enum DebeziumOp {
+    Create,
+    Update,
+    Delete,
+    /// A state read without its previous one, as the snapshots of Debezium
+    Read,
+}
+
+impl DebeziumOp {
+    fn code(&self) -> &'static str {
+        match self {
+            DebeziumOp::Create => "c",
+            DebeziumOp::Update => "u",
+            DebeziumOp::Delete => "d",
+            DebeziumOp::Read => "r",
+        }
+    }
+}
+
+/// Writes the records as the payload of Debezium change events, schemas disabled: the record states `before` and
+/// `after` the change, its `op`, its `source` and the time it was serialized. Transactions and blocks are created
+/// once, an account is read unless its previous state is given
+pub struct DebeziumSerializer {}
+
+impl DebeziumSerializer {
+    fn envelope<T: Serialize>(op: DebeziumOp, table: &str, slot: i64, before: Option<&T>, after: Option<&T>) -> Result<Vec<u8>, SerializerError> {
+        let ts_ms = chrono::Utc::now().timestamp_millis();
+        Ok(serde_json::to_vec(&json!({
+            "before": before,
+            "after": after,
+            "source": {
+                "version": env!("CARGO_PKG_VERSION"),
+                "connector": env!("CARGO_PKG_NAME"),
+                "ts_ms": ts_ms,
+                "snapshot": matches!(op, DebeziumOp::Read),
+                "table": table,
+                "slot": slot,
+            },
+            "op": op.code(),
+            "ts_ms": ts_ms,
+        }))?)
+    }
+
+    /// The record of an event, its state before the change for a delete
+    fn record<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, SerializerError> {
+        let mut envelope: Value = serde_json::from_slice(bytes)?;
+        let record = match envelope["after"].take() {
+            Value::Null => envelope["before"].take(),
+            after => after,
+        };
+        if record.is_null() {
+            return Err(SerializerError::EmptyEnvelope);
+        }
+        Ok(serde_json::from_value(record)?)
+    }
+}
+
+impl Serializer for DebeziumSerializer {
+    fn serialize_account(&self, account: &DbAccountInfo) -> Result<Vec<u8>, SerializerError> {
+        Self::envelope(DebeziumOp::Read, "account", account.slot, None, Some(account))
+    }
+
+    fn deserialize_account(&self, bytes: &[u8]) -> Result<DbAccountInfo, SerializerError> {
+        Self::record(bytes)
+    }
+
+    /// A closed account, without lamports, is deleted, its last state being the closed account when the previous one is unknown
+    fn serialize_account_change(&self, before: Option<&DbAccountInfo>, after: &DbAccountInfo) -> Result<Vec<u8>, SerializerError> {
+        match (before, after.lamports) {
+            (before, 0) => Self::envelope(DebeziumOp::Delete, "account", after.slot, Some(before.unwrap_or(after)), None),
+            (None, _) => Self::envelope(DebeziumOp::Create, "account", after.slot, None, Some(after)),
+            (Some(before), _) => Self::envelope(DebeziumOp::Update, "account", after.slot, Some(before), Some(after)),
+        }
+    }
+
+    fn serialize_transaction(&self, transaction: &DbTransaction) -> Result<Vec<u8>, SerializerError> {
+        Self::envelope(DebeziumOp::Create, "transaction", transaction.slot, None, Some(transaction))
+    }
+
+    fn deserialize_transaction(&self, bytes: &[u8]) -> Result<DbTransaction, SerializerError> {
+        Self::record(bytes)
+    }
+
+    fn serialize_block(&self, block: &DbBlockInfo) -> Result<Vec<u8>, SerializerError> {
+        Self::envelope(DebeziumOp::Create, "block", block.slot, None, Some(block))
+    }
+
+    fn deserialize_block(&self, bytes: &[u8]) -> Result<DbBlockInfo, SerializerError> {
+        Self::record(bytes)
+    }
+}
+
+pub fn serializer(format: SerializationFormat) -> Box<dyn Serializer> {
+    match format {
+        SerializationFormat::Json => Box::new(JsonSerializer {}),
+        SerializationFormat::Borsh => Box::new(BorshSerializer {}),
+        SerializationFormat::Debezium => Box::new(DebeziumSerializer {}),
+    }
+}
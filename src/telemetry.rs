@@ -0,0 +1,96 @@
+//! OpenTelemetry spans of the write path. Spans are only recorded when the plugin is built
+//! with the `otel` feature, otherwise they compile to no-ops.
+
+use crate::config::GeyserPluginPostgresConfig;
+use log::*;
+#[cfg(feature = "otel")]
+use opentelemetry::global;
+#[cfg(feature = "otel")]
+use opentelemetry::trace::TraceContextExt;
+#[cfg(feature = "otel")]
+use opentelemetry::trace::Tracer;
+#[cfg(feature = "otel")]
+use opentelemetry::Context;
+
+pub const TRACER_NAME: &str = "solana-geyser-plugin-postgres";
+
+/// Installs the OTLP exporter sending spans to the configured `otel_endpoint`
+#[cfg(feature = "otel")]
+pub fn init(config: &GeyserPluginPostgresConfig) {
+    use opentelemetry_otlp::WithExportConfig;
+
+    if let Some(endpoint) = &config.otel_endpoint {
+        let result = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(opentelemetry_otlp::new_exporter().http().with_endpoint(endpoint))
+            .install_simple();
+        match result {
+            Ok(_) => info!("[telemetry] exporting spans to endpoint={}", endpoint),
+            Err(err) => error!("[telemetry] failed to install exporter endpoint={} error=[{}]", endpoint, err),
+        }
+    }
+}
+
+#[cfg(not(feature = "otel"))]
+pub fn init(config: &GeyserPluginPostgresConfig) {
+    if config.otel_endpoint.is_some() {
+        warn!("[telemetry] \"otel_endpoint\" is set but the plugin was built without the \"otel\" feature");
+    }
+}
+
+/// Flushes the spans not exported yet
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    global::shutdown_tracer_provider();
+}
+
+/// A span of the write path, ended when dropped
+pub struct WriteSpan {
+    #[cfg(feature = "otel")]
+    cx: Context,
+}
+
+impl WriteSpan {
+    /// Starts a span without a parent, e.g. for a flush
+    pub fn root(name: &'static str) -> Self {
+        #[cfg(feature = "otel")]
+        {
+            let span = global::tracer(TRACER_NAME).start(name);
+            Self { cx: Context::new().with_span(span) }
+        }
+        #[cfg(not(feature = "otel"))]
+        {
+            let _ = name;
+            Self {}
+        }
+    }
+
+    /// Starts a span nested in this one
+    pub fn child(&self, name: &'static str) -> Self {
+        #[cfg(feature = "otel")]
+        {
+            let span = global::tracer(TRACER_NAME).start_with_context(name, &self.cx);
+            Self { cx: self.cx.with_span(span) }
+        }
+        #[cfg(not(feature = "otel"))]
+        {
+            let _ = name;
+            Self {}
+        }
+    }
+
+    /// Records an attribute on the span
+    pub fn set_attribute(&self, key: &'static str, value: String) {
+        #[cfg(feature = "otel")]
+        self.cx.span().set_attribute(opentelemetry::KeyValue::new(key, value));
+        #[cfg(not(feature = "otel"))]
+        let _ = (key, value);
+    }
+}
+
+impl Drop for WriteSpan {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        self.cx.span().end();
+    }
+}
@@ -0,0 +1,55 @@
+//! Fixtures shared by the integration tests, included by each of them with `mod common;`
+#![allow(dead_code)]
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+pub static TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// Length of the data of an SPL token account, without extensions
+pub const TOKEN_ACCOUNT_LENGTH: usize = 165;
+/// Length of the data of an SPL mint, without extensions
+pub const MINT_LENGTH: usize = 82;
+
+/// The data of an initialized SPL token account of `mint` held by `owner`
+pub fn token_account_data(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Vec<u8> {
+    let mut data = vec![0; TOKEN_ACCOUNT_LENGTH];
+    data[0..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data[108] = 1;
+    data
+}
+
+/// The data of an initialized SPL mint without authorities
+pub fn mint_data(supply: u64, decimals: u8) -> Vec<u8> {
+    let mut data = vec![0; MINT_LENGTH];
+    data[36..44].copy_from_slice(&supply.to_le_bytes());
+    data[44] = decimals;
+    data[45] = 1;
+    data
+}
+
+/// Notifies the plugin of an update of an account of the token program, out of startup
+pub fn update_token_program_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, data: &[u8], slot: u64, write_version: u64) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 2039280,
+                owner: TOKEN_PROGRAM_ID.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version,
+                txn_signature: None,
+            }),
+            slot,
+            false,
+        )
+        .unwrap();
+}
@@ -0,0 +1,79 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+static TOKEN_ACCOUNT_OWNER: Pubkey = pubkey!("cpmaMZyBQiPxpeuxNsQhW7N8z1o9yaNdLgiPhWGUEiX");
+
+fn close_token_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, slot: u64, txn_signature: Option<&Signature>) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 0,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &[0; 165],
+                write_version: slot,
+                txn_signature,
+            }),
+            slot,
+            false,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_account_close_log() {
+    let slot = rand::random::<u32>() as u64;
+    let closed_with_signature = Keypair::new().pubkey();
+    let closed_without_signature = Keypair::new().pubkey();
+    let signature = Signature::new_unique();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_single_threaded.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+
+    for address in [&closed_with_signature, &closed_without_signature] {
+        client
+            .execute(
+                "INSERT INTO spl_token_account (pubkey, owner, mint, slot) VALUES ($1, $2, $3, $4)",
+                &[&address.to_string(), &TOKEN_ACCOUNT_OWNER.to_string(), &Keypair::new().pubkey().to_string(), &(slot as i64)],
+            )
+            .expect("Error inserting token account");
+    }
+    close_token_account(&mut geyser_plugin, &closed_with_signature, slot + 1, Some(&signature));
+    close_token_account(&mut geyser_plugin, &closed_without_signature, slot + 1, None);
+    sleep(Duration::from_secs(1));
+
+    for address in [&closed_with_signature, &closed_without_signature] {
+        let rows = client
+            .query("SELECT * from spl_token_account where pubkey=$1", &[&address.to_string()])
+            .expect("Error selecting account");
+        assert_eq!(rows.len(), 0, "Closed token account not removed");
+    }
+    let closing_signature = |client: &mut postgres::Client, address: &Pubkey| -> Option<String> {
+        client
+            .query_one("SELECT closed_at_slot, closing_signature from account_close_log where pubkey=$1", &[&address.to_string()])
+            .map(|row| {
+                assert_eq!(row.get::<_, i64>("closed_at_slot"), slot as i64 + 1, "Incorrect closed at slot");
+                row.get("closing_signature")
+            })
+            .expect("Close not logged")
+    };
+    assert_eq!(closing_signature(&mut client, &closed_with_signature), Some(signature.to_string()), "Incorrect closing signature");
+    assert_eq!(closing_signature(&mut client, &closed_without_signature), None, "Unexpected closing signature");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,100 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+static DATA: [u8; 12] = [0x00, 0xff, 0x29, 0x5c, 0x27, 0x00, 0x01, 0xb7, 0xcd, 0x00, 0x00, 0x80];
+
+fn stored_data(config_path: &str) -> Vec<u8> {
+    let address: Pubkey = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(config_path).unwrap();
+
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 2790960,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &DATA,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            0,
+            false,
+        )
+        .unwrap();
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let rows = client.query("SELECT data from account where pubkey=$1", &[&address.as_ref()]).expect("Error selecting accounts");
+    assert_eq!(rows.len(), 1, "Incorrect number of rows found");
+    let data: Vec<u8> = rows.first().expect("No results found").get("data");
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+    data
+}
+
+#[test]
+fn test_account_data_bytea() {
+    let data = stored_data(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_single_threaded_unknown.json"));
+    assert_eq!(data, DATA.to_vec(), "Incorrect data");
+}
+
+#[test]
+fn test_account_data_storage_size() {
+    // incompressible data, as the data of most accounts is to TOAST
+    let accounts = (0..20)
+        .map(|_| (Keypair::new().pubkey(), (0..1024).map(|_| rand::random::<u8>()).collect::<Vec<u8>>()))
+        .collect::<Vec<(Pubkey, Vec<u8>)>>();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_single_threaded_unknown.json")).unwrap();
+    for (address, data) in &accounts {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 2790960,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data,
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                0,
+                false,
+            )
+            .unwrap();
+    }
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let pubkeys = accounts.iter().map(|(address, _)| address.to_bytes().to_vec()).collect::<Vec<Vec<u8>>>();
+    let row = client
+        .query_one(
+            "SELECT SUM(pg_column_size(data))::BIGINT, SUM(pg_column_size(encode(data, 'base64')))::BIGINT, SUM(length(data))::BIGINT FROM account WHERE pubkey = ANY($1)",
+            &[&pubkeys],
+        )
+        .expect("Error measuring the data");
+    let (bytea_size, base64_size, data_size): (i64, i64, i64) = (row.get(0), row.get(1), row.get(2));
+    client.execute("DELETE FROM account WHERE pubkey = ANY($1)", &[&pubkeys]).expect("Error deleting accounts");
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+
+    assert_eq!(data_size, 20 * 1024, "Incorrect data length");
+    // the bytea column only adds the varlena header of each value to the data
+    assert!(bytea_size <= data_size + 20 * 4, "Bytea larger than the data: {} > {}", bytea_size, data_size);
+    assert!(bytea_size * 4 <= base64_size * 3 + 20 * 4, "Bytea not a quarter smaller than base64: {} vs {}", bytea_size, base64_size);
+}
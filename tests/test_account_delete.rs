@@ -0,0 +1,83 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+fn update_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, lamports: u64, owner: &Pubkey, data: &[u8], slot: u64) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports,
+                owner: owner.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version: slot,
+                txn_signature: None,
+            }),
+            slot,
+            false,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_account_delete() {
+    let slot = rand::random::<u32>() as u64;
+    let address: Pubkey = Keypair::new().pubkey();
+    let wallet: Pubkey = Keypair::new().pubkey();
+    let data = common::token_account_data(&Keypair::new().pubkey(), &Keypair::new().pubkey(), 0);
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_single_threaded.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let token_account_slots = |client: &mut postgres::Client| -> Vec<i64> {
+        client
+            .query("SELECT slot from spl_token_account where pubkey=$1", &[&address.to_string()])
+            .expect("Error selecting token account")
+            .iter()
+            .map(|row| row.get("slot"))
+            .collect()
+    };
+
+    // closed accounts are reassigned to the system program, no longer selected by the token program owner
+    update_account(&mut geyser_plugin, &address, 2039280, &OWNER, &data, slot);
+    update_account(&mut geyser_plugin, &address, 0, &system_program::id(), &[], slot + 1);
+    sleep(Duration::from_secs(1));
+    assert_eq!(token_account_slots(&mut client), Vec::<i64>::new(), "Closed token account not removed");
+    let rows = client
+        .query("SELECT closed_at_slot from account_close_log where pubkey=$1", &[&address.to_string()])
+        .expect("Error selecting close log");
+    assert_eq!(rows.len(), 1, "Close not logged");
+
+    // the account recreated at a later slot is not removed by a close notified again at the earlier slot
+    update_account(&mut geyser_plugin, &address, 2039280, &OWNER, &data, slot + 2);
+    update_account(&mut geyser_plugin, &address, 0, &system_program::id(), &[], slot + 1);
+    sleep(Duration::from_secs(1));
+    assert_eq!(token_account_slots(&mut client), vec![slot as i64 + 2], "Recreated token account removed");
+
+    // closed system accounts which were never token accounts are not logged
+    update_account(&mut geyser_plugin, &wallet, 0, &system_program::id(), &[], slot);
+    sleep(Duration::from_secs(1));
+    let rows = client
+        .query("SELECT closed_at_slot from account_close_log where pubkey=$1", &[&wallet.to_string()])
+        .expect("Error selecting close log");
+    assert_eq!(rows.len(), 0, "Unexpected close log of a system account");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,70 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+fn account(address: &Pubkey) -> DbAccountInfo {
+    DbAccountInfo::new(
+        &ReplicaAccountInfoV2 {
+            pubkey: address.as_ref(),
+            lamports: 1,
+            owner: OWNER.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data: &[1, 2, 3],
+            write_version: 0,
+            txn_signature: None,
+        },
+        1,
+    )
+}
+
+fn written(reader: &mut postgres::Client, addresses: &[Pubkey]) -> usize {
+    let keys = addresses.iter().map(|address| address.to_string()).collect::<Vec<String>>();
+    reader.query("SELECT pubkey from raw_account where pubkey = ANY($1)", &[&keys]).expect("Error selecting accounts").len()
+}
+
+#[test]
+fn test_account_flush_interval() {
+    let addresses = (0..3).map(|_| Keypair::new().pubkey()).collect::<Vec<Pubkey>>();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_account_flush_interval.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+
+    // the batch is far from full, the first account is written with the next one once the interval elapses
+    client.update_account(account(&addresses[0]), true).expect("Error buffering account");
+    let written_before_interval = written(&mut reader, &addresses[..1]);
+    sleep(Duration::from_millis(400));
+    client.update_account(account(&addresses[1]), true).expect("Error buffering account");
+    let written_after_interval = written(&mut reader, &addresses[..2]);
+
+    // without notifications, the idle worker flushes the buffer once the interval elapses
+    client.update_account(account(&addresses[2]), true).expect("Error buffering account");
+    client.flush_for_interval().expect("Error flushing accounts");
+    let written_before_idle_interval = written(&mut reader, &addresses[2..]);
+    sleep(Duration::from_millis(400));
+    client.flush_for_interval().expect("Error flushing accounts");
+    let written_after_idle_interval = written(&mut reader, &addresses[2..]);
+
+    let keys = addresses.iter().map(|address| address.to_string()).collect::<Vec<String>>();
+    reader.execute("DELETE FROM raw_account where pubkey = ANY($1)", &[&keys]).expect("Error deleting accounts");
+    assert_eq!(written_before_interval, 0, "Account flushed before the interval");
+    assert_eq!(written_after_interval, 2, "Accounts not flushed after the interval");
+    assert_eq!(written_before_idle_interval, 0, "Account flushed before the idle interval");
+    assert_eq!(written_after_idle_interval, 1, "Account not flushed by the idle worker");
+    reader.close().expect("Error disconnecting");
+}
@@ -0,0 +1,62 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+#[test]
+fn test_account_projection() {
+    let address: Pubkey = Keypair::new().pubkey();
+    let short_address: Pubkey = Keypair::new().pubkey();
+    let authority: Pubkey = Keypair::new().pubkey();
+    let amount = u64::MAX - 42;
+    // 8 bytes of discriminator, the authority then the amount
+    let mut data = [0_u8; 48];
+    data[8..40].copy_from_slice(authority.as_ref());
+    data[40..48].copy_from_slice(&amount.to_le_bytes());
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_account_projections.json")).unwrap();
+    // the amount is beyond the data of the short account
+    for (address, data) in [(&address, &data[..]), (&short_address, &data[..44])] {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 1,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data,
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                0,
+                false,
+            )
+            .unwrap();
+    }
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let projections = |client: &mut postgres::Client, address: &Pubkey| -> (Option<String>, Option<String>) {
+        let row = client
+            .query_one("SELECT projected_authority, projected_amount::TEXT from account where pubkey=$1", &[&address.as_ref()])
+            .expect("Error selecting account");
+        (row.get(0), row.get(1))
+    };
+    assert_eq!(projections(&mut client, &address), (Some(authority.to_string()), Some(amount.to_string())), "Incorrect projections");
+    assert_eq!(projections(&mut client, &short_address), (Some(authority.to_string()), None), "Field beyond the data projected");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
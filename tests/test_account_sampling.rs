@@ -0,0 +1,17 @@
+use solana_geyser_plugin_postgres::accounts_selector::is_account_sampled;
+use solana_sdk::pubkey::Pubkey;
+
+#[test]
+fn test_account_sampling() {
+    let pubkeys = (0..20000).map(|_| Pubkey::new_unique()).collect::<Vec<Pubkey>>();
+    let sampled = pubkeys.iter().filter(|pubkey| is_account_sampled(pubkey.as_ref(), 100)).collect::<Vec<&Pubkey>>();
+    assert!(sampled.len() > 140 && sampled.len() < 260, "Sampling ratio not honored: {}", sampled.len());
+
+    // the same accounts are sampled every time
+    for pubkey in &pubkeys {
+        assert_eq!(is_account_sampled(pubkey.as_ref(), 100), sampled.contains(&pubkey), "Sampling not deterministic");
+    }
+
+    // a rate of 1 keeps every account
+    assert!(pubkeys.iter().all(|pubkey| is_account_sampled(pubkey.as_ref(), 1)));
+}
@@ -0,0 +1,96 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use rand::Rng;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("AddressLookupTab1e1111111111111111111111111");
+
+fn update_lookup_table(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, authority: &Pubkey, addresses: &[Pubkey], lamports: u64, slot: u64) {
+    // the LookupTable variant, an active table extended at the slot, its authority then the addresses
+    let mut data = vec![0_u8; 56];
+    data[0..4].copy_from_slice(&1_u32.to_le_bytes());
+    data[4..12].copy_from_slice(&u64::MAX.to_le_bytes());
+    data[12..20].copy_from_slice(&slot.to_le_bytes());
+    data[21] = 1;
+    data[22..54].copy_from_slice(authority.as_ref());
+    for address in addresses {
+        data.extend_from_slice(address.as_ref());
+    }
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            slot,
+            false,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_address_lookup_table() {
+    let address: Pubkey = Keypair::new().pubkey();
+    let authority: Pubkey = Keypair::new().pubkey();
+    let addresses: Vec<Pubkey> = (0..3).map(|_| Keypair::new().pubkey()).collect();
+    let slot = rand::thread_rng().gen::<u32>() as u64;
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_address_lookup_table.json")).unwrap();
+    update_lookup_table(&mut geyser_plugin, &address, &authority, &addresses[..2], 1, slot);
+    update_lookup_table(&mut geyser_plugin, &address, &authority, &addresses, 1, slot + 1);
+    // an earlier state notified late does not overwrite the entries
+    update_lookup_table(&mut geyser_plugin, &address, &authority, &addresses[..1], 1, slot);
+    sleep(Duration::from_secs(1));
+
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let row = client
+        .query_one(
+            "SELECT authority, deactivation_slot, last_extended_slot from address_lookup_table where pubkey=$1",
+            &[&address.to_string()],
+        )
+        .expect("Error selecting lookup table");
+    assert_eq!(
+        (row.get(0), row.get(1), row.get(2)),
+        (Some(authority.to_string()), None::<i64>, slot as i64 + 1),
+        "Incorrect lookup table"
+    );
+    let entries: Vec<(i16, String)> = client
+        .query("SELECT index, address from address_lookup_table_entry where table_pubkey=$1 ORDER BY index", &[&address.to_string()])
+        .expect("Error selecting lookup table entries")
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect();
+    assert_eq!(
+        entries,
+        addresses.iter().enumerate().map(|(i, address)| (i as i16, address.to_string())).collect::<Vec<_>>(),
+        "Incorrect lookup table entries"
+    );
+
+    // closing the table removes its entries
+    update_lookup_table(&mut geyser_plugin, &address, &authority, &addresses, 0, slot + 2);
+    sleep(Duration::from_secs(1));
+    let count: i64 = client
+        .query_one("SELECT COUNT(*) from address_lookup_table_entry where table_pubkey=$1", &[&address.to_string()])
+        .expect("Error selecting lookup table entries")
+        .get(0);
+    assert_eq!(count, 0, "Entries of a closed lookup table kept");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
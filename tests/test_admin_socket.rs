@@ -0,0 +1,32 @@
+use std::io::Read;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+
+#[test]
+fn test_admin_socket() {
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_admin_socket.json")).unwrap();
+
+    sleep(Duration::from_secs(1));
+    let mut stream = UnixStream::connect("/tmp/geyser-postgres-test.sock").expect("Failed to connect to the admin socket");
+    let mut response = String::new();
+    stream.read_to_string(&mut response).expect("Error reading the admin socket");
+    let stats: serde_json::Value = serde_json::from_str(&response).expect("Invalid JSON");
+
+    assert_eq!(stats["pending_account_updates"], 0, "Incorrect pending account updates");
+    assert_eq!(stats["pending_transactions"], 0, "Incorrect pending transactions");
+    assert_eq!(stats["queue_depth"], 0, "Incorrect queue depth");
+    assert_eq!(stats["bytes_written"], 0, "Incorrect bytes written");
+    let workers = stats["workers"].as_array().expect("No workers found");
+    assert_eq!(workers.len(), 1, "Incorrect number of workers");
+    assert_eq!(workers[0]["state"], "idle", "Incorrect worker state");
+    assert!(stats["last_error"].is_null(), "Unexpected last error");
+
+    geyser_plugin.on_unload();
+    assert!(!Path::new("/tmp/geyser-postgres-test.sock").exists(), "Admin socket not removed");
+}
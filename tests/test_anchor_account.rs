@@ -0,0 +1,76 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("M2mx93ekt1fmXSVkTrUL9xVFHkmME8HTUi5Cyc5aF7K");
+const LISTING_DISCRIMINATOR: [u8; 8] = [51, 173, 177, 113, 25, 241, 109, 189];
+
+#[test]
+fn test_anchor_account() {
+    let listing_address: Pubkey = Keypair::new().pubkey();
+    let other_address: Pubkey = Keypair::new().pubkey();
+    let seller: Pubkey = Keypair::new().pubkey();
+    // the discriminator, the seller, the price, the expiry, the bump then whether the listing is active
+    let mut listing = vec![0_u8; 58];
+    listing[0..8].copy_from_slice(&LISTING_DISCRIMINATOR);
+    listing[8..40].copy_from_slice(seller.as_ref());
+    listing[40..48].copy_from_slice(&u64::MAX.to_le_bytes());
+    listing[48..56].copy_from_slice(&(-42_i64).to_le_bytes());
+    listing[56] = 254;
+    listing[57] = 1;
+    // an account of another type of the program
+    let mut other = listing.clone();
+    other[0] = 0;
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_anchor_accounts.json")).unwrap();
+    for (address, data) in [(&listing_address, &listing), (&other_address, &other)] {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 1,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data,
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                1,
+                false,
+            )
+            .unwrap();
+    }
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let row = client
+        .query_one(
+            "SELECT seller, price::TEXT, expiry, bump, active from test_anchor_listing where pubkey=$1",
+            &[&listing_address.to_string()],
+        )
+        .expect("Error selecting listing");
+    assert_eq!(
+        (row.get(0), row.get(1), row.get(2), row.get(3), row.get(4)),
+        (seller.to_string(), u64::MAX.to_string(), -42_i64, 254_i16, true),
+        "Incorrect listing"
+    );
+    let other_count: i64 = client
+        .query_one("SELECT COUNT(*) from test_anchor_listing where pubkey=$1", &[&other_address.to_string()])
+        .expect("Error selecting listing")
+        .get(0);
+    assert_eq!(other_count, 0, "Account of another type written");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,123 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_postgres::config::BatchCommit;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClientBuilder;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+fn account(address: &Pubkey, rent_epoch: u64, slot: u64) -> DbAccountInfo {
+    DbAccountInfo::new(
+        &ReplicaAccountInfoV2 {
+            pubkey: address.as_ref(),
+            lamports: 1,
+            owner: OWNER.as_ref(),
+            executable: false,
+            rent_epoch,
+            data: &[1, 2, 3],
+            write_version: 0,
+            txn_signature: None,
+        },
+        slot,
+    )
+}
+
+#[test]
+fn test_batch_autocommit() {
+    let addresses = [Keypair::new().pubkey(), Keypair::new().pubkey()];
+    let keys = addresses.iter().map(|address| address.to_string()).collect::<Vec<String>>();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_batch_autocommit.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    for address in &addresses {
+        client.update_account(account(address, 1, 1), false).expect("Error writing account");
+    }
+
+    // the second account of the batch fails once with a serialization failure, after the first one is committed
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let suffix = rand::random::<u32>();
+    reader
+        .batch_execute(&format!(
+            "
+                CREATE SEQUENCE test_batch_autocommit_{0};
+                CREATE FUNCTION test_batch_autocommit_{0}() RETURNS trigger AS $$ BEGIN
+                    IF NEW.pubkey = '{1}' AND nextval('test_batch_autocommit_{0}') = 1 THEN
+                        RAISE EXCEPTION 'account {1} rejected' USING ERRCODE = 'serialization_failure';
+                    END IF;
+                    RETURN NEW;
+                END $$ LANGUAGE plpgsql;
+                CREATE TRIGGER test_batch_autocommit_{0} BEFORE INSERT ON account_current_rent_epoch FOR EACH ROW EXECUTE FUNCTION test_batch_autocommit_{0}();
+            ",
+            suffix, addresses[1],
+        ))
+        .expect("Error creating trigger");
+    for address in &addresses {
+        client.update_account(account(address, 2, 2), true).expect("Error writing batch");
+    }
+    let attempts: i64 = reader
+        .query_one(&format!("SELECT last_value FROM test_batch_autocommit_{}", suffix), &[])
+        .expect("Error selecting attempts")
+        .get(0);
+    let history = reader
+        .query(
+            "SELECT pubkey, old_epoch, new_epoch, slot from account_rent_epoch_history where pubkey = ANY($1) ORDER BY pubkey",
+            &[&keys],
+        )
+        .expect("Error selecting rent epoch history")
+        .iter()
+        .map(|row| (row.get(0), row.get(1), row.get(2), row.get(3)))
+        .collect::<Vec<(String, i64, i64, i64)>>();
+    let current = reader
+        .query("SELECT rent_epoch from account_current_rent_epoch where pubkey = ANY($1)", &[&keys])
+        .expect("Error selecting rent epochs")
+        .iter()
+        .map(|row| row.get(0))
+        .collect::<Vec<i64>>();
+    reader
+        .batch_execute(&format!(
+            "
+                DROP TRIGGER test_batch_autocommit_{0} ON account_current_rent_epoch;
+                DROP FUNCTION test_batch_autocommit_{0};
+                DROP SEQUENCE test_batch_autocommit_{0};
+            ",
+            suffix
+        ))
+        .expect("Error dropping trigger");
+    reader
+        .execute("DELETE FROM account_rent_epoch_history where pubkey = ANY($1)", &[&keys])
+        .expect("Error deleting history");
+    reader
+        .execute("DELETE FROM account_current_rent_epoch where pubkey = ANY($1)", &[&keys])
+        .expect("Error deleting rent epochs");
+
+    assert_eq!(attempts, 2, "Failed batch not retried");
+    let mut expected = keys.iter().map(|key| (key.clone(), 1, 2, 2)).collect::<Vec<(String, i64, i64, i64)>>();
+    expected.sort();
+    assert_eq!(history, expected, "Retried batch applied twice");
+    assert_eq!(current, vec![2, 2], "Incorrect rent epochs");
+    reader.close().expect("Error disconnecting");
+}
+
+#[test]
+fn test_batch_autocommit_commitment_views() {
+    // the view writes would run outside of the transaction scoping them to their schema
+    let config = GeyserPluginPostgresConfig {
+        account_batch_commit: BatchCommit::Autocommit,
+        commitment_views: true,
+        ..GeyserPluginPostgresConfig::default()
+    };
+    let err = PostgresClientBuilder::build_pararallel_postgres_client(&config)
+        .err()
+        .expect("Autocommit accepted with the commitment views");
+    assert!(err.to_string().contains("commitment_views"), "Incompatible settings not reported: {}", err);
+}
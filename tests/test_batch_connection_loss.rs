@@ -0,0 +1,98 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+static TOKEN_ACCOUNT_OWNER: Pubkey = pubkey!("cpmaMZyBQiPxpeuxNsQhW7N8z1o9yaNdLgiPhWGUEiX");
+
+fn update_token_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey) {
+    let data = common::token_account_data(&Keypair::new().pubkey(), &TOKEN_ACCOUNT_OWNER, 0);
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 2039280,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            1,
+            true,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_batch_connection_loss() {
+    let first_address: Pubkey = Keypair::new().pubkey();
+    let second_address: Pubkey = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_single_threaded.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+
+    // the connection writing the second account of the batch is terminated once, after the first one is written
+    let suffix = &second_address.to_string()[..8].to_lowercase();
+    client
+        .batch_execute(&format!(
+            "
+                CREATE SEQUENCE test_connection_loss_{0};
+                CREATE FUNCTION test_connection_loss_{0}() RETURNS trigger AS $$ BEGIN
+                    IF NEW.pubkey = '{1}' THEN
+                        IF nextval('test_connection_loss_{0}') = 1 THEN
+                            PERFORM pg_terminate_backend(pg_backend_pid());
+                        END IF;
+                    END IF;
+                    RETURN NEW;
+                END $$ LANGUAGE plpgsql;
+                CREATE TRIGGER test_connection_loss_{0} BEFORE INSERT ON spl_token_account
+                    FOR EACH ROW EXECUTE FUNCTION test_connection_loss_{0}();
+            ",
+            suffix, second_address,
+        ))
+        .expect("Error creating trigger");
+
+    update_token_account(&mut geyser_plugin, &first_address);
+    update_token_account(&mut geyser_plugin, &second_address);
+    sleep(Duration::from_secs(2));
+
+    let terminations: i64 = client
+        .query_one(&format!("SELECT last_value FROM test_connection_loss_{}", suffix), &[])
+        .expect("Error selecting sequence")
+        .get(0);
+    client
+        .batch_execute(&format!(
+            "
+                DROP TRIGGER test_connection_loss_{0} ON spl_token_account;
+                DROP FUNCTION test_connection_loss_{0};
+                DROP SEQUENCE test_connection_loss_{0};
+            ",
+            suffix
+        ))
+        .expect("Error dropping trigger");
+    assert_eq!(terminations, 2, "Batch not retried after the connection loss");
+
+    // the retried batch wrote each account once, the first one being rolled back with the lost connection
+    for address in [&first_address, &second_address] {
+        let rows = client
+            .query("SELECT slot from spl_token_account where pubkey=$1", &[&address.to_string()])
+            .expect("Error selecting token account");
+        assert_eq!(rows.len(), 1, "Incorrect number of rows found");
+    }
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
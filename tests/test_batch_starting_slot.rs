@@ -0,0 +1,63 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+const CONFIG_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_batch_starting_slot.json");
+
+#[test]
+fn test_batch_starting_slot() {
+    let below_address: Pubkey = Keypair::new().pubkey();
+    let above_address: Pubkey = Keypair::new().pubkey();
+    let mut client = SimplePostgresClient::connect_to_db(&GeyserPluginPostgresConfig::read_from(CONFIG_PATH).unwrap()).expect("Failed to connect");
+    // above the slots of the other tests, so it is the highest available slot the bound is computed from
+    let highest_slot: i64 = client
+        .query_one("SELECT GREATEST(MAX(slot), 1::BIGINT << 50) + 2 FROM slot", &[])
+        .expect("Error selecting highest slot")
+        .get(0);
+    client
+        .execute("INSERT INTO slot (slot, status, updated_on) VALUES ($1, 'rooted', now())", &[&highest_slot])
+        .expect("Error inserting slot");
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(CONFIG_PATH).unwrap();
+    for (address, slot) in [(&below_address, highest_slot - 1), (&above_address, highest_slot + 1)] {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 1,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: &[1, 2, 3],
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                slot as u64,
+                true,
+            )
+            .unwrap();
+    }
+    geyser_plugin.notify_end_of_startup().unwrap();
+
+    sleep(Duration::from_secs(1));
+    // the slots of the startup accounts are written too
+    client.execute("DELETE FROM slot WHERE slot >= $1", &[&(highest_slot - 1)]).expect("Error deleting slots");
+    let count = |client: &mut postgres::Client, address: &Pubkey| -> usize { client.query("SELECT slot from account where pubkey=$1", &[&address.as_ref()]).expect("Error selecting account").len() };
+    assert_eq!(count(&mut client, &below_address), 0, "Startup account below the batch starting slot written");
+    assert_eq!(count(&mut client, &above_address), 1, "Startup account above the batch starting slot skipped");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use rand::Rng;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+#[test]
+fn test_bytes_written() {
+    let address: Pubkey = Keypair::new().pubkey();
+    let data = common::token_account_data(&Keypair::new().pubkey(), &Keypair::new().pubkey(), 0);
+    // slots of the same number of digits, so the queries of the account have the same length at each of them
+    let slot = rand::thread_rng().gen_range(100_000_000..900_000_000);
+    let account = |slot: u64| {
+        DbAccountInfo::new(
+            &ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 2039280,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &data,
+                write_version: 0,
+                txn_signature: None,
+            },
+            slot,
+        )
+    };
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_single_threaded.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    client.update_account(account(slot), false).unwrap();
+    let account_bytes = client.bytes_written();
+    assert!(account_bytes > 0, "Account query not counted");
+
+    // a startup batch of two updates is only counted once flushed, for its query writing the latest of them
+    client.update_account(account(slot + 1), true).unwrap();
+    assert_eq!(client.bytes_written(), account_bytes, "Buffered update counted");
+    client.update_account(account(slot + 2), true).unwrap();
+    assert_eq!(client.bytes_written(), 2 * account_bytes, "Incorrect bytes written by the batch");
+}
@@ -0,0 +1,27 @@
+use solana_geyser_plugin_postgres::postgres_client::cache::BoundedCache;
+use solana_geyser_plugin_postgres::postgres_client::cache::CacheStats;
+
+#[test]
+fn test_cache() {
+    let cache: BoundedCache<&str, u8> = BoundedCache::new("test", 2);
+    cache.insert("a", 1);
+    cache.insert("b", 2);
+    assert_eq!(cache.get(&"a"), Some(1), "Cached value not found");
+
+    // over capacity, "b" is the least recently used
+    cache.insert("c", 3);
+    assert_eq!(cache.get(&"b"), None, "Least recently used value not evicted");
+    assert_eq!(cache.get(&"a"), Some(1), "Recently used value evicted");
+    assert_eq!(cache.get(&"c"), Some(3), "Inserted value not found");
+
+    assert_eq!(
+        cache.stats(),
+        CacheStats {
+            size: 2,
+            hits: 3,
+            misses: 1,
+            evictions: 1,
+        },
+        "Incorrect cache stats"
+    );
+}
@@ -0,0 +1,116 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("CndyV3LdqHUfDLmE5naZjVN8rBZz4tqhSefbAnjHMFr");
+const CANDY_MACHINE_DISCRIMINATOR: [u8; 8] = [51, 173, 177, 113, 25, 241, 109, 189];
+
+fn push_string(data: &mut Vec<u8>, text: &str) {
+    data.extend_from_slice(&(text.len() as u32).to_le_bytes());
+    data.extend_from_slice(text.as_bytes());
+}
+
+/// Serializes a candy machine with a creator and either hidden settings or config line settings followed by its
+/// config lines
+fn candy_machine_data(collection_mint: &Pubkey, items_redeemed: u64, items_available: u64, hidden_settings: bool) -> Vec<u8> {
+    let mut data = CANDY_MACHINE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&[1, 4, 0, 0, 0, 0, 0, 0]);
+    data.extend_from_slice(Keypair::new().pubkey().as_ref());
+    data.extend_from_slice(Keypair::new().pubkey().as_ref());
+    data.extend_from_slice(collection_mint.as_ref());
+    data.extend_from_slice(&items_redeemed.to_le_bytes());
+    data.extend_from_slice(&items_available.to_le_bytes());
+    push_string(&mut data, "DROP");
+    data.extend_from_slice(&500_u16.to_le_bytes());
+    data.extend_from_slice(&0_u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(&1_u32.to_le_bytes());
+    data.extend_from_slice(Keypair::new().pubkey().as_ref());
+    data.extend_from_slice(&[1, 100]);
+    match hidden_settings {
+        true => {
+            data.push(0);
+            data.push(1);
+            push_string(&mut data, "Drop #$ID+1$");
+            push_string(&mut data, "https://example.com/drop.json");
+            data.extend_from_slice(&[7; 32]);
+        }
+        false => {
+            data.push(1);
+            push_string(&mut data, "Drop #");
+            data.extend_from_slice(&4_u32.to_le_bytes());
+            push_string(&mut data, "https://example.com/");
+            data.extend_from_slice(&9_u32.to_le_bytes());
+            data.push(0);
+            data.push(0);
+            // the config lines
+            data.resize(data.len() + items_available as usize * 13 + 64, 0);
+        }
+    }
+    data
+}
+
+#[test]
+fn test_candy_machine() {
+    let config_lines_address: Pubkey = Keypair::new().pubkey();
+    let hidden_address: Pubkey = Keypair::new().pubkey();
+    let collection_mint: Pubkey = Keypair::new().pubkey();
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_candy_machine.json")).unwrap();
+    for (address, data, slot) in [
+        (&config_lines_address, candy_machine_data(&collection_mint, 3, 10, false), 2),
+        (&hidden_address, candy_machine_data(&collection_mint, 5, 1000, true), 2),
+        // an earlier state notified late is ignored
+        (&config_lines_address, candy_machine_data(&collection_mint, 1, 10, false), 1),
+    ] {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 1,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: &data,
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                slot,
+                false,
+            )
+            .unwrap();
+    }
+    sleep(Duration::from_secs(1));
+
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let rows: Vec<(String, String, i64, i64, bool)> = client
+        .query(
+            "SELECT pubkey, symbol, items_redeemed, items_available, hidden_settings from candy_machine where collection_mint=$1 ORDER BY items_available",
+            &[&collection_mint.to_string()],
+        )
+        .expect("Error selecting candy machines")
+        .iter()
+        .map(|row| (row.get(0), row.get(1), row.get(2), row.get(3), row.get(4)))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            (config_lines_address.to_string(), "DROP".to_string(), 3, 10, false),
+            (hidden_address.to_string(), "DROP".to_string(), 5, 1000, true),
+        ],
+        "Incorrect candy machines"
+    );
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
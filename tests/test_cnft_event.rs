@@ -0,0 +1,151 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::InnerInstructions;
+use solana_transaction_status::TransactionStatusMeta;
+
+static BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+static NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+static COMPRESSION_PROGRAM_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+const MINT_V1_DISCRIMINATOR: [u8; 8] = [145, 98, 192, 118, 184, 147, 118, 104];
+const TRANSFER_DISCRIMINATOR: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
+
+fn notify_transaction(geyser_plugin: &mut GeyserPluginPostgres, instruction: Instruction, payer: &Keypair, inner_instructions: Vec<CompiledInstruction>, slot: u64) -> Vec<u8> {
+    let transaction = Transaction::new(&[payer], Message::new(&[instruction], Some(&payer.pubkey())), Hash::new_unique());
+    let signature = transaction.signatures[0];
+    let transaction = SanitizedTransaction::from_transaction_for_tests(transaction);
+    let transaction_status_meta = TransactionStatusMeta {
+        inner_instructions: Some(vec![InnerInstructions {
+            index: 0,
+            instructions: inner_instructions,
+        }]),
+        ..TransactionStatusMeta::default()
+    };
+    geyser_plugin
+        .notify_transaction(
+            ReplicaTransactionInfoVersions::V0_0_2(&ReplicaTransactionInfoV2 {
+                signature: &signature,
+                is_vote: false,
+                transaction: &transaction,
+                transaction_status_meta: &transaction_status_meta,
+                index: 0,
+            }),
+            slot,
+        )
+        .unwrap();
+    signature.as_ref().to_vec()
+}
+
+#[test]
+fn test_cnft_event() {
+    let slot = rand::random::<u32>() as u64;
+    let tree = Keypair::new().pubkey();
+    let tree_authority = Pubkey::find_program_address(&[tree.as_ref()], &BUBBLEGUM_PROGRAM_ID).0;
+    let owner = Keypair::new();
+    let new_owner = Keypair::new().pubkey();
+    let leaf_index: u32 = 7;
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_cnft_events.json")).unwrap();
+
+    // mint_v1, the leaf index being logged through the noop program
+    let mint = Instruction::new_with_bytes(
+        BUBBLEGUM_PROGRAM_ID,
+        &MINT_V1_DISCRIMINATOR,
+        vec![
+            AccountMeta::new(tree_authority, false),
+            AccountMeta::new_readonly(owner.pubkey(), false),
+            AccountMeta::new_readonly(owner.pubkey(), false),
+            AccountMeta::new(tree, false),
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new_readonly(owner.pubkey(), true),
+            AccountMeta::new_readonly(NOOP_PROGRAM_ID, false),
+            AccountMeta::new_readonly(COMPRESSION_PROGRAM_ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let message = Message::new(std::slice::from_ref(&mint), Some(&owner.pubkey()));
+    let noop_index = message.account_keys.iter().position(|key| key == &NOOP_PROGRAM_ID).unwrap() as u8;
+    let mut leaf_schema_event = vec![1, 0, 0];
+    leaf_schema_event.extend_from_slice(&[0; 96]);
+    leaf_schema_event.extend_from_slice(&(leaf_index as u64).to_le_bytes());
+    leaf_schema_event.extend_from_slice(&[0; 96]);
+    let mint_signature = notify_transaction(
+        &mut geyser_plugin,
+        mint,
+        &owner,
+        vec![CompiledInstruction::new_from_raw_parts(noop_index, leaf_schema_event, vec![])],
+        slot,
+    );
+
+    // transfer
+    let mut transfer_data = TRANSFER_DISCRIMINATOR.to_vec();
+    transfer_data.extend_from_slice(&[0; 96]);
+    transfer_data.extend_from_slice(&(leaf_index as u64).to_le_bytes());
+    transfer_data.extend_from_slice(&leaf_index.to_le_bytes());
+    let transfer = Instruction::new_with_bytes(
+        BUBBLEGUM_PROGRAM_ID,
+        &transfer_data,
+        vec![
+            AccountMeta::new_readonly(tree_authority, false),
+            AccountMeta::new_readonly(owner.pubkey(), true),
+            AccountMeta::new_readonly(owner.pubkey(), false),
+            AccountMeta::new_readonly(new_owner, false),
+            AccountMeta::new(tree, false),
+            AccountMeta::new_readonly(NOOP_PROGRAM_ID, false),
+            AccountMeta::new_readonly(COMPRESSION_PROGRAM_ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let transfer_signature = notify_transaction(&mut geyser_plugin, transfer, &owner, vec![], slot + 1);
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let rows = client
+        .query(
+            "SELECT event_type, leaf_index, new_owner, slot, signature from cnft_event where tree=$1 ORDER BY slot",
+            &[&tree.to_string()],
+        )
+        .expect("Error selecting events");
+    let events = rows
+        .iter()
+        .map(|row| {
+            (
+                row.get::<_, String>("event_type"),
+                row.get::<_, i64>("leaf_index"),
+                row.get::<_, Option<String>>("new_owner"),
+                row.get::<_, i64>("slot"),
+                row.get::<_, Vec<u8>>("signature"),
+            )
+        })
+        .collect::<Vec<(String, i64, Option<String>, i64, Vec<u8>)>>();
+    assert_eq!(
+        events,
+        vec![
+            ("mint".to_string(), leaf_index as i64, Some(owner.pubkey().to_string()), slot as i64, mint_signature),
+            ("transfer".to_string(), leaf_index as i64, Some(new_owner.to_string()), slot as i64 + 1, transfer_signature),
+        ],
+        "Incorrect compressed NFT events"
+    );
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
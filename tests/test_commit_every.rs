@@ -0,0 +1,153 @@
+mod common;
+
+use std::collections::HashSet;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+static UNKNOWN_OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+const FAILING_CONFIG_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_commit_every_failing_connection.json");
+
+fn token_account(address: &Pubkey, slot: u64) -> DbAccountInfo {
+    let data = common::token_account_data(&Keypair::new().pubkey(), &Keypair::new().pubkey(), 0);
+    DbAccountInfo::new(
+        &ReplicaAccountInfoV2 {
+            pubkey: address.as_ref(),
+            lamports: 2039280,
+            owner: OWNER.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data: &data,
+            write_version: 0,
+            txn_signature: None,
+        },
+        slot,
+    )
+}
+
+/// The transactions which inserted the rows selected by `query`, by their `xmin`
+fn inserting_transactions(reader: &mut postgres::Client, query: &str, keys: &[String]) -> Vec<String> {
+    reader
+        .query(query, &[&keys])
+        .expect("Error selecting rows")
+        .iter()
+        .map(|row| row.get::<_, String>("xmin"))
+        .collect::<Vec<String>>()
+}
+
+#[test]
+fn test_commit_every() {
+    let slot = rand::random::<u32>() as u64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_commit_every.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let addresses = (0..4).map(|_| Keypair::new().pubkey()).collect::<Vec<Pubkey>>();
+    let pubkeys = addresses.iter().map(|address| address.to_string()).collect::<Vec<String>>();
+    let account_query = "SELECT xmin::TEXT AS xmin FROM spl_token_account WHERE pubkey = ANY($1)";
+
+    // the first account flushes are not visible until the third one commits the group
+    client.update_account(token_account(&addresses[0], slot), false).unwrap();
+    client.update_account(token_account(&addresses[1], slot), false).unwrap();
+    assert!(inserting_transactions(&mut reader, account_query, &pubkeys).is_empty(), "Grouped account flushes committed early");
+    client.update_account(token_account(&addresses[2], slot), false).unwrap();
+    let account_transactions = inserting_transactions(&mut reader, account_query, &pubkeys);
+    assert_eq!(account_transactions.len(), 3, "Incorrect number of accounts committed with the group");
+    assert_eq!(account_transactions.iter().collect::<HashSet<&String>>().len(), 1, "Account flushes not grouped in a single commit");
+
+    // the slots, without commit_every, are committed on every flush
+    client.update_slot_status(slot, None, SlotStatus::Processed).unwrap();
+    client.update_slot_status(slot + 1, None, SlotStatus::Processed).unwrap();
+    let slots = [slot.to_string(), (slot + 1).to_string()];
+    let slot_transactions = inserting_transactions(&mut reader, "SELECT xmin::TEXT AS xmin FROM slot WHERE slot::TEXT = ANY($1)", &slots);
+    assert_eq!(slot_transactions.len(), 2, "Slots not committed on every flush");
+    assert_eq!(slot_transactions.iter().collect::<HashSet<&String>>().len(), 2, "Slot flushes grouped");
+
+    // the open group is committed on demand, as when the worker is idle or stops
+    client.update_account(token_account(&addresses[3], slot), false).unwrap();
+    assert_eq!(inserting_transactions(&mut reader, account_query, &pubkeys).len(), 3, "Account of the open group visible");
+    client.commit_grouped_writes().unwrap();
+    assert_eq!(inserting_transactions(&mut reader, account_query, &pubkeys).len(), 4, "Open group not committed");
+
+    reader.execute("DELETE FROM slot WHERE slot::TEXT = ANY($1)", &[&slots.to_vec()]).expect("Error deleting slots");
+    reader.close().expect("Error disconnecting");
+}
+
+#[test]
+fn test_commit_every_failing_connection() {
+    // create the database of the connection whose commits fail
+    let config = GeyserPluginPostgresConfig::read_from(FAILING_CONFIG_PATH).expect("Failed to read config");
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let exists = reader
+        .query_opt("SELECT 1 FROM pg_database WHERE datname='solana_commit_failure'", &[])
+        .expect("Error selecting databases")
+        .is_some();
+    if !exists {
+        reader.batch_execute("CREATE DATABASE solana_commit_failure").expect("Error creating database");
+    }
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(FAILING_CONFIG_PATH).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    // a deferred trigger fails the commits of the unknown accounts, and the commits of their replays
+    let mut failing_reader = SimplePostgresClient::connect_to_named_db(&config, "failing").expect("Failed to connect");
+    failing_reader
+        .batch_execute(
+            "CREATE OR REPLACE FUNCTION fail_commit() RETURNS trigger AS $$ BEGIN RAISE EXCEPTION 'commit refused'; END $$ LANGUAGE plpgsql;
+            DROP TRIGGER IF EXISTS fail_commit ON account;
+            CREATE CONSTRAINT TRIGGER fail_commit AFTER INSERT OR UPDATE ON account DEFERRABLE INITIALLY DEFERRED FOR EACH ROW EXECUTE FUNCTION fail_commit();",
+        )
+        .expect("Error creating trigger");
+
+    let slot = rand::random::<u32>() as u64;
+    let token_address = Keypair::new().pubkey();
+    let unknown_address = Keypair::new().pubkey();
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    client.update_account(token_account(&token_address, slot), false).unwrap();
+    client
+        .update_account(
+            DbAccountInfo::new(
+                &ReplicaAccountInfoV2 {
+                    pubkey: unknown_address.as_ref(),
+                    lamports: 1,
+                    owner: UNKNOWN_OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: &[1, 2, 3],
+                    write_version: 0,
+                    txn_signature: None,
+                },
+                slot,
+            ),
+            false,
+        )
+        .unwrap();
+    assert!(client.commit_grouped_writes().is_err(), "Failed commit of the group not reported");
+
+    // the failing connection doesn't keep the writes grouped on the other one from committing
+    let token_accounts = inserting_transactions(&mut reader, "SELECT xmin::TEXT AS xmin FROM spl_token_account WHERE pubkey = ANY($1)", &[token_address.to_string()]);
+    assert_eq!(token_accounts.len(), 1, "Grouped writes of the other connection not committed");
+    let unknown_accounts = failing_reader
+        .query("SELECT slot FROM account WHERE pubkey=$1", &[&unknown_address.as_ref()])
+        .expect("Error selecting account");
+    assert!(unknown_accounts.is_empty(), "Write of the failing connection committed");
+
+    failing_reader.batch_execute("DROP TRIGGER fail_commit ON account;").expect("Error dropping trigger");
+    failing_reader.close().expect("Error disconnecting");
+    reader.close().expect("Error disconnecting");
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+fn update_token_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, amount: u64, slot: u64) {
+    common::update_token_program_account(geyser_plugin, address, &common::token_account_data(&Pubkey::default(), address, amount), slot, 0);
+}
+
+#[test]
+fn test_commitment_views() {
+    let address = Keypair::new().pubkey();
+    let slot = rand::random::<u32>() as u64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_commitment_views.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let amounts = |client: &mut postgres::Client| -> Vec<Option<String>> {
+        ["confirmed", "rooted"]
+            .iter()
+            .map(|schema| {
+                client
+                    .query_opt(&format!("SELECT amount::TEXT from {}.spl_token_account where pubkey=$1", schema), &[&address.to_string()])
+                    .expect("Error selecting token account")
+                    .map(|row| row.get(0))
+            })
+            .collect()
+    };
+
+    // a processed update is in neither view
+    update_token_account(&mut geyser_plugin, &address, 10, slot);
+    geyser_plugin.update_slot_status(slot, None, SlotStatus::Processed).unwrap();
+    sleep(Duration::from_secs(1));
+    assert_eq!(amounts(&mut client), vec![None, None], "Processed update written to a view");
+
+    geyser_plugin.update_slot_status(slot, None, SlotStatus::Confirmed).unwrap();
+    sleep(Duration::from_secs(1));
+    assert_eq!(amounts(&mut client), vec![Some("10".to_string()), None], "Confirmed update not written to the confirmed view only");
+
+    geyser_plugin.update_slot_status(slot, None, SlotStatus::Rooted).unwrap();
+    sleep(Duration::from_secs(1));
+    assert_eq!(amounts(&mut client), vec![Some("10".to_string()), Some("10".to_string())], "Rooted update not promoted");
+
+    // slot + 1 is confirmed then abandoned when slot + 2 roots
+    update_token_account(&mut geyser_plugin, &address, 20, slot + 1);
+    geyser_plugin.update_slot_status(slot + 1, Some(slot), SlotStatus::Confirmed).unwrap();
+    sleep(Duration::from_secs(1));
+    assert_eq!(amounts(&mut client), vec![Some("20".to_string()), Some("10".to_string())], "Confirmed update not written");
+
+    geyser_plugin.update_slot_status(slot + 2, Some(slot), SlotStatus::Rooted).unwrap();
+    sleep(Duration::from_secs(1));
+    assert_eq!(amounts(&mut client), vec![Some("10".to_string()), Some("10".to_string())], "Abandoned confirmed update not restored");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+#[test]
+fn test_connection_pool() {
+    let mint: Pubkey = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_connection_pool.json")).unwrap();
+
+    // the 4 workers write through the 2 connections of the pool
+    for slot in 1..=20 {
+        let data = common::token_account_data(&mint, &Keypair::new().pubkey(), 0);
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: Keypair::new().pubkey().as_ref(),
+                    lamports: 2039280,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: &data,
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                slot,
+                false,
+            )
+            .unwrap();
+    }
+    sleep(Duration::from_secs(2));
+
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let count: i64 = client
+        .query_one("SELECT COUNT(*) FROM spl_token_account WHERE mint=$1", &[&mint.to_string()])
+        .expect("Error counting token accounts")
+        .get(0);
+    assert_eq!(count, 20, "Incorrect token account count");
+    // the connection of this client has the application name of the plugin's
+    let connections: i64 = client
+        .query_one("SELECT COUNT(*) FROM pg_stat_activity WHERE application_name = 'test_connection_pool' AND pid != pg_backend_pid()", &[])
+        .expect("Error counting connections")
+        .get(0);
+    assert!((1..=2).contains(&connections), "Incorrect connection count: {}", connections);
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
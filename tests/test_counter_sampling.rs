@@ -0,0 +1,21 @@
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::counters::inc_sampled_counter;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+
+#[test]
+fn test_counter_sampling() {
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_counter_sampling.json")).unwrap();
+    geyser_plugin.on_unload();
+
+    // the rates compiled into the counter are replaced by the configured ones at its first increment
+    let counter = Mutex::new(solana_metrics::create_counter!("geyser-plugin-postgres-test-us", 100_000, 100_000));
+    inc_sampled_counter(&counter, log::Level::Debug, 1);
+    let counter = counter.lock().unwrap();
+    assert_eq!(counter.lograte.load(Ordering::Relaxed), 7, "Log rate not configured");
+    assert_eq!(counter.metricsrate.load(Ordering::Relaxed), 3000, "Metrics rate not configured");
+    assert_eq!(counter.counts.load(Ordering::Relaxed), 1, "Counter not incremented");
+}
@@ -0,0 +1,47 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+
+/// Names of the indexes of the table in the test schema
+fn indexes(client: &mut postgres::Client, table: &str) -> Vec<String> {
+    client
+        .query(
+            "SELECT indexname FROM pg_indexes WHERE schemaname='geyser_test_create_indexes' AND tablename=$1 ORDER BY indexname",
+            &[&table],
+        )
+        .expect("Error selecting indexes")
+        .iter()
+        .map(|row| row.get(0))
+        .collect()
+}
+
+#[test]
+fn test_create_indexes() {
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_create_indexes.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    let mut client = SimplePostgresClient::connect_to_db(&GeyserPluginPostgresConfig { schema: None, ..config }).expect("Failed to connect");
+    let indexes_at_load = indexes(&mut client, "spl_token_account");
+    let slot_indexes_at_load = indexes(&mut client, "slot");
+    // the comment on the owner index is deferred with it, the connection no handler is routed to, whose database
+    // doesn't exist, being left out
+    geyser_plugin.notify_end_of_startup().unwrap();
+    let indexes_after_startup = indexes(&mut client, "spl_token_account");
+    let slot_indexes_after_startup = indexes(&mut client, "slot");
+    // the indexes are only created by the first notification
+    client.batch_execute("DROP INDEX geyser_test_create_indexes.slot_epoch").expect("Error dropping index");
+    geyser_plugin.notify_end_of_startup().unwrap();
+    let slot_indexes_after_renotify = indexes(&mut client, "slot");
+    geyser_plugin.on_unload();
+    client.batch_execute("DROP SCHEMA geyser_test_create_indexes CASCADE").expect("Error dropping schema");
+
+    // the unique index of the upserts is created with the table
+    assert_eq!(indexes_at_load, vec!["spl_token_account_owner_pair".to_string()]);
+    assert_eq!(slot_indexes_at_load, vec!["slot_pkey".to_string()]);
+    assert!(indexes_after_startup.contains(&"spl_token_account_owner".to_string()), "Index not created after startup");
+    assert!(indexes_after_startup.contains(&"spl_token_account_mint".to_string()), "Index not created after startup");
+    assert_eq!(slot_indexes_after_startup, vec!["slot_epoch".to_string(), "slot_pkey".to_string()]);
+    assert_eq!(slot_indexes_after_renotify, vec!["slot_pkey".to_string()], "Indexes created again by a later notification");
+    client.close().expect("Error disconnecting");
+}
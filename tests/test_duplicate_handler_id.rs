@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::postgres_client::register_account_handler;
+use solana_geyser_plugin_postgres::postgres_client::AccountHandler;
+use solana_geyser_plugin_postgres::postgres_client::AccountHandlerId;
+use solana_geyser_plugin_postgres::postgres_client::AccountQuery;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+
+struct NamedAccountHandler {
+    table: &'static str,
+}
+
+impl AccountHandler for NamedAccountHandler {
+    fn init(&self, _config: &GeyserPluginPostgresConfig) -> String {
+        format!("CREATE TABLE IF NOT EXISTS {} (pubkey VARCHAR(44) PRIMARY KEY);", self.table)
+    }
+
+    fn account_match(&self, _account: &DbAccountInfo) -> bool {
+        true
+    }
+
+    fn account_update(&self, _account: &DbAccountInfo) -> AccountQuery {
+        AccountQuery::default()
+    }
+}
+
+#[test]
+fn test_duplicate_handler_id() {
+    let config = GeyserPluginPostgresConfig::default();
+    let mut account_handlers: HashMap<AccountHandlerId, Box<dyn AccountHandler>> = HashMap::default();
+    register_account_handler(&mut account_handlers, AccountHandlerId::TokenManager, Box::new(NamedAccountHandler { table: "first" })).expect("Error registering handler");
+
+    let err = register_account_handler(&mut account_handlers, AccountHandlerId::TokenManager, Box::new(NamedAccountHandler { table: "second" })).expect_err("Duplicate handler id registered");
+    assert_eq!(err.0, AccountHandlerId::TokenManager, "Incorrect duplicate handler id");
+    assert!(err.to_string().contains("TokenManager"), "Duplicate handler id not reported: {}", err);
+    assert_eq!(account_handlers.len(), 1, "Incorrect handler count");
+    assert!(account_handlers[&AccountHandlerId::TokenManager].init(&config).contains("first"), "Registered handler replaced");
+}
@@ -0,0 +1,78 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoVersions;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::TransactionStatusMeta;
+
+#[test]
+fn test_epoch_schedule() {
+    let config = GeyserPluginPostgresConfig::default();
+    assert_eq!(config.epoch_schedule().get_epoch(0), 0);
+    assert_eq!(config.epoch_schedule().get_epoch(431_999), 0);
+    assert_eq!(config.epoch_schedule().get_epoch(432_000), 1);
+    assert_eq!(config.epoch_schedule().get_epoch(190_080_000), 440);
+
+    // warmup epochs start at 32 slots and double until slots_per_epoch
+    let config = GeyserPluginPostgresConfig {
+        epoch_warmup: true,
+        ..GeyserPluginPostgresConfig::default()
+    };
+    assert_eq!(config.epoch_schedule().get_epoch(31), 0);
+    assert_eq!(config.epoch_schedule().get_epoch(32), 1);
+    assert_eq!(config.epoch_schedule().get_epoch(96), 2);
+}
+
+#[test]
+fn test_epoch_columns() {
+    let slot = rand::random::<u32>() as u64;
+    let expected_epoch = (slot / 1000) as i64;
+
+    let keypair = Keypair::new();
+    let message = Message::new(&[system_instruction::transfer(&keypair.pubkey(), &keypair.pubkey(), 1)], Some(&keypair.pubkey()));
+    let transaction = Transaction::new(&[&keypair], message, Hash::default());
+    let signature = transaction.signatures[0];
+    let transaction = SanitizedTransaction::from_transaction_for_tests(transaction);
+    let transaction_status_meta = TransactionStatusMeta::default();
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_epoch.json")).unwrap();
+    geyser_plugin.update_slot_status(slot, None, SlotStatus::Confirmed).unwrap();
+    geyser_plugin
+        .notify_transaction(
+            ReplicaTransactionInfoVersions::V0_0_2(&ReplicaTransactionInfoV2 {
+                signature: &signature,
+                is_vote: false,
+                transaction: &transaction,
+                transaction_status_meta: &transaction_status_meta,
+                index: 0,
+            }),
+            slot,
+        )
+        .unwrap();
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let slot_epoch: Option<i64> = client.query_one("SELECT epoch from slot where slot=$1", &[&(slot as i64)]).expect("Error selecting slot").get("epoch");
+    assert_eq!(slot_epoch, Some(expected_epoch), "Incorrect slot epoch");
+    let transaction_epoch: Option<i64> = client
+        .query_one("SELECT epoch from transaction where signature=$1", &[&signature.as_ref()])
+        .expect("Error selecting transaction")
+        .get("epoch");
+    assert_eq!(transaction_epoch, Some(expected_epoch), "Incorrect transaction epoch");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
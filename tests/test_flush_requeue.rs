@@ -0,0 +1,82 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+fn account(address: &Pubkey) -> DbAccountInfo {
+    DbAccountInfo::new(
+        &ReplicaAccountInfoV2 {
+            pubkey: address.as_ref(),
+            lamports: 1,
+            owner: OWNER.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data: &[1, 2, 3],
+            write_version: 0,
+            txn_signature: None,
+        },
+        1,
+    )
+}
+
+#[test]
+fn test_flush_requeue() {
+    let written: Pubkey = Keypair::new().pubkey();
+    let rejected: Pubkey = Keypair::new().pubkey();
+    let keys = vec![written.to_string(), rejected.to_string()];
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_flush_requeue.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    // the second account of the batch is rejected once, failing the first flush midway
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let suffix = rand::random::<u32>();
+    reader
+        .batch_execute(&format!(
+            "
+                CREATE SEQUENCE test_flush_requeue_{0};
+                CREATE FUNCTION test_flush_requeue_{0}() RETURNS trigger AS $$ BEGIN
+                    IF NEW.pubkey = '{1}' AND nextval('test_flush_requeue_{0}') = 1 THEN
+                        RAISE EXCEPTION 'account {1} rejected';
+                    END IF;
+                    RETURN NEW;
+                END $$ LANGUAGE plpgsql;
+                CREATE TRIGGER test_flush_requeue_{0} BEFORE INSERT ON raw_account FOR EACH ROW EXECUTE FUNCTION test_flush_requeue_{0}();
+            ",
+            suffix, rejected,
+        ))
+        .expect("Error creating trigger");
+
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    client.update_account(account(&written), true).expect("Error buffering account");
+    let failed_flush = client.update_account(account(&rejected), true);
+    let rows_after_failure = reader.query("SELECT pubkey from raw_account where pubkey = ANY($1)", &[&keys]).expect("Error selecting accounts").len();
+    let end_of_startup = client.notify_end_of_startup();
+    let rows_after_startup = reader.query("SELECT pubkey from raw_account where pubkey = ANY($1)", &[&keys]).expect("Error selecting accounts").len();
+    reader
+        .batch_execute(&format!(
+            "
+                DROP TRIGGER test_flush_requeue_{0} ON raw_account;
+                DROP FUNCTION test_flush_requeue_{0};
+                DROP SEQUENCE test_flush_requeue_{0};
+            ",
+            suffix
+        ))
+        .expect("Error dropping trigger");
+    reader.execute("DELETE FROM raw_account where pubkey = ANY($1)", &[&keys]).expect("Error deleting accounts");
+
+    assert!(failed_flush.is_err(), "Rejected flush succeeded");
+    assert_eq!(rows_after_failure, 0, "Failed flush partially written");
+    assert!(end_of_startup.is_ok(), "Queued accounts not flushed: {:?}", end_of_startup.err());
+    assert_eq!(rows_after_startup, 2, "Queued accounts lost");
+    reader.close().expect("Error disconnecting");
+}
@@ -0,0 +1,70 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoVersions;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_transaction;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_transaction_status::TransactionStatusMeta;
+
+#[test]
+fn test_fork_event() {
+    let slot = rand::random::<u32>() as u64;
+    let keypair = Keypair::new();
+    let transaction = system_transaction::transfer(&keypair, &Keypair::new().pubkey(), 42, Hash::default());
+    let signature = transaction.signatures[0];
+    let transaction = SanitizedTransaction::from_transaction_for_tests(transaction);
+    let transaction_status_meta = TransactionStatusMeta::default();
+    let transaction_info = ReplicaTransactionInfoV2 {
+        signature: &signature,
+        is_vote: false,
+        transaction: &transaction,
+        transaction_status_meta: &transaction_status_meta,
+        index: 0,
+    };
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_fork_events.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+
+    // slot + 1 and slot + 2 fork off slot, the chain through slot + 3 roots
+    geyser_plugin.update_slot_status(slot, None, SlotStatus::Rooted).unwrap();
+    geyser_plugin.update_slot_status(slot + 1, Some(slot), SlotStatus::Processed).unwrap();
+    geyser_plugin.notify_transaction(ReplicaTransactionInfoVersions::V0_0_2(&transaction_info), slot + 1).unwrap();
+    geyser_plugin.update_slot_status(slot + 2, Some(slot + 1), SlotStatus::Processed).unwrap();
+    geyser_plugin.update_slot_status(slot + 3, Some(slot), SlotStatus::Processed).unwrap();
+    geyser_plugin.update_slot_status(slot + 4, Some(slot + 3), SlotStatus::Processed).unwrap();
+    geyser_plugin.update_slot_status(slot + 3, Some(slot), SlotStatus::Rooted).unwrap();
+
+    sleep(Duration::from_secs(1));
+    let rows = client
+        .query("SELECT first_slot, last_slot, slots, dropped_transactions from fork_event where rooted_slot=$1", &[&(slot as i64 + 3)])
+        .expect("Error selecting fork events");
+    assert_eq!(rows.len(), 1, "Incorrect number of fork events");
+    assert_eq!(rows[0].get::<_, i64>("first_slot"), slot as i64 + 1, "Incorrect first slot");
+    assert_eq!(rows[0].get::<_, i64>("last_slot"), slot as i64 + 2, "Incorrect last slot");
+    assert_eq!(rows[0].get::<_, Vec<i64>>("slots"), vec![slot as i64 + 1, slot as i64 + 2], "Incorrect abandoned slots");
+    assert_eq!(rows[0].get::<_, i64>("dropped_transactions"), 1, "Incorrect dropped transactions");
+
+    // the slot above the root is still pending, nothing else is abandoned
+    geyser_plugin.update_slot_status(slot + 4, Some(slot + 3), SlotStatus::Rooted).unwrap();
+    sleep(Duration::from_secs(1));
+    let rows = client
+        .query("SELECT rooted_slot from fork_event where rooted_slot=$1", &[&(slot as i64 + 4)])
+        .expect("Error selecting fork events");
+    assert_eq!(rows.len(), 0, "Unexpected fork event");
+    let rows = client
+        .query("SELECT slot from transaction where signature=$1", &[&signature.as_ref()])
+        .expect("Error selecting transactions");
+    assert_eq!(rows.len(), 0, "Transaction of an abandoned slot written");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static TOKEN_OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+static UNKNOWN_OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+static TOKEN_ACCOUNT_OWNER: Pubkey = pubkey!("cpmaMZyBQiPxpeuxNsQhW7N8z1o9yaNdLgiPhWGUEiX");
+
+fn update_startup_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, owner: &Pubkey, data: &[u8]) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 2039280,
+                owner: owner.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            1,
+            true,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_handler_buffers() {
+    let token_addresses: [Pubkey; 2] = [Keypair::new().pubkey(), Keypair::new().pubkey()];
+    let unknown_address: Pubkey = Keypair::new().pubkey();
+    let token_data = common::token_account_data(&Keypair::new().pubkey(), &TOKEN_ACCOUNT_OWNER, 0);
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_handler_buffers.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let token_account_count = |client: &mut postgres::Client| -> usize {
+        client
+            .query(
+                "SELECT slot from spl_token_account where pubkey = any($1)",
+                &[&token_addresses.iter().map(|a| a.to_string()).collect::<Vec<String>>()],
+            )
+            .expect("Error selecting token accounts")
+            .len()
+    };
+    let unknown_account_count = |client: &mut postgres::Client| -> usize {
+        client
+            .query("SELECT slot from account where pubkey=$1", &[&unknown_address.as_ref()])
+            .expect("Error selecting account")
+            .len()
+    };
+
+    // interleaved, the token account buffer fills up while the unknown account one holds a single account
+    update_startup_account(&mut geyser_plugin, &token_addresses[0], &TOKEN_OWNER, &token_data);
+    update_startup_account(&mut geyser_plugin, &unknown_address, &UNKNOWN_OWNER, &[1, 2, 3]);
+    update_startup_account(&mut geyser_plugin, &token_addresses[1], &TOKEN_OWNER, &token_data);
+    sleep(Duration::from_secs(1));
+    assert_eq!(token_account_count(&mut client), 2, "Full token account buffer not flushed");
+    assert_eq!(unknown_account_count(&mut client), 0, "Unknown account flushed with the token accounts");
+
+    geyser_plugin.notify_end_of_startup().unwrap();
+    sleep(Duration::from_secs(1));
+    assert_eq!(unknown_account_count(&mut client), 1, "Unknown account buffer not flushed at the end of startup");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClientBuilder;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+
+const CONFIG_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_handler_connections.json");
+
+#[test]
+fn test_handler_connections() {
+    // create the secondary database the slot handler is routed to
+    let config = GeyserPluginPostgresConfig::read_from(CONFIG_PATH).expect("Failed to read config");
+    let mut client = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let exists = client
+        .query_opt("SELECT 1 FROM pg_database WHERE datname='solana_secondary'", &[])
+        .expect("Error selecting databases")
+        .is_some();
+    if !exists {
+        client.batch_execute("CREATE DATABASE solana_secondary").expect("Error creating database");
+    }
+
+    let slot_num: u32 = rand::random::<u32>();
+    let slot = slot_num as i64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(CONFIG_PATH).unwrap();
+    geyser_plugin.update_slot_status(slot as u64, None, SlotStatus::Confirmed).unwrap();
+
+    sleep(Duration::from_secs(1));
+    let mut secondary_client = SimplePostgresClient::connect_to_named_db(&config, "secondary").expect("Failed to connect");
+    let rows = secondary_client.query("SELECT * from slot where slot=$1", &[&slot]).expect("Error selecting slots");
+    assert_eq!(rows.len(), 1, "Slot not written to the routed connection");
+
+    let rows = client.query("SELECT * from slot where slot=$1", &[&slot]).expect("Error selecting slots");
+    assert_eq!(rows.len(), 0, "Slot written to the default connection");
+
+    secondary_client.close().expect("Error disconnecting");
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
+
+#[test]
+fn test_unknown_handler_connection_key() {
+    let config = GeyserPluginPostgresConfig {
+        connections: HashMap::from([("secondary".to_string(), "host=localhost user=solana password=solana port=5432".to_string())]),
+        handler_connections: HashMap::from([("slots".to_string(), "secondary".to_string())]),
+        ..GeyserPluginPostgresConfig::default()
+    };
+    let err = PostgresClientBuilder::build_pararallel_postgres_client(&config).err().expect("Unknown handler connection key accepted");
+    assert!(err.to_string().contains("\"slots\""), "Unknown key not reported: {}", err);
+}
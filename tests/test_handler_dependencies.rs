@@ -0,0 +1,108 @@
+mod common;
+
+use std::collections::HashMap;
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClientBuilder;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+fn update_startup_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, data: &[u8]) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 2039280,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            1,
+            true,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_handler_dependencies() {
+    let mint: Pubkey = Keypair::new().pubkey();
+    let token_addresses: [Pubkey; 2] = [Keypair::new().pubkey(), Keypair::new().pubkey()];
+    let mut mint_data = vec![0; 82];
+    mint_data[45] = 1;
+    let token_data = common::token_account_data(&mint, &Keypair::new().pubkey(), 0);
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_handler_dependencies.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+
+    // as a foreign key, the token accounts of the mint are refused until the mint is written
+    let suffix = &mint.to_string()[..8].to_lowercase();
+    client
+        .batch_execute(&format!(
+            "
+                CREATE FUNCTION test_handler_dependencies_{0}() RETURNS trigger AS $$ BEGIN
+                    IF NEW.mint = '{1}' AND NOT EXISTS (SELECT 1 FROM spl_mint WHERE pubkey = NEW.mint) THEN
+                        RAISE EXCEPTION 'mint % not written', NEW.mint;
+                    END IF;
+                    RETURN NEW;
+                END $$ LANGUAGE plpgsql;
+                CREATE TRIGGER test_handler_dependencies_{0} BEFORE INSERT ON spl_token_account
+                    FOR EACH ROW EXECUTE FUNCTION test_handler_dependencies_{0}();
+            ",
+            suffix, mint,
+        ))
+        .expect("Error creating trigger");
+
+    // both handlers are selected for the three accounts, the token account buffer filling up first is flushed after the mint one
+    update_startup_account(&mut geyser_plugin, &token_addresses[0], &token_data);
+    update_startup_account(&mut geyser_plugin, &mint, &mint_data);
+    update_startup_account(&mut geyser_plugin, &token_addresses[1], &token_data);
+    sleep(Duration::from_secs(1));
+
+    let token_account_count = client
+        .query("SELECT slot from spl_token_account where mint=$1", &[&mint.to_string()])
+        .expect("Error selecting token accounts")
+        .len();
+    client
+        .batch_execute(&format!(
+            "
+                DROP TRIGGER test_handler_dependencies_{0} ON spl_token_account;
+                DROP FUNCTION test_handler_dependencies_{0};
+            ",
+            suffix
+        ))
+        .expect("Error dropping trigger");
+    assert_eq!(token_account_count, 2, "Token accounts not written after their mint");
+
+    geyser_plugin.notify_end_of_startup().unwrap();
+    sleep(Duration::from_secs(1));
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
+
+#[test]
+fn test_handler_dependency_cycle() {
+    let config = GeyserPluginPostgresConfig {
+        handler_dependencies: HashMap::from([
+            ("token_account".to_string(), vec!["token_mint".to_string()]),
+            ("token_mint".to_string(), vec!["token_account".to_string()]),
+        ]),
+        ..GeyserPluginPostgresConfig::default()
+    };
+    let err = PostgresClientBuilder::build_pararallel_postgres_client(&config).err().expect("Cyclic handler dependencies accepted");
+    assert!(err.to_string().contains("cycle"), "Cycle not reported: {}", err);
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+static HIGH_CARDINALITY_OWNER: Pubkey = pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+static MINT: Pubkey = pubkey!("DUSTawucrTsGU8hcqRdHDCbuYhCPADMLM2VcCb8VnFnQ");
+
+#[test]
+fn test_high_cardinality_owners() {
+    let address: Pubkey = Keypair::new().pubkey();
+    let data = common::token_account_data(&MINT, &HIGH_CARDINALITY_OWNER, 0);
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_high_cardinality_owners.json")).unwrap();
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 2039280,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &data,
+                write_version: 1,
+                txn_signature: None,
+            }),
+            1,
+            false,
+        )
+        .unwrap();
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    // the owner index leaves the owner out
+    let index_definition: String = client
+        .query_one("SELECT indexdef FROM pg_indexes WHERE schemaname='public' AND indexname='spl_token_account_owner'", &[])
+        .expect("Error selecting owner index")
+        .get("indexdef");
+    assert!(
+        index_definition.contains(&format!("'{}'", HIGH_CARDINALITY_OWNER)),
+        "Owner not excluded from the owner index: {}",
+        index_definition
+    );
+
+    // its accounts are still stored and indexed by mint
+    let rows = client
+        .query("SELECT owner from spl_token_account where pubkey=$1 and mint=$2", &[&address.to_string(), &MINT.to_string()])
+        .expect("Error selecting account");
+    assert_eq!(rows.len(), 1, "Account not stored");
+    let owner: String = rows.first().expect("No results found").get("owner");
+    assert_eq!(owner, HIGH_CARDINALITY_OWNER.to_string(), "Incorrect owner");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,140 @@
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::Duration;
+use std::time::Instant;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+
+const CONFIG_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_idle_probe.json");
+
+/// An empty simple query, as sent by a probe
+const EMPTY_QUERY: &[u8] = b"Q\0\0\0\x05\0";
+
+/// Forwards the connections to the database until they are stalled: their bytes are then dropped while they stay
+/// open, as when the server is gone without the connection being closed
+struct StallingProxy {
+    port: u16,
+    accepted: Arc<AtomicUsize>,
+    stalled_below: Arc<AtomicUsize>,
+    /// Empty queries sent to the server
+    empty_queries: Arc<AtomicUsize>,
+}
+
+impl StallingProxy {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+        let port = listener.local_addr().unwrap().port();
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let stalled_below = Arc::new(AtomicUsize::new(0));
+        let empty_queries = Arc::new(AtomicUsize::new(0));
+        let (thread_accepted, thread_stalled_below, thread_empty_queries) = (accepted.clone(), stalled_below.clone(), empty_queries.clone());
+        std::thread::spawn(move || {
+            for client in listener.incoming() {
+                let client = client.expect("Failed to accept");
+                let id = thread_accepted.fetch_add(1, Ordering::SeqCst);
+                let server = TcpStream::connect("127.0.0.1:5432").expect("Failed to connect");
+                for (mut from, mut to, to_server) in [(client.try_clone().unwrap(), server.try_clone().unwrap(), true), (server, client, false)] {
+                    let stalled_below = thread_stalled_below.clone();
+                    let empty_queries = thread_empty_queries.clone();
+                    std::thread::spawn(move || {
+                        let mut buf = [0; 8192];
+                        while let Ok(len) = from.read(&mut buf) {
+                            if len == 0 {
+                                return;
+                            }
+                            if to_server && buf[..len].windows(EMPTY_QUERY.len()).any(|window| window == EMPTY_QUERY) {
+                                empty_queries.fetch_add(1, Ordering::SeqCst);
+                            }
+                            if id < stalled_below.load(Ordering::SeqCst) {
+                                continue;
+                            }
+                            if to.write_all(&buf[..len]).is_err() {
+                                return;
+                            }
+                        }
+                    });
+                }
+            }
+        });
+        Self {
+            port,
+            accepted,
+            stalled_below,
+            empty_queries,
+        }
+    }
+
+    /// Stalls the connections accepted so far
+    fn stall_accepted(&self) {
+        self.stalled_below.store(self.accepted.load(Ordering::SeqCst), Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_idle_probe() {
+    // the tables are created through a direct connection
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(CONFIG_PATH).unwrap();
+    geyser_plugin.on_unload();
+
+    let proxy = StallingProxy::start();
+    let mut config = GeyserPluginPostgresConfig::read_from(CONFIG_PATH).expect("Failed to read config");
+    config.connection_str = format!("host=127.0.0.1 user=solana password=solana port={}", proxy.port);
+    let slot = rand::random::<u32>() as i64;
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    client.update_slot_status(slot as u64, None, SlotStatus::Processed).expect("Error writing slot");
+
+    // the idle connection of the pool is stalled, its probe times out and a new connection writes the next slot
+    proxy.stall_accepted();
+    let accepted = proxy.accepted.load(Ordering::SeqCst);
+    sleep(Duration::from_millis(200));
+    let started = Instant::now();
+    let result = client.update_slot_status(slot as u64 + 1, None, SlotStatus::Processed);
+    let elapsed = started.elapsed();
+
+    let mut reader = SimplePostgresClient::connect_to_db(&GeyserPluginPostgresConfig::read_from(CONFIG_PATH).unwrap()).expect("Failed to connect");
+    let rows = reader.query("SELECT slot from slot where slot = ANY($1)", &[&vec![slot, slot + 1]]).expect("Error selecting slots");
+    reader.execute("DELETE FROM slot where slot = ANY($1)", &[&vec![slot, slot + 1]]).expect("Error deleting slots");
+
+    assert!(result.is_ok(), "Write through the stalled connection failed: {:?}", result.err());
+    assert!(elapsed < Duration::from_secs(5), "Stalled connection not probed, write took {:?}", elapsed);
+    assert!(proxy.accepted.load(Ordering::SeqCst) > accepted, "Stalled connection not replaced");
+    assert_eq!(rows.len(), 2, "Slots not written");
+    reader.close().expect("Error disconnecting");
+}
+
+#[test]
+fn test_no_probe_by_default() {
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(CONFIG_PATH).unwrap();
+    geyser_plugin.on_unload();
+
+    // without idle_probe_ms, the connections are checked out without a round trip to the server
+    let proxy = StallingProxy::start();
+    let mut config = GeyserPluginPostgresConfig::read_from(CONFIG_PATH).expect("Failed to read config");
+    config.connection_str = format!("host=127.0.0.1 user=solana password=solana port={}", proxy.port);
+    config.idle_probe_ms = None;
+    let slot = rand::random::<u32>() as i64;
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    for offset in 0..3 {
+        client.update_slot_status((slot + offset) as u64, None, SlotStatus::Processed).expect("Error writing slot");
+        sleep(Duration::from_millis(150));
+    }
+
+    let mut reader = SimplePostgresClient::connect_to_db(&GeyserPluginPostgresConfig::read_from(CONFIG_PATH).unwrap()).expect("Failed to connect");
+    reader
+        .execute("DELETE FROM slot where slot = ANY($1)", &[&vec![slot, slot + 1, slot + 2]])
+        .expect("Error deleting slots");
+    assert_eq!(proxy.empty_queries.load(Ordering::SeqCst), 0, "Connections probed on checkout");
+}
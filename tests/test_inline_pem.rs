@@ -0,0 +1,46 @@
+use std::fs;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+
+const CONFIG_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_inline_pem.json");
+
+#[test]
+fn test_inline_pem() {
+    let config = GeyserPluginPostgresConfig::read_from(CONFIG_PATH).expect("Failed to read config");
+    let mut client = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let setting = |client: &mut postgres::Client, name: &str| client.query_one(&format!("SHOW {}", name), &[]).unwrap().get::<_, String>(0);
+    if setting(&mut client, "ssl") != "on" {
+        eprintln!("SSL is off on the server, skipping");
+        return;
+    }
+    // the certificate of the server is its own authority, and serves as the client certificate as well
+    let cert_pem = fs::read_to_string(setting(&mut client, "ssl_cert_file")).expect("Failed to read the server certificate");
+    let key_pem = fs::read_to_string(setting(&mut client, "ssl_key_file")).expect("Failed to read the server key");
+    // the inline contents are preferred to the files, which do not exist
+    let tls_config = GeyserPluginPostgresConfig {
+        use_ssl: Some(true),
+        server_ca: Some("/nonexistent/server-ca.pem".to_string()),
+        client_cert: Some("/nonexistent/client-cert.pem".to_string()),
+        client_key: Some("/nonexistent/client-key.pem".to_string()),
+        server_ca_pem: Some(cert_pem.clone()),
+        client_cert_pem: Some(cert_pem),
+        client_key_pem: Some(key_pem),
+        ..config
+    };
+
+    let mut client = SimplePostgresClient::connect_to_db(&tls_config).expect("Failed to connect with the inline certificates");
+    assert!(client.query_one("SELECT ssl FROM pg_stat_ssl WHERE pid = pg_backend_pid()", &[]).unwrap().get::<_, bool>(0));
+
+    match SimplePostgresClient::connect_to_db(&GeyserPluginPostgresConfig {
+        server_ca_pem: Some("not a certificate".to_string()),
+        ..tls_config
+    }) {
+        Err(GeyserPluginError::ConfigFileReadError { msg }) => {
+            assert!(msg.contains("\"server_ca_pem\""), "Invalid inline certificate not reported: {}", msg);
+            assert!(!msg.contains("not a certificate"), "Inline certificate logged: {}", msg);
+        }
+        other => panic!("Expected a config error, got {:?}", other.map(|_| ())),
+    }
+}
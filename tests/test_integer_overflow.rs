@@ -0,0 +1,73 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+/// Writes an account of `lamports` and `rent_epoch` with the config, returning the stored values
+fn write_account(config_path: &str, lamports: u64, rent_epoch: u64) -> Option<(i64, i64)> {
+    let address: Pubkey = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(config_path).unwrap();
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch,
+                data: &[1, 2, 3],
+                write_version: 0,
+                txn_signature: None,
+            }),
+            0,
+            false,
+        )
+        .unwrap();
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let stored = client
+        .query_opt("SELECT lamports, rent_epoch from account where pubkey=$1", &[&address.as_ref()])
+        .expect("Error selecting account")
+        .map(|row| (row.get::<_, i64>("lamports"), row.get::<_, i64>("rent_epoch")));
+    client.execute("DELETE FROM account where pubkey=$1", &[&address.as_ref()]).expect("Error deleting account");
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+    stored
+}
+
+#[test]
+fn test_integer_overflow_clamp() {
+    let stored = write_account(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_integer_overflow_clamp.json"),
+        i64::MAX as u64 + 1,
+        i64::MAX as u64 + 1,
+    );
+    assert_eq!(stored, Some((i64::MAX, i64::MAX)), "Overflowing values not clamped");
+}
+
+#[test]
+fn test_integer_overflow_skip() {
+    let stored = write_account(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_integer_overflow_skip.json"), i64::MAX as u64 + 1, 0);
+    assert_eq!(stored, None, "Overflowing account written");
+}
+
+#[test]
+fn test_rent_exempt_rent_epoch() {
+    // the rent epoch of the rent-exempt accounts is stored as -1, even when the overflows are skipped
+    for config in ["test_config_integer_overflow_clamp.json", "test_config_integer_overflow_skip.json"] {
+        let stored = write_account(&format!("{}/tests/{}", env!("CARGO_MANIFEST_DIR"), config), 1, u64::MAX);
+        assert_eq!(stored, Some((1, -1)), "Rent-exempt account not written with {}", config);
+    }
+}
@@ -0,0 +1,59 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+#[test]
+fn test_join_flush() {
+    let slot = rand::random::<u32>() as i64;
+    let addresses = (0..3).map(|_| Keypair::new().pubkey()).collect::<Vec<Pubkey>>();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_join_flush.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    // fewer accounts than the batch size, startup not being over when the plugin is unloaded
+    for address in &addresses {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 1,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: &[1, 2, 3],
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                slot as u64,
+                true,
+            )
+            .unwrap();
+    }
+    sleep(Duration::from_secs(1));
+    geyser_plugin.on_unload();
+
+    let mut client = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let keys = addresses.iter().map(|address| address.to_string()).collect::<Vec<String>>();
+    let accounts = client.query("SELECT pubkey from raw_account where pubkey = ANY($1)", &[&keys]).expect("Error selecting accounts");
+    let slots = client.query("SELECT status from slot where slot=$1", &[&slot]).expect("Error selecting slot");
+    client.execute("DELETE FROM raw_account where pubkey = ANY($1)", &[&keys]).expect("Error deleting accounts");
+    client.execute("DELETE FROM slot where slot=$1", &[&slot]).expect("Error deleting slot");
+
+    assert_eq!(accounts.len(), 3, "Buffered accounts lost on unload");
+    assert_eq!(
+        slots.iter().map(|row| row.get::<_, String>(0)).collect::<Vec<String>>(),
+        vec!["rooted".to_string()],
+        "Startup slot not written"
+    );
+    client.close().expect("Error disconnecting");
+}
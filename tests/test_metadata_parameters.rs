@@ -0,0 +1,77 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Appends a borsh string of the bytes padded with null bytes as the metadata program does
+fn push_text(data: &mut Vec<u8>, text: &[u8], padded_len: usize) {
+    data.extend_from_slice(&(padded_len as u32).to_le_bytes());
+    data.extend_from_slice(text);
+    data.resize(data.len() + padded_len - text.len(), 0);
+}
+
+#[test]
+fn test_metadata_parameters() {
+    let metadata_address: Pubkey = Keypair::new().pubkey();
+    let mint: Pubkey = Keypair::new().pubkey();
+    let creator: Pubkey = Keypair::new().pubkey();
+    // texts closing the quoted value of a rendered statement, they are stored as they are
+    let name = "');DROP TABLE token_metadata;--";
+    let symbol = "$1 \\'";
+    let mut data = vec![4_u8];
+    data.extend_from_slice(Keypair::new().pubkey().as_ref());
+    data.extend_from_slice(mint.as_ref());
+    push_text(&mut data, name.as_bytes(), 32);
+    push_text(&mut data, symbol.as_bytes(), 10);
+    push_text(&mut data, b"https://example.com/1.json", 200);
+    // the seller fee basis points, then a verified creator
+    data.extend_from_slice(&500_u16.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(&1_u32.to_le_bytes());
+    data.extend_from_slice(creator.as_ref());
+    data.extend_from_slice(&[1, 100]);
+    data.resize(679, 0);
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_metadata_parameters.json")).unwrap();
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: metadata_address.as_ref(),
+                lamports: 5616720,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            1,
+            false,
+        )
+        .unwrap();
+    sleep(Duration::from_secs(1));
+
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let row = client
+        .query_one("SELECT name, symbol, slot from token_metadata where mint=$1", &[&mint.to_string()])
+        .expect("Error selecting metadata");
+    assert_eq!((row.get(0), row.get(1), row.get(2)), (name.to_string(), symbol.to_string(), 1_i64), "Incorrect metadata");
+    let row = client
+        .query_one("SELECT creator, verified, share from token_metadata_creators where mint=$1", &[&mint.to_string()])
+        .expect("Error selecting creator");
+    assert_eq!((row.get(0), row.get(1), row.get(2)), (creator.to_string(), true, 100_i16), "Incorrect creator");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,66 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+#[test]
+fn test_mint_account() {
+    let mint: Pubkey = Keypair::new().pubkey();
+    let mint_authority: Pubkey = Keypair::new().pubkey();
+    let supply = u64::MAX - 7;
+    // mint authority set, no freeze authority
+    let mut data = vec![0_u8; 82];
+    data[0..4].copy_from_slice(&1_u32.to_le_bytes());
+    data[4..36].copy_from_slice(mint_authority.as_ref());
+    data[36..44].copy_from_slice(&supply.to_le_bytes());
+    data[44] = 9;
+    data[45] = 1;
+    data[50..82].copy_from_slice(Keypair::new().pubkey().as_ref());
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_mint_account.json")).unwrap();
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: mint.as_ref(),
+                lamports: 1461600,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            1,
+            false,
+        )
+        .unwrap();
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let row = client
+        .query_one(
+            "SELECT mint_authority, supply::TEXT, decimals, is_initialized, freeze_authority, slot from spl_mint where pubkey=$1",
+            &[&mint.to_string()],
+        )
+        .expect("Error selecting mint");
+    assert_eq!(row.get::<_, Option<String>>("mint_authority"), Some(mint_authority.to_string()), "Incorrect mint authority");
+    assert_eq!(row.get::<_, String>("supply"), supply.to_string(), "Incorrect supply");
+    assert_eq!(row.get::<_, i16>("decimals"), 9, "Incorrect decimals");
+    assert!(row.get::<_, bool>("is_initialized"), "Mint not initialized");
+    assert_eq!(row.get::<_, Option<String>>("freeze_authority"), None, "Freeze authority set without its option tag");
+    assert_eq!(row.get::<_, i64>("slot"), 1, "Incorrect slot");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::pool::ConnectionPools;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static TOKEN_ACCOUNT_OWNER: Pubkey = pubkey!("cpmaMZyBQiPxpeuxNsQhW7N8z1o9yaNdLgiPhWGUEiX");
+
+fn update_token_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, mint: &Pubkey, slot: u64) {
+    common::update_token_program_account(geyser_plugin, address, &common::token_account_data(mint, &TOKEN_ACCOUNT_OWNER, 1), slot, slot);
+}
+
+#[test]
+fn test_mint_allowlist() {
+    let address: Pubkey = Keypair::new().pubkey();
+    let mint: Pubkey = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_mint_allowlist.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+
+    // the mint is not allowlisted yet
+    update_token_account(&mut geyser_plugin, &address, &mint, 1);
+    sleep(Duration::from_secs(1));
+    let rows = client
+        .query("SELECT * from spl_token_account where pubkey=$1", &[&address.to_string()])
+        .expect("Error selecting account");
+    assert_eq!(rows.len(), 0, "Account of a mint outside the allowlist indexed");
+
+    client
+        .execute("INSERT INTO spl_token_mint_allowlist (mint) VALUES ($1)", &[&mint.to_string()])
+        .expect("Error inserting mint");
+    // past the refresh interval
+    sleep(Duration::from_secs(2));
+    update_token_account(&mut geyser_plugin, &address, &mint, 2);
+    sleep(Duration::from_secs(1));
+    let rows = client
+        .query("SELECT * from spl_token_account where pubkey=$1", &[&address.to_string()])
+        .expect("Error selecting account");
+    assert_eq!(rows.len(), 1, "Account of an allowlisted mint not indexed");
+    let slot: i64 = rows.first().expect("No results found").get("slot");
+    assert_eq!(slot, 2, "Incorrect slot");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
+
+#[test]
+fn test_mint_allowlist_refresh_checkouts() {
+    let config = GeyserPluginPostgresConfig::read_from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_mint_allowlist.json")).expect("Failed to read config");
+    let pools = ConnectionPools::new(&config).expect("Error opening the pools");
+    let mut client = SimplePostgresClient::with_pools(&config, None, pools.clone()).expect("Failed to build the client");
+    let checkouts = || pools.utilization().iter().map(|pool| pool.checkouts).sum::<u64>();
+    let account = || DbAccountInfo::from_raw(&Pubkey::new_unique(), &Pubkey::new_unique(), &[], 1);
+
+    // the mints are loaded by the first update, the next ones within the refresh interval take no connection
+    let before = checkouts();
+    client.update_account(account(), false).expect("Error updating account");
+    assert_eq!(checkouts() - before, 1, "Mints not loaded");
+    for _ in 0..10 {
+        client.update_account(account(), false).expect("Error updating account");
+    }
+    assert_eq!(checkouts() - before, 1, "Connections checked out within the refresh interval");
+    sleep(Duration::from_millis(1100));
+    client.update_account(account(), false).expect("Error updating account");
+    assert_eq!(checkouts() - before, 2, "Mints not reloaded past the refresh interval");
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static TOKEN_ACCOUNT_OWNER: Pubkey = pubkey!("cpmaMZyBQiPxpeuxNsQhW7N8z1o9yaNdLgiPhWGUEiX");
+
+fn update_mint(geyser_plugin: &mut GeyserPluginPostgres, mint: &Pubkey, supply: u64, decimals: u8) {
+    common::update_token_program_account(geyser_plugin, mint, &common::mint_data(supply, decimals), 1, 1);
+}
+
+fn update_token_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, mint: &Pubkey, amount: u64) {
+    common::update_token_program_account(geyser_plugin, address, &common::token_account_data(mint, &TOKEN_ACCOUNT_OWNER, amount), 1, 1);
+}
+
+#[test]
+fn test_nft_token_accounts() {
+    let nft_mint: Pubkey = Keypair::new().pubkey();
+    let fungible_mint: Pubkey = Keypair::new().pubkey();
+    let nft_account: Pubkey = Keypair::new().pubkey();
+    let fungible_account: Pubkey = Keypair::new().pubkey();
+    let single_fungible_account: Pubkey = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_nft_token_accounts.json")).unwrap();
+
+    update_mint(&mut geyser_plugin, &nft_mint, 1, 0);
+    update_mint(&mut geyser_plugin, &fungible_mint, 1_000_000_000, 6);
+    sleep(Duration::from_secs(1));
+    update_token_account(&mut geyser_plugin, &nft_account, &nft_mint, 1);
+    update_token_account(&mut geyser_plugin, &fungible_account, &fungible_mint, 1_000_000);
+    // a single base unit of a mint with decimals is not an NFT
+    update_token_account(&mut geyser_plugin, &single_fungible_account, &fungible_mint, 1);
+    // flush the last batch
+    update_token_account(&mut geyser_plugin, &Keypair::new().pubkey(), &nft_mint, 1);
+    sleep(Duration::from_secs(1));
+
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    for (address, expected_rows) in [(nft_account, 1), (fungible_account, 0), (single_fungible_account, 0), (nft_mint, 0), (fungible_mint, 0)] {
+        let rows = client
+            .query("SELECT * from spl_token_account where pubkey=$1", &[&address.to_string()])
+            .expect("Error selecting account");
+        assert_eq!(rows.len(), expected_rows, "Incorrect rows for {}", address);
+    }
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
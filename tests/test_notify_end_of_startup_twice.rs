@@ -0,0 +1,47 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+#[test]
+fn test_notify_end_of_startup_twice() {
+    let slot = rand::random::<u32>() as u64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_single_threaded_unknown.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let pubkey = Pubkey::new_unique();
+    let account = DbAccountInfo::new(
+        &ReplicaAccountInfoV2 {
+            pubkey: pubkey.as_ref(),
+            lamports: 1,
+            owner: OWNER.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data: &[1],
+            write_version: 0,
+            txn_signature: None,
+        },
+        slot,
+    );
+    client.update_account(account, true).unwrap();
+    let slot_count = |reader: &mut postgres::Client| -> i64 { reader.query_one("SELECT COUNT(*) from slot where slot=$1", &[&(slot as i64)]).expect("Error counting slots").get(0) };
+
+    client.notify_end_of_startup().unwrap();
+    assert_eq!(slot_count(&mut reader), 1, "Startup slot not flushed");
+
+    // a second call does not flush the startup slots again
+    reader.execute("DELETE FROM slot where slot=$1", &[&(slot as i64)]).expect("Error deleting slot");
+    client.notify_end_of_startup().unwrap();
+    assert_eq!(slot_count(&mut reader), 0, "Startup slot flushed again");
+    reader.close().expect("Error disconnecting");
+}
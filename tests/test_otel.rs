@@ -0,0 +1,62 @@
+#![cfg(feature = "otel")]
+
+use opentelemetry_sdk::testing::trace::InMemorySpanExporterBuilder;
+use opentelemetry_sdk::trace::TracerProvider;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+#[test]
+fn test_otel_flush_spans() {
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_single_threaded_unknown.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    let exporter = InMemorySpanExporterBuilder::new().build();
+    let provider = TracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    // batch_size is 2, the second startup account flushes the batch
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    for _ in 0..2 {
+        client
+            .update_account(
+                DbAccountInfo {
+                    pubkey: Pubkey::new_unique().to_bytes().to_vec(),
+                    lamports: 2790960,
+                    owner: OWNER.to_bytes().to_vec(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: vec![1, 2, 3],
+                    slot: 1,
+                    write_version: 0,
+                    txn_signature: None,
+                    readable: Default::default(),
+                },
+                true,
+            )
+            .unwrap();
+    }
+
+    provider.force_flush();
+    let spans = exporter.get_finished_spans().expect("Failed to get spans");
+    let flush = spans.iter().find(|s| s.name == "account_flush").expect("No flush span");
+    let children = |parent: &opentelemetry_sdk::export::trace::SpanData| {
+        spans
+            .iter()
+            .filter(|s| s.parent_span_id == parent.span_context.span_id())
+            .map(|s| s.name.to_string())
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(children(flush), vec!["build_sql", "execute"], "Incorrect flush child spans");
+    let build_sql = spans.iter().find(|s| s.name == "build_sql").expect("No build_sql span");
+    // the accounts of a handler's batch are built by a single query, under one span
+    assert_eq!(children(build_sql), vec!["account_handler"], "Incorrect handler spans");
+}
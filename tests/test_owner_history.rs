@@ -0,0 +1,59 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static FORMER_OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+static NEW_OWNER: Pubkey = pubkey!("M2mx93ekt1fmXSVkTrUL9xVFHkmME8HTUi5Cyc5aF7K");
+
+#[test]
+fn test_owner_history() {
+    let address: Pubkey = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_owner_history.json")).unwrap();
+    // the reassignment is notified twice, then an update of an earlier slot arrives last
+    for (owner, slot) in [(&FORMER_OWNER, 1), (&NEW_OWNER, 2), (&NEW_OWNER, 2), (&FORMER_OWNER, 1)] {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 1,
+                    owner: owner.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: &[0; 8],
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                slot,
+                false,
+            )
+            .unwrap();
+    }
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let history = client
+        .query("SELECT old_owner, new_owner, slot from account_owner_history where pubkey=$1", &[&address.to_string()])
+        .expect("Error selecting owner history")
+        .iter()
+        .map(|row| (row.get(0), row.get(1), row.get(2)))
+        .collect::<Vec<(String, String, i64)>>();
+    assert_eq!(history, vec![(FORMER_OWNER.to_string(), NEW_OWNER.to_string(), 2)], "Incorrect owner history");
+    let owner: String = client
+        .query_one("SELECT owner from account_current_owner where pubkey=$1", &[&address.to_string()])
+        .expect("Error selecting owner")
+        .get(0);
+    assert_eq!(owner, NEW_OWNER.to_string(), "Incorrect current owner");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::RwLock;
+use std::thread;
+
+use solana_geyser_plugin_postgres::locks;
+
+#[test]
+fn test_poisoned_lock() {
+    let mutex = Arc::new(Mutex::new(1));
+    let rw_lock = Arc::new(RwLock::new(1));
+    let (poisoned_mutex, poisoned_rw_lock) = (mutex.clone(), rw_lock.clone());
+    let result = thread::spawn(move || {
+        let _mutex_guard = poisoned_mutex.lock().unwrap();
+        let _rw_lock_guard = poisoned_rw_lock.write().unwrap();
+        panic!("poisoning the locks");
+    })
+    .join();
+    assert!(result.is_err());
+    assert!(mutex.is_poisoned() && rw_lock.is_poisoned());
+
+    // the locks keep working once poisoned
+    *locks::lock(&mutex, "test") += 1;
+    *locks::write(&rw_lock, "test") += 1;
+    assert_eq!(*locks::lock(&mutex, "test"), 2);
+    assert_eq!(*locks::read(&rw_lock, "test"), 2);
+}
@@ -0,0 +1,40 @@
+use std::thread;
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::postgres_client::pool::checkout;
+use solana_geyser_plugin_postgres::postgres_client::pool::ConnectionPools;
+use solana_geyser_plugin_postgres::postgres_client::pool::PoolUtilization;
+
+fn utilization(pools: &ConnectionPools) -> PoolUtilization {
+    pools.utilization().into_iter().next().expect("No pool")
+}
+
+#[test]
+fn test_pool_metrics() {
+    let config = GeyserPluginPostgresConfig::read_from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_pool_metrics.json")).expect("Error reading config");
+    let pools = ConnectionPools::new(&config).expect("Error opening the pools");
+    let first = checkout(pools.named(None)).expect("Error checking out");
+    let second = checkout(pools.named(None)).expect("Error checking out");
+    let saturated = utilization(&pools);
+
+    // a third checkout waits for one of the connections to be returned
+    let waiting_pools = pools.clone();
+    let waiting = thread::spawn(move || drop(checkout(waiting_pools.named(None)).expect("Error checking out")));
+    sleep(Duration::from_millis(500));
+    let waited = utilization(&pools);
+    drop(first);
+    waiting.join().unwrap();
+    drop(second);
+    let released = utilization(&pools);
+    pools.report();
+    let reported = utilization(&pools);
+
+    assert_eq!((saturated.size, saturated.in_use, saturated.idle, saturated.waiting, saturated.checkouts), (2, 2, 0, 0, 2));
+    assert_eq!((waited.in_use, waited.waiting), (2, 1), "Waiting checkout not counted");
+    assert_eq!((released.in_use, released.idle, released.waiting, released.checkouts), (0, 2, 0, 3));
+    assert!(released.max_wait_us >= 500000, "Wait of the checkout not measured: {:?}", released);
+    assert!(released.wait_us >= released.max_wait_us);
+    assert_eq!((reported.checkouts, reported.wait_us, reported.max_wait_us), (0, 0, 0), "Checkouts not reset once reported");
+}
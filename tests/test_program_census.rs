@@ -0,0 +1,70 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static STAKE_PROGRAM_ID: Pubkey = pubkey!("Stake11111111111111111111111111111111111111");
+static TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+fn update_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, owner: &Pubkey, data: &[u8]) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 2282880,
+                owner: owner.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version: 1,
+                txn_signature: None,
+            }),
+            1,
+            false,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_program_census() {
+    let stake_account: Pubkey = Keypair::new().pubkey();
+    let token_account: Pubkey = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_program_census.json")).unwrap();
+    // selected without handler
+    update_account(&mut geyser_plugin, &stake_account, &STAKE_PROGRAM_ID, &[1; 200]);
+    // handled by the token_account handler
+    update_account(&mut geyser_plugin, &token_account, &TOKEN_PROGRAM_ID, &[0; 165]);
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let rows = client
+        .query("SELECT owner, data_len from program_account_census where pubkey=$1", &[&stake_account.to_string()])
+        .expect("Error selecting census");
+    assert_eq!(rows.len(), 1, "Unmatched account not recorded");
+    let first_row = rows.first().expect("No results found");
+    assert_eq!(first_row.get::<_, String>("owner"), STAKE_PROGRAM_ID.to_string(), "Incorrect owner");
+    assert_eq!(first_row.get::<_, i64>("data_len"), 200, "Incorrect data length");
+
+    let rows = client
+        .query("SELECT * from program_account_census where pubkey=$1", &[&token_account.to_string()])
+        .expect("Error selecting census");
+    assert_eq!(rows.len(), 0, "Handled account recorded");
+
+    let account_count: i64 = client
+        .query_one("SELECT account_count from program_census where owner=$1", &[&STAKE_PROGRAM_ID.to_string()])
+        .expect("Error selecting program census")
+        .get("account_count");
+    assert!(account_count >= 1, "Incorrect account count");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,57 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+#[test]
+fn test_raw_account() {
+    let address: Pubkey = Keypair::new().pubkey();
+    let data = (0..=255).collect::<Vec<u8>>();
+    let stale_data = vec![42_u8; 8];
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_raw_account.json")).unwrap();
+    // the update of an earlier slot notified last is ignored
+    for (data, slot) in [(&data, 2), (&stale_data, 1)] {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 42,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data,
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                slot,
+                false,
+            )
+            .unwrap();
+    }
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let row = client
+        .query_one("SELECT owner, slot, lamports, data_base64 from raw_account where pubkey=$1", &[&address.to_string()])
+        .expect("Error selecting raw account");
+    assert_eq!(
+        (row.get(0), row.get(1), row.get(2), row.get(3)),
+        (OWNER.to_string(), 2_i64, 42_i64, base64::encode(&data)),
+        "Incorrect raw account"
+    );
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
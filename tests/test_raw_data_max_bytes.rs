@@ -0,0 +1,57 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+#[test]
+fn test_raw_data_max_bytes() {
+    let small_address: Pubkey = Keypair::new().pubkey();
+    let large_address: Pubkey = Keypair::new().pubkey();
+    let small_data = vec![7_u8; 16];
+    let large_data = vec![7_u8; 17];
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_raw_data_max_bytes.json")).unwrap();
+    for (address, data) in [(&small_address, &small_data), (&large_address, &large_data)] {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 2790960,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data,
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                0,
+                false,
+            )
+            .unwrap();
+    }
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let stored = |client: &mut postgres::Client, address: &Pubkey| -> (Option<Vec<u8>>, bool) {
+        let row = client
+            .query_one("SELECT data, data_omitted from account where pubkey=$1", &[&address.as_ref()])
+            .expect("Error selecting account");
+        (row.get("data"), row.get("data_omitted"))
+    };
+    assert_eq!(stored(&mut client, &small_address), (Some(small_data), false), "Small account data not stored");
+    assert_eq!(stored(&mut client, &large_address), (None, true), "Large account data stored");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,19 @@
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_sdk::pubkey::Pubkey;
+
+#[test]
+fn test_readable_account_info() {
+    let (pubkey, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account = DbAccountInfo::from_raw(&pubkey, &owner, &[1, 2, 3], 1);
+    let serialized = account.try_to_vec().expect("Error serializing the account");
+
+    assert_eq!(account.pubkey_str(), pubkey.to_string());
+    assert_eq!(account.owner_str(), owner.to_string());
+    // the cached encodings are neither serialized nor compared
+    assert_eq!(account.try_to_vec().expect("Error serializing the account"), serialized, "Encodings serialized");
+    let deserialized = DbAccountInfo::try_from_slice(&serialized).expect("Error deserializing the account");
+    assert_eq!(deserialized, account);
+    assert_eq!(deserialized.clone().pubkey_str(), pubkey.to_string());
+}
@@ -0,0 +1,158 @@
+#![cfg(feature = "redis")]
+
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::redis_sink::account_key;
+use solana_geyser_plugin_postgres::redis_sink::RedisSink;
+use solana_geyser_plugin_postgres::serializer::serializer;
+use solana_geyser_plugin_postgres::serializer::SerializationFormat;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+/// (version, account) of each key
+type MockStore = Arc<Mutex<HashMap<String, (String, Vec<u8>)>>>;
+
+/// Redis server answering the account upsert script, keeping the highest version of each key. When `drop_first`,
+/// the first connection is closed after reading its first command, before replying
+fn mock_redis(drop_first: bool) -> (String, MockStore) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let store = MockStore::default();
+    let store_clone = store.clone();
+    thread::spawn(move || {
+        for (index, stream) in listener.incoming().enumerate() {
+            let store = store_clone.clone();
+            let drop_connection = drop_first && index == 0;
+            thread::spawn(move || serve(stream.unwrap(), store, drop_connection));
+        }
+    });
+    (address, store)
+}
+
+fn serve(stream: TcpStream, store: MockStore, drop_connection: bool) {
+    let mut writer = stream.try_clone().unwrap();
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap() == 0 {
+            return;
+        }
+        let count: usize = line.trim_end().trim_start_matches('*').parse().unwrap();
+        let mut args = Vec::new();
+        for _ in 0..count {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let length: usize = line.trim_end().trim_start_matches('$').parse().unwrap();
+            let mut arg = vec![0; length + 2];
+            reader.read_exact(&mut arg).unwrap();
+            arg.truncate(length);
+            args.push(arg);
+        }
+        if drop_connection {
+            return;
+        }
+        assert_eq!(args[0], b"EVAL", "Unexpected command");
+        let key = String::from_utf8(args[3].clone()).unwrap();
+        let version = String::from_utf8(args[4].clone()).unwrap();
+        let mut store = store.lock().unwrap();
+        let reply = match store.get(&key) {
+            Some((stored_version, _)) if *stored_version >= version => ":0\r\n",
+            _ => {
+                store.insert(key, (version, args[5].clone()));
+                ":1\r\n"
+            }
+        };
+        writer.write_all(reply.as_bytes()).unwrap();
+    }
+}
+
+fn redis_sink(address: String) -> RedisSink {
+    let mut config = GeyserPluginPostgresConfig::read_from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_redis.json")).unwrap();
+    config.redis_address = Some(address);
+    RedisSink::connect(&config).unwrap().expect("No Redis sink")
+}
+
+fn account(pubkey: &Pubkey, owner: &Pubkey, slot: u64, data: &[u8]) -> DbAccountInfo {
+    DbAccountInfo::new(
+        &ReplicaAccountInfoV2 {
+            pubkey: pubkey.as_ref(),
+            lamports: 1,
+            owner: owner.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data,
+            write_version: slot,
+            txn_signature: None,
+        },
+        slot,
+    )
+}
+
+#[test]
+fn test_redis_sink() {
+    let (address, store) = mock_redis(false);
+    let mut redis_sink = redis_sink(address);
+
+    let pubkey = Pubkey::new_unique();
+    let unselected_pubkey = Pubkey::new_unique();
+    let latest = account(&pubkey, &OWNER, 2, &[2]);
+    redis_sink.update_account(&latest, false).unwrap();
+    // notified late, older than the stored account
+    redis_sink.update_account(&account(&pubkey, &OWNER, 1, &[1]), false).unwrap();
+    redis_sink.update_account(&account(&unselected_pubkey, &Pubkey::new_unique(), 2, &[2]), false).unwrap();
+    redis_sink.flush().unwrap();
+
+    let store = store.lock().unwrap();
+    let (_, stored) = store.get(&account_key(pubkey.as_ref())).expect("Account not stored");
+    assert_eq!(serializer(SerializationFormat::Json).deserialize_account(stored).unwrap(), latest, "Latest account not stored");
+    assert!(!store.contains_key(&account_key(unselected_pubkey.as_ref())), "Unselected account stored");
+}
+
+#[test]
+fn test_redis_sink_batch() {
+    let (address, store) = mock_redis(false);
+    let mut redis_sink = redis_sink(address);
+
+    // buffered until "batch_size" accounts are
+    let first = account(&Pubkey::new_unique(), &OWNER, 1, &[1]);
+    redis_sink.update_account(&first, false).unwrap();
+    assert!(store.lock().unwrap().is_empty(), "Account written before the batch is full");
+    let second = account(&Pubkey::new_unique(), &OWNER, 1, &[2]);
+    redis_sink.update_account(&second, false).unwrap();
+
+    let store = store.lock().unwrap();
+    assert!(store.contains_key(&account_key(first.pubkey.as_ref())), "First account not stored");
+    assert!(store.contains_key(&account_key(second.pubkey.as_ref())), "Second account not stored");
+}
+
+#[test]
+fn test_redis_sink_reconnect() {
+    let (address, store) = mock_redis(true);
+    let mut redis_sink = redis_sink(address);
+
+    let pubkey = Pubkey::new_unique();
+    let latest = account(&pubkey, &OWNER, 1, &[1]);
+    redis_sink.update_account(&latest, false).unwrap();
+    redis_sink.flush().unwrap();
+    let later = account(&pubkey, &OWNER, 2, &[2]);
+    redis_sink.update_account(&later, false).unwrap();
+    redis_sink.flush().unwrap();
+
+    let store = store.lock().unwrap();
+    let (_, stored) = store.get(&account_key(pubkey.as_ref())).expect("Account not stored");
+    assert_eq!(serializer(SerializationFormat::Json).deserialize_account(stored).unwrap(), later, "Latest account not stored");
+}
@@ -0,0 +1,58 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+#[test]
+fn test_rent_epoch_history() {
+    let address: Pubkey = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_rent_epoch_history.json")).unwrap();
+    // rent is collected at slot 2, notified twice, an update of an earlier slot arriving last
+    for (rent_epoch, slot) in [(300, 1), (300, 2), (301, 3), (301, 3), (300, 2)] {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 1,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch,
+                    data: &[0; 8],
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                slot,
+                false,
+            )
+            .unwrap();
+    }
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let history = client
+        .query("SELECT old_epoch, new_epoch, slot from account_rent_epoch_history where pubkey=$1", &[&address.to_string()])
+        .expect("Error selecting rent epoch history")
+        .iter()
+        .map(|row| (row.get(0), row.get(1), row.get(2)))
+        .collect::<Vec<(i64, i64, i64)>>();
+    assert_eq!(history, vec![(300, 301, 3)], "Incorrect rent epoch history");
+    let rent_epoch: i64 = client
+        .query_one("SELECT rent_epoch from account_current_rent_epoch where pubkey=$1", &[&address.to_string()])
+        .expect("Error selecting rent epoch")
+        .get(0);
+    assert_eq!(rent_epoch, 301, "Incorrect current rent epoch");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
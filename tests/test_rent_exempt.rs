@@ -0,0 +1,58 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+#[test]
+fn test_rent_exempt() {
+    let exempt_address: Pubkey = Keypair::new().pubkey();
+    let paying_address: Pubkey = Keypair::new().pubkey();
+    let data = vec![7_u8; 10];
+    // (128 bytes of account overhead + 10 bytes of data) * 3480 lamports per byte-year * 2 years
+    let minimum_balance = 960480;
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_rent_exempt.json")).unwrap();
+    for (address, lamports) in [(&exempt_address, minimum_balance), (&paying_address, minimum_balance - 1)] {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: &data,
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                0,
+                false,
+            )
+            .unwrap();
+    }
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let is_rent_exempt = |client: &mut postgres::Client, address: &Pubkey| -> Option<bool> {
+        client
+            .query_one("SELECT is_rent_exempt from account where pubkey=$1", &[&address.as_ref()])
+            .expect("Error selecting account")
+            .get("is_rent_exempt")
+    };
+    assert_eq!(is_rent_exempt(&mut client, &exempt_address), Some(true), "Rent-exempt account not flagged");
+    assert_eq!(is_rent_exempt(&mut client, &paying_address), Some(false), "Rent-paying account flagged");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
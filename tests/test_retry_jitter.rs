@@ -0,0 +1,54 @@
+use solana_geyser_plugin_postgres::config::RetryJitter;
+use solana_geyser_plugin_postgres::postgres_client::retry::RetryPolicy;
+use std::collections::HashSet;
+use std::time::Duration;
+
+const WORKERS: usize = 20;
+
+fn policy(jitter: RetryJitter) -> RetryPolicy {
+    RetryPolicy {
+        retries: 5,
+        base_delay_ms: 100,
+        max_delay_ms: 1000,
+        jitter,
+    }
+}
+
+/// The delay each worker waits before retrying after the failed `attempt`
+fn worker_delays(policy: &RetryPolicy, attempt: u32) -> Vec<Duration> {
+    (0..WORKERS).map(|_| policy.delay(attempt)).collect()
+}
+
+#[test]
+fn test_retry_backoff() {
+    let policy = policy(RetryJitter::None);
+    let backoffs = (0..6).map(|attempt| policy.backoff_ms(attempt)).collect::<Vec<u64>>();
+    assert_eq!(backoffs, vec![100, 200, 400, 800, 1000, 1000], "Incorrect backoff");
+    assert_eq!(policy.backoff_ms(100), 1000, "Backoff not capped");
+
+    // without jitter every worker retries at the same instant
+    let delays = worker_delays(&policy, 2);
+    assert!(delays.iter().all(|delay| *delay == Duration::from_millis(400)));
+}
+
+#[test]
+fn test_retry_full_jitter() {
+    let policy = policy(RetryJitter::Full);
+    for attempt in 0..6 {
+        let backoff = Duration::from_millis(policy.backoff_ms(attempt));
+        let delays = worker_delays(&policy, attempt);
+        assert!(delays.iter().all(|delay| *delay <= backoff), "Delay out of bounds");
+        assert!(delays.iter().collect::<HashSet<&Duration>>().len() > 1, "Delays do not vary across workers");
+    }
+}
+
+#[test]
+fn test_retry_equal_jitter() {
+    let policy = policy(RetryJitter::Equal);
+    for attempt in 0..6 {
+        let backoff = Duration::from_millis(policy.backoff_ms(attempt));
+        let delays = worker_delays(&policy, attempt);
+        assert!(delays.iter().all(|delay| *delay >= backoff / 2 && *delay <= backoff), "Delay out of bounds");
+        assert!(delays.iter().collect::<HashSet<&Duration>>().len() > 1, "Delays do not vary across workers");
+    }
+}
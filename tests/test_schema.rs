@@ -0,0 +1,57 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+#[test]
+fn test_schema() {
+    let address: Pubkey = Keypair::new().pubkey();
+    let slot = rand::random::<u32>() as i64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_schema.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 1,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &[1, 2, 3],
+                write_version: 0,
+                txn_signature: None,
+            }),
+            slot as u64,
+            false,
+        )
+        .unwrap();
+    geyser_plugin.update_slot_status(slot as u64, None, SlotStatus::Rooted).unwrap();
+    sleep(Duration::from_secs(1));
+    geyser_plugin.on_unload();
+
+    // the tables are read qualified, from a connection searching the default schema
+    let mut client = SimplePostgresClient::connect_to_db(&GeyserPluginPostgresConfig { schema: None, ..config }).expect("Failed to connect");
+    let count = |client: &mut postgres::Client, query: &str, param: &(dyn postgres::types::ToSql + Sync)| -> i64 { client.query_one(query, &[param]).expect("Error counting rows").get(0) };
+    let accounts = count(&mut client, "SELECT count(*) FROM geyser_test_schema.raw_account WHERE pubkey=$1", &address.to_string());
+    let default_accounts = count(&mut client, "SELECT count(*) FROM raw_account WHERE pubkey=$1", &address.to_string());
+    let slots = count(&mut client, "SELECT count(*) FROM geyser_test_schema.slot WHERE slot=$1", &slot);
+    let default_slots = count(&mut client, "SELECT count(*) FROM slot WHERE slot=$1", &slot);
+    client.batch_execute("DROP SCHEMA geyser_test_schema CASCADE").expect("Error dropping schema");
+
+    assert_eq!((accounts, slots), (1, 1), "Rows not written to the configured schema");
+    assert_eq!((default_accounts, default_slots), (0, 0), "Rows written to the default schema");
+    client.close().expect("Error disconnecting");
+}
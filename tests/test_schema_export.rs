@@ -0,0 +1,57 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+
+#[test]
+fn test_schema_export() {
+    let _ = std::fs::remove_file("/tmp/test_schema_export.json");
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_schema_export.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    let schema: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("/tmp/test_schema_export.json").expect("Schema not exported")).expect("Invalid JSON");
+    let tables = schema["tables"].as_array().expect("No tables");
+    let table = tables.iter().find(|table| table["name"] == "spl_token_account").expect("Token account table not exported");
+    let columns = table["columns"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|column| (column["name"].as_str().unwrap(), column["type"].as_str().unwrap(), column["nullable"].as_bool().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        &columns[..4],
+        &[
+            ("pubkey", "character varying(44)", false),
+            ("owner", "character varying(44)", false),
+            ("mint", "character varying(44)", false),
+            ("slot", "bigint", false)
+        ],
+        "Incorrect columns"
+    );
+    let index = table["indexes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|index| index["name"] == "spl_token_account_owner_pair")
+        .expect("Index not exported");
+    assert_eq!(index["unique"], true, "Index not unique");
+    assert_eq!(
+        index["definition"], "CREATE UNIQUE INDEX spl_token_account_owner_pair ON spl_token_account USING btree (pubkey, owner, mint)",
+        "Incorrect index definition"
+    );
+    assert!(
+        table["indexes"].as_array().unwrap().iter().any(|index| index["name"] == "spl_token_account_mint"),
+        "Mint index not exported"
+    );
+    // the slot and transaction tables are always created, with the types of their columns
+    assert!(tables.iter().any(|table| table["name"] == "slot"), "Slot table not exported");
+    let types = schema["types"].as_array().expect("No types");
+    assert!(types.iter().any(|t| t["name"] == "TransactionErrorCode" && t["kind"] == "enum"), "Enum type not exported");
+
+    // the scratch schema the tables are described in is rolled back
+    let mut client = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let scratch_schemas = client.query("SELECT 1 FROM pg_namespace WHERE nspname = 'geyser_schema_export'", &[]).expect("Error selecting schemas");
+    assert!(scratch_schemas.is_empty(), "Scratch schema left behind");
+    client.close().expect("Error disconnecting");
+}
@@ -0,0 +1,54 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+
+const CONFIG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_schema_migrations.json");
+
+#[test]
+fn test_schema_migrations() {
+    let config = GeyserPluginPostgresConfig::read_from(CONFIG).expect("Error reading config");
+    let mut client = SimplePostgresClient::connect_to_db(&GeyserPluginPostgresConfig { schema: None, ..config }).expect("Failed to connect");
+    // the slot table of an earlier version, its status too narrow for the later statuses
+    client
+        .batch_execute(
+            "
+                DROP SCHEMA IF EXISTS geyser_test_schema_migrations CASCADE;
+                CREATE SCHEMA geyser_test_schema_migrations;
+                CREATE TABLE geyser_test_schema_migrations.slot (slot BIGINT PRIMARY KEY, parent BIGINT, status VARCHAR(16) NOT NULL, updated_on TIMESTAMP NOT NULL);
+            ",
+        )
+        .expect("Error creating the earlier schema");
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(CONFIG).unwrap();
+    geyser_plugin.on_unload();
+    let status_length: Option<i32> = client
+        .query_one(
+            "SELECT character_maximum_length FROM information_schema.columns WHERE table_schema = 'geyser_test_schema_migrations' AND table_name = 'slot' AND column_name = 'status'",
+            &[],
+        )
+        .expect("Error reading the status column")
+        .get(0);
+    let applied = |client: &mut postgres::Client| -> Vec<(i32, chrono::NaiveDateTime)> {
+        client
+            .query("SELECT version, applied_at FROM geyser_test_schema_migrations.schema_migrations ORDER BY version", &[])
+            .expect("Error reading the migrations")
+            .into_iter()
+            .map(|row| (row.get(0), row.get(1)))
+            .collect()
+    };
+    let first_applied = applied(&mut client);
+
+    // loaded again, the recorded migrations are not applied twice
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(CONFIG).unwrap();
+    geyser_plugin.on_unload();
+    let second_applied = applied(&mut client);
+    client.batch_execute("DROP SCHEMA geyser_test_schema_migrations CASCADE").expect("Error dropping schema");
+
+    assert_eq!(status_length, Some(32), "The slot status was not widened");
+    assert_eq!(first_applied.iter().map(|(version, _)| *version).collect::<Vec<i32>>(), vec![1]);
+    assert_eq!(first_applied, second_applied, "Migrations applied again");
+    client.close().expect("Error disconnecting");
+}
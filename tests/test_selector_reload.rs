@@ -0,0 +1,81 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+fn update_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, owner: &Pubkey) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 1,
+                owner: owner.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &[1, 2, 3],
+                write_version: 0,
+                txn_signature: None,
+            }),
+            0,
+            false,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_selector_reload() {
+    let new_owner = Keypair::new().pubkey();
+    let addresses = (0..4).map(|_| Keypair::new().pubkey()).collect::<Vec<Pubkey>>();
+    let config_file = tempfile::NamedTempFile::new().unwrap();
+    let config_path = config_file.path().to_str().unwrap().to_string();
+    let config_json = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_selector_reload.json")).unwrap();
+    std::fs::write(&config_path, &config_json).unwrap();
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(&config_path).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    update_account(&mut geyser_plugin, &addresses[0], &OWNER);
+    update_account(&mut geyser_plugin, &addresses[1], &new_owner);
+
+    // the modification time may have a coarse resolution, the file being rewritten once it changed
+    sleep(Duration::from_millis(1100));
+    std::fs::write(&config_path, config_json.replace(&OWNER.to_string(), &new_owner.to_string())).unwrap();
+    sleep(Duration::from_millis(500));
+    update_account(&mut geyser_plugin, &addresses[2], &new_owner);
+
+    // a selector referencing an unknown handler is not swapped in
+    sleep(Duration::from_millis(1100));
+    std::fs::write(&config_path, config_json.replace(&OWNER.to_string(), &new_owner.to_string()).replace("raw_account", "no_such_handler")).unwrap();
+    sleep(Duration::from_millis(500));
+    update_account(&mut geyser_plugin, &addresses[3], &new_owner);
+
+    sleep(Duration::from_secs(1));
+    geyser_plugin.on_unload();
+
+    let mut client = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let keys = addresses.iter().map(|address| address.to_string()).collect::<Vec<String>>();
+    let written = client
+        .query("SELECT pubkey from raw_account where pubkey = ANY($1)", &[&keys])
+        .expect("Error selecting accounts")
+        .iter()
+        .map(|row| row.get::<_, String>(0))
+        .collect::<Vec<String>>();
+    client.execute("DELETE FROM raw_account where pubkey = ANY($1)", &[&keys]).expect("Error deleting accounts");
+
+    let is_written = |address: &Pubkey| written.contains(&address.to_string());
+    assert!(is_written(&addresses[0]), "Account of the initial owner not written");
+    assert!(!is_written(&addresses[1]), "Account written before its owner was selected");
+    assert!(is_written(&addresses[2]), "Account of the reloaded owner not written");
+    assert!(is_written(&addresses[3]), "Invalid selector swapped in");
+    client.close().expect("Error disconnecting");
+}
@@ -0,0 +1,136 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaBlockInfo;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_postgres::postgres_client::build_db_transaction;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::DbBlockInfo;
+use solana_geyser_plugin_postgres::serializer::serializer;
+use solana_geyser_plugin_postgres::serializer::SerializationFormat;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::message::v0;
+use solana_sdk::message::v0::LoadedAddresses;
+use solana_sdk::message::SimpleAddressLoader;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_sdk::transaction::TransactionError;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::Reward;
+use solana_transaction_status::RewardType;
+use solana_transaction_status::TransactionStatusMeta;
+
+#[test]
+fn test_serializer() {
+    let slot = rand::random::<u32>() as u64;
+    let pubkey = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let signature = solana_sdk::signature::Signature::new_unique();
+    let account = DbAccountInfo::new(
+        &ReplicaAccountInfoV2 {
+            pubkey: pubkey.as_ref(),
+            lamports: 42,
+            owner: owner.as_ref(),
+            executable: false,
+            rent_epoch: 7,
+            data: &[1, 2, 3],
+            write_version: 3,
+            txn_signature: Some(&signature),
+        },
+        slot,
+    );
+
+    let keypair = Keypair::new();
+    let instruction = system_instruction::transfer(&keypair.pubkey(), &Pubkey::new_unique(), 42);
+    let message = v0::Message::try_compile(&keypair.pubkey(), &[instruction], &[], Hash::new_unique()).unwrap();
+    let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &[&keypair]).unwrap();
+    let transaction = SanitizedTransaction::try_create(transaction, Hash::new_unique(), Some(false), SimpleAddressLoader::Enabled(LoadedAddresses::default()), false).unwrap();
+    let rewards = vec![Reward {
+        pubkey: keypair.pubkey().to_string(),
+        lamports: 10,
+        post_balance: 100,
+        reward_type: Some(RewardType::Voting),
+        commission: Some(5),
+    }];
+    let transaction = build_db_transaction(
+        slot,
+        &ReplicaTransactionInfoV2 {
+            signature: transaction.signature(),
+            is_vote: false,
+            transaction: &transaction,
+            transaction_status_meta: &TransactionStatusMeta {
+                status: Err(TransactionError::InstructionError(0, InstructionError::Custom(1))),
+                fee: 5000,
+                pre_balances: vec![1000, 0, 1],
+                post_balances: vec![0, 42, 1],
+                log_messages: Some(vec!["Program log: failed".to_string()]),
+                rewards: Some(rewards.clone()),
+                ..TransactionStatusMeta::default()
+            },
+            index: 0,
+        },
+        1,
+    );
+
+    let block = DbBlockInfo::from(&ReplicaBlockInfo {
+        slot,
+        blockhash: &Hash::new_unique().to_string(),
+        rewards: &rewards,
+        block_time: Some(1_700_000_000),
+        block_height: Some(slot + 1),
+    });
+
+    for format in [SerializationFormat::Json, SerializationFormat::Borsh, SerializationFormat::Debezium] {
+        let serializer = serializer(format);
+        let bytes = serializer.serialize_account(&account).unwrap();
+        assert_eq!(serializer.deserialize_account(&bytes).unwrap(), account, "{:?}", format);
+        let bytes = serializer.serialize_transaction(&transaction).unwrap();
+        assert_eq!(serializer.deserialize_transaction(&bytes).unwrap(), transaction, "{:?}", format);
+        let bytes = serializer.serialize_block(&block).unwrap();
+        assert_eq!(serializer.deserialize_block(&bytes).unwrap(), block, "{:?}", format);
+    }
+}
+
+#[test]
+fn test_debezium_envelope() {
+    let slot = rand::random::<u32>() as u64;
+    let pubkey = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let account = |lamports: u64, slot: u64| {
+        DbAccountInfo::new(
+            &ReplicaAccountInfoV2 {
+                pubkey: pubkey.as_ref(),
+                lamports,
+                owner: owner.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &[1, 2, 3],
+                write_version: 1,
+                txn_signature: None,
+            },
+            slot,
+        )
+    };
+    let created = account(42, slot);
+    let updated = account(43, slot + 1);
+    let serializer = serializer(SerializationFormat::Debezium);
+
+    let envelope: serde_json::Value = serde_json::from_slice(&serializer.serialize_account_change(None, &created).unwrap()).unwrap();
+    assert_eq!(envelope["op"], "c", "Insert not created");
+    assert!(envelope["before"].is_null(), "Insert with a previous state");
+    assert_eq!(envelope["after"], serde_json::to_value(&created).unwrap(), "Incorrect inserted account");
+    assert_eq!(envelope["source"]["table"], "account", "Incorrect source table");
+    assert_eq!(envelope["source"]["slot"], slot, "Incorrect source slot");
+    assert!(envelope["ts_ms"].is_i64(), "Missing event time");
+
+    let bytes = serializer.serialize_account_change(Some(&created), &updated).unwrap();
+    let envelope: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(envelope["op"], "u", "Update not updated");
+    assert_eq!(envelope["before"], serde_json::to_value(&created).unwrap(), "Incorrect previous account");
+    assert_eq!(envelope["after"], serde_json::to_value(&updated).unwrap(), "Incorrect updated account");
+    assert_eq!(envelope["source"]["slot"], slot + 1, "Incorrect source slot");
+    assert_eq!(serializer.deserialize_account(&bytes).unwrap(), updated, "Update not read as the updated account");
+}
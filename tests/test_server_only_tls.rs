@@ -0,0 +1,34 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+
+const CONFIG_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_server_only_tls.json");
+
+#[test]
+fn test_server_only_tls() {
+    let config = GeyserPluginPostgresConfig::read_from(CONFIG_PATH).expect("Failed to read config");
+    let mut client = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let setting = |client: &mut postgres::Client, name: &str| client.query_one(&format!("SHOW {}", name), &[]).unwrap().get::<_, String>(0);
+    if setting(&mut client, "ssl") != "on" {
+        eprintln!("SSL is off on the server, skipping");
+        return;
+    }
+    // the certificate of the server is its own authority
+    let tls_config = GeyserPluginPostgresConfig {
+        use_ssl: Some(true),
+        server_ca: Some(setting(&mut client, "ssl_cert_file")),
+        ..config
+    };
+
+    // the client authenticates by its password only
+    let mut client = SimplePostgresClient::connect_to_db(&tls_config).expect("Failed to connect without a client certificate");
+    assert!(client.query_one("SELECT ssl FROM pg_stat_ssl WHERE pid = pg_backend_pid()", &[]).unwrap().get::<_, bool>(0));
+
+    match SimplePostgresClient::connect_to_db(&GeyserPluginPostgresConfig {
+        client_cert: Some("/solana/.ssh/client-cert.pem".to_string()),
+        ..tls_config
+    }) {
+        Err(GeyserPluginError::ConfigFileReadError { msg }) => assert!(msg.contains("\"client_key\""), "Missing client key not reported: {}", msg),
+        other => panic!("Expected a config error, got {:?}", other.map(|_| ())),
+    }
+}
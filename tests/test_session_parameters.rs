@@ -0,0 +1,22 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+
+#[test]
+fn test_session_parameters() {
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_session_parameters.json")).unwrap();
+    let mut config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    let mut client = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    for (name, value) in [("work_mem", "12MB"), ("jit", "off"), ("lock_timeout", "3s")] {
+        let setting: String = client.query_one(&format!("SHOW {}", name), &[]).expect("Error showing parameter").get(0);
+        assert_eq!(setting, value, "Incorrect {}", name);
+    }
+    client.close().expect("Error disconnecting");
+
+    // a name which is not a plain parameter name is rejected
+    config.session_parameters.insert("work_mem TO '1MB'; DROP TABLE slot; SET jit".to_string(), "off".to_string());
+    assert!(SimplePostgresClient::connect_to_db(&config).is_err(), "Invalid parameter name accepted");
+}
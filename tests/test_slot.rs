@@ -3,9 +3,12 @@ use std::time::Duration;
 
 use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
 use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
 use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
 use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
 
+const STATUS_NAME_CONFIG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_slot_status_name.json");
+
 #[test]
 fn test_slot() {
     let slot_num: u32 = rand::random::<u32>();
@@ -28,3 +31,57 @@ fn test_slot() {
     client.close().expect("Error disconnecting");
     geyser_plugin.on_unload();
 }
+
+#[test]
+fn test_slot_status_name() {
+    let config = GeyserPluginPostgresConfig::read_from(STATUS_NAME_CONFIG).expect("Error reading config");
+    let mut client = SimplePostgresClient::connect_to_db(&GeyserPluginPostgresConfig { schema: None, ..config }).expect("Failed to connect");
+    // the slot table of an earlier version, its status too narrow for the names of the later statuses
+    client
+        .batch_execute(
+            "
+                DROP SCHEMA IF EXISTS geyser_test_slot_status_name CASCADE;
+                CREATE SCHEMA geyser_test_slot_status_name;
+                CREATE TABLE geyser_test_slot_status_name.slot (slot BIGINT PRIMARY KEY, parent BIGINT, status VARCHAR(16) NOT NULL, updated_on TIMESTAMP NOT NULL);
+            ",
+        )
+        .expect("Error creating the earlier slot table");
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(STATUS_NAME_CONFIG).unwrap();
+    let status_length: Option<i32> = client
+        .query_one(
+            "SELECT character_maximum_length FROM information_schema.columns WHERE table_schema = 'geyser_test_slot_status_name' AND table_name = 'slot' AND column_name = 'status'",
+            &[],
+        )
+        .expect("Error reading the status column")
+        .get(0);
+
+    // each status is stored by its name
+    let slot = rand::random::<u32>() as u64;
+    let statuses = [SlotStatus::Processed, SlotStatus::Confirmed, SlotStatus::Rooted];
+    for (i, status) in statuses.iter().enumerate() {
+        geyser_plugin.update_slot_status(slot + i as u64, None, *status).unwrap();
+    }
+    sleep(Duration::from_secs(1));
+    geyser_plugin.on_unload();
+    let stored_statuses = statuses
+        .iter()
+        .enumerate()
+        .map(|(i, _)| {
+            client
+                .query_one("SELECT status FROM geyser_test_slot_status_name.slot WHERE slot=$1", &[&((slot + i as u64) as i64)])
+                .expect("Error selecting slot")
+                .get::<_, String>("status")
+        })
+        .collect::<Vec<String>>();
+    client.batch_execute("DROP SCHEMA geyser_test_slot_status_name CASCADE").expect("Error dropping schema");
+
+    assert_eq!(status_length, Some(32), "The slot status was not widened");
+    assert_eq!(
+        stored_statuses,
+        statuses.iter().map(|status| status.as_str().to_string()).collect::<Vec<String>>(),
+        "Incorrect statuses"
+    );
+    client.close().expect("Error disconnecting");
+}
@@ -0,0 +1,61 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaBlockInfo;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaBlockInfoVersions;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+
+fn notify_block(geyser_plugin: &mut GeyserPluginPostgres, slot: u64, block_time: i64) {
+    geyser_plugin
+        .notify_block_metadata(ReplicaBlockInfoVersions::V0_0_1(&ReplicaBlockInfo {
+            slot,
+            blockhash: "EEFdm1t3obBG5q2V7kwCs5HvHdfVAWbQs5dV1QZLqJJB",
+            rewards: &[],
+            block_height: None,
+            block_time: Some(block_time),
+        }))
+        .unwrap();
+}
+
+#[test]
+fn test_slot_catchup() {
+    let slot_num: u32 = rand::random::<u32>();
+    let slot = slot_num as i64;
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64;
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_slot_catchup.json")).unwrap();
+
+    // an hour behind, intermediate statuses are skipped
+    notify_block(&mut geyser_plugin, slot as u64, now - 3600);
+    geyser_plugin.update_slot_status(slot as u64, None, SlotStatus::Processed).unwrap();
+    geyser_plugin.update_slot_status(slot as u64, None, SlotStatus::Confirmed).unwrap();
+    geyser_plugin.update_slot_status(slot as u64 + 1, None, SlotStatus::Confirmed).unwrap();
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let rows = client.query("SELECT * from slot where slot=$1 or slot=$2", &[&slot, &(slot + 1)]).expect("Error selecting slots");
+    assert_eq!(rows.len(), 0, "Intermediate statuses written during catch-up");
+
+    geyser_plugin.update_slot_status(slot as u64, None, SlotStatus::Rooted).unwrap();
+    sleep(Duration::from_secs(1));
+    let rows = client.query("SELECT status from slot where slot=$1", &[&slot]).expect("Error selecting slots");
+    assert_eq!(rows.len(), 1, "Rooted status not written during catch-up");
+    let status: String = rows[0].get("status");
+    assert_eq!(status, SlotStatus::Rooted.as_str(), "Incorrect status");
+
+    // caught up, full fidelity again
+    notify_block(&mut geyser_plugin, slot as u64 + 1, now);
+    geyser_plugin.update_slot_status(slot as u64 + 1, None, SlotStatus::Confirmed).unwrap();
+    sleep(Duration::from_secs(1));
+    let rows = client.query("SELECT status from slot where slot=$1", &[&(slot + 1)]).expect("Error selecting slots");
+    assert_eq!(rows.len(), 1, "Status not written after catching up");
+    let status: String = rows[0].get("status");
+    assert_eq!(status, SlotStatus::Confirmed.as_str(), "Incorrect status");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
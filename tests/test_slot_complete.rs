@@ -0,0 +1,72 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+static TOKEN_ACCOUNT_OWNER: Pubkey = pubkey!("cpmaMZyBQiPxpeuxNsQhW7N8z1o9yaNdLgiPhWGUEiX");
+static MINT: Pubkey = pubkey!("DUSTawucrTsGU8hcqRdHDCbuYhCPADMLM2VcCb8VnFnQ");
+
+#[test]
+fn test_slot_complete() {
+    let slot = rand::random::<u32>() as u64;
+    let address: Pubkey = Keypair::new().pubkey();
+    let data = common::token_account_data(&MINT, &TOKEN_ACCOUNT_OWNER, 0);
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_slot_complete.json")).unwrap();
+    // buffered by the worker until the end of startup
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 2039280,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &data,
+                write_version: 1,
+                txn_signature: None,
+            }),
+            slot,
+            true,
+        )
+        .unwrap();
+    geyser_plugin.update_slot_status(slot, None, SlotStatus::Rooted).unwrap();
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let slot_rows = client.query("SELECT * from slot where slot=$1", &[&(slot as i64)]).expect("Error selecting slots");
+    assert_eq!(slot_rows.len(), 1, "Slot status not written");
+    let rows = client.query("SELECT * from slot_complete where slot=$1", &[&(slot as i64)]).expect("Error selecting markers");
+    assert_eq!(rows.len(), 0, "Slot marked complete before its account committed");
+
+    geyser_plugin.notify_end_of_startup().unwrap();
+    sleep(Duration::from_secs(1));
+    let rows = client
+        .query("SELECT * from spl_token_account where pubkey=$1", &[&address.to_string()])
+        .expect("Error selecting account");
+    assert_eq!(rows.len(), 1, "Account not written");
+    let rows = client.query("SELECT * from slot_complete where slot=$1", &[&(slot as i64)]).expect("Error selecting markers");
+    assert_eq!(rows.len(), 1, "Slot not marked complete");
+
+    // a rooted slot without in-flight writes is complete right away
+    geyser_plugin.update_slot_status(slot + 1, Some(slot), SlotStatus::Rooted).unwrap();
+    sleep(Duration::from_secs(1));
+    let rows = client.query("SELECT * from slot_complete where slot=$1", &[&(slot as i64 + 1)]).expect("Error selecting markers");
+    assert_eq!(rows.len(), 1, "Slot not marked complete");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
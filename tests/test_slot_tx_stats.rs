@@ -0,0 +1,78 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::message::Message;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_sdk::transaction::Transaction;
+use solana_sdk::transaction::TransactionError;
+use solana_transaction_status::TransactionStatusMeta;
+
+fn notify_transaction(geyser_plugin: &mut GeyserPluginPostgres, transaction: &SanitizedTransaction, index: usize, failed: bool, slot: u64) {
+    let status = match failed {
+        true => Err(TransactionError::InstructionError(0, InstructionError::InsufficientFunds)),
+        false => Ok(()),
+    };
+    geyser_plugin
+        .notify_transaction(
+            ReplicaTransactionInfoVersions::V0_0_2(&ReplicaTransactionInfoV2 {
+                signature: transaction.signature(),
+                is_vote: false,
+                transaction,
+                transaction_status_meta: &TransactionStatusMeta {
+                    status,
+                    fee: 5000 + index as u64,
+                    ..TransactionStatusMeta::default()
+                },
+                index,
+            }),
+            slot,
+        )
+        .unwrap();
+}
+
+fn new_transaction() -> SanitizedTransaction {
+    let keypair = Keypair::new();
+    let instruction = system_instruction::transfer(&keypair.pubkey(), &Keypair::new().pubkey(), 42);
+    let transaction = Transaction::new(&[&keypair], Message::new(&[instruction], Some(&keypair.pubkey())), Hash::new_unique());
+    SanitizedTransaction::from_transaction_for_tests(transaction)
+}
+
+#[test]
+fn test_slot_tx_stats() {
+    let slot = rand::random::<u32>() as u64;
+    let transactions = (0..4).map(|_| new_transaction()).collect::<Vec<SanitizedTransaction>>();
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_slot_tx_stats.json")).unwrap();
+    // the transactions of the later slot arrive first, the ones of the slot out of their order
+    notify_transaction(&mut geyser_plugin, &transactions[3], 0, false, slot + 1);
+    notify_transaction(&mut geyser_plugin, &transactions[2], 2, false, slot);
+    notify_transaction(&mut geyser_plugin, &transactions[0], 0, true, slot);
+    notify_transaction(&mut geyser_plugin, &transactions[1], 1, false, slot);
+    // notified again, the transaction is not counted twice
+    notify_transaction(&mut geyser_plugin, &transactions[1], 1, false, slot);
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let stats = |client: &mut postgres::Client, slot: u64| -> (i64, i64, i64) {
+        let row = client
+            .query_one("SELECT tx_count, failed_count, total_fee FROM slot_tx_stats WHERE slot=$1", &[&(slot as i64)])
+            .expect("Error selecting the slot stats");
+        (row.get(0), row.get(1), row.get(2))
+    };
+    assert_eq!(stats(&mut client, slot), (3, 1, 15003), "Incorrect stats of the slot");
+    assert_eq!(stats(&mut client, slot + 1), (1, 0, 5000), "Incorrect stats of the later slot");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
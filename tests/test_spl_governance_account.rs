@@ -0,0 +1,110 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+static OWNER: Pubkey = pubkey!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
+
+/// ProposalV2 in "Voting" state with options "Approve" (1_000 votes) and "Other" (250 votes) and 40 deny votes
+fn proposal_v2_data(governance: &Pubkey, mint: &Pubkey, token_owner_record: &Pubkey) -> Vec<u8> {
+    let mut data = vec![14];
+    data.extend_from_slice(governance.as_ref());
+    data.extend_from_slice(mint.as_ref());
+    data.push(2); // state
+    data.extend_from_slice(token_owner_record.as_ref());
+    data.extend_from_slice(&[1, 1]); // signatories count, signed off count
+    data.extend_from_slice(&[1, 0, 1, 2, 2]); // vote type MultiChoice
+    data.extend_from_slice(&2_u32.to_le_bytes());
+    for (label, vote_weight) in [("Approve", 1_000_u64), ("Other", 250)] {
+        data.extend_from_slice(&(label.len() as u32).to_le_bytes());
+        data.extend_from_slice(label.as_bytes());
+        data.extend_from_slice(&vote_weight.to_le_bytes());
+        data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0]); // vote result, transactions counts
+    }
+    data.push(1);
+    data.extend_from_slice(&40_u64.to_le_bytes()); // deny vote weight
+    data.extend_from_slice(&[0; 128]); // remaining proposal fields
+    data
+}
+
+/// VoteRecordV2 approving a proposal with a weight of 1_000
+fn vote_record_v2_data(proposal: &Pubkey, voter: &Pubkey) -> Vec<u8> {
+    let mut data = vec![12];
+    data.extend_from_slice(proposal.as_ref());
+    data.extend_from_slice(voter.as_ref());
+    data.push(0); // is relinquished
+    data.extend_from_slice(&1_000_u64.to_le_bytes());
+    data.push(0); // approve
+    data.extend_from_slice(&1_u32.to_le_bytes());
+    data.extend_from_slice(&[0, 100]);
+    data.extend_from_slice(&[0; 8]); // reserved
+    data
+}
+
+fn update_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, data: &[u8]) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 2790960,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            1,
+            false,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_spl_governance_account() {
+    let (governance, mint, token_owner_record) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (proposal, vote_record, voter) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_spl_governance.json")).unwrap();
+    update_account(&mut geyser_plugin, &proposal, &proposal_v2_data(&governance, &mint, &token_owner_record));
+    update_account(&mut geyser_plugin, &vote_record, &vote_record_v2_data(&proposal, &voter));
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+
+    // check proposal
+    let row = client
+        .query_one(
+            "SELECT governance, governing_token_mint, state, token_owner_record, option_vote_weights::TEXT AS option_vote_weights, deny_vote_weight::BIGINT AS deny_vote_weight from spl_governance_proposal where pubkey=$1",
+            &[&proposal.to_string()],
+        )
+        .expect("Error selecting proposal");
+    assert_eq!(row.get::<_, String>("governance"), governance.to_string(), "Incorrect governance");
+    assert_eq!(row.get::<_, String>("governing_token_mint"), mint.to_string(), "Incorrect governing token mint");
+    assert_eq!(row.get::<_, i16>("state"), 2, "Incorrect state");
+    assert_eq!(row.get::<_, String>("token_owner_record"), token_owner_record.to_string(), "Incorrect token owner record");
+    assert_eq!(row.get::<_, String>("option_vote_weights"), "{1000,250}", "Incorrect option vote weights");
+    assert_eq!(row.get::<_, Option<i64>>("deny_vote_weight"), Some(40), "Incorrect deny vote weight");
+
+    // check vote record
+    let row = client
+        .query_one(
+            "SELECT proposal, voter, is_relinquished, voter_weight::BIGINT AS voter_weight, vote from spl_governance_vote_record where pubkey=$1",
+            &[&vote_record.to_string()],
+        )
+        .expect("Error selecting vote record");
+    assert_eq!(row.get::<_, String>("proposal"), proposal.to_string(), "Incorrect proposal");
+    assert_eq!(row.get::<_, String>("voter"), voter.to_string(), "Incorrect voter");
+    assert!(!row.get::<_, bool>("is_relinquished"), "Incorrect relinquished flag");
+    assert_eq!(row.get::<_, i64>("voter_weight"), 1_000, "Incorrect voter weight");
+    assert_eq!(row.get::<_, String>("vote"), "approve", "Incorrect vote");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
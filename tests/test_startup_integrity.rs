@@ -0,0 +1,79 @@
+mod common;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+static TOKEN_ACCOUNT_OWNER: Pubkey = pubkey!("cpmaMZyBQiPxpeuxNsQhW7N8z1o9yaNdLgiPhWGUEiX");
+static MINT: Pubkey = pubkey!("DUSTawucrTsGU8hcqRdHDCbuYhCPADMLM2VcCb8VnFnQ");
+
+#[test]
+fn test_startup_integrity_dropped_account() {
+    let slot = rand::random::<u32>() as u64;
+    let addresses = (0..3).map(|_| Keypair::new().pubkey()).collect::<Vec<Pubkey>>();
+    let data = common::token_account_data(&MINT, &TOKEN_ACCOUNT_OWNER, 0);
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_startup_integrity.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    // the database rejects the write of the second account, which gets dropped
+    client
+        .batch_execute(&format!(
+            "
+                CREATE OR REPLACE FUNCTION reject_dropped_account() RETURNS trigger AS $$
+                BEGIN
+                    IF NEW.pubkey = '{0}' THEN
+                        RAISE EXCEPTION 'dropped account';
+                    END IF;
+                    RETURN NEW;
+                END $$ LANGUAGE plpgsql;
+                CREATE TRIGGER reject_dropped_account BEFORE INSERT ON spl_token_account FOR EACH ROW EXECUTE FUNCTION reject_dropped_account();
+            ",
+            addresses[1]
+        ))
+        .expect("Error creating trigger");
+
+    for (i, address) in addresses.iter().enumerate() {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 2039280,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: &data,
+                    write_version: i as u64,
+                    txn_signature: None,
+                }),
+                slot,
+                true,
+            )
+            .unwrap();
+    }
+    geyser_plugin.notify_end_of_startup().unwrap();
+    client
+        .batch_execute("DROP TRIGGER reject_dropped_account ON spl_token_account; DROP FUNCTION reject_dropped_account();")
+        .expect("Error dropping trigger");
+
+    let report = client
+        .query_one(
+            "SELECT notified_accounts, flushed_accounts, expected_rooted_slots, rooted_slots from startup_integrity_report ORDER BY reported_on DESC LIMIT 1",
+            &[],
+        )
+        .expect("Error selecting report");
+    assert_eq!(report.get::<_, i64>("notified_accounts"), 3, "Incorrect notified accounts");
+    assert_eq!(report.get::<_, i64>("flushed_accounts"), 2, "Dropped account not reported");
+    assert_eq!(report.get::<_, i64>("expected_rooted_slots"), 1, "Incorrect expected rooted slots");
+    assert_eq!(report.get::<_, i64>("rooted_slots"), 1, "Incorrect rooted slots");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,73 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+#[test]
+fn test_startup_memory_budget() {
+    let addresses: Vec<Pubkey> = (0..8).map(|_| Keypair::new().pubkey()).collect();
+    // a little over 2 KiB per account with its fields, the budget of 16 KiB being flushed at 12 KiB
+    let data = vec![7_u8; 2000];
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_startup_memory_budget.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let written_count = |client: &mut postgres::Client| -> i64 {
+        client
+            .query_one(
+                "SELECT COUNT(*) from raw_account where pubkey = any($1)",
+                &[&addresses.iter().map(|a| a.to_string()).collect::<Vec<String>>()],
+            )
+            .expect("Error selecting accounts")
+            .get(0)
+    };
+
+    let mut notified = addresses.iter();
+    let mut notify = |count: usize| {
+        for address in notified.by_ref().take(count) {
+            geyser_plugin
+                .update_account(
+                    ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                        pubkey: address.as_ref(),
+                        lamports: 1,
+                        owner: OWNER.as_ref(),
+                        executable: false,
+                        rent_epoch: 0,
+                        data: &data,
+                        write_version: 0,
+                        txn_signature: None,
+                    }),
+                    1,
+                    true,
+                )
+                .unwrap();
+            // the queued accounts count in the estimate too, let the worker buffer each before the next
+            sleep(Duration::from_millis(100));
+        }
+    };
+    // far below the batch size and the budget, the accounts stay buffered
+    notify(2);
+    sleep(Duration::from_secs(1));
+    assert_eq!(written_count(&mut client), 0, "Accounts flushed below the budget");
+
+    // the sixth account nears the budget, flushing the buffer
+    notify(6);
+    sleep(Duration::from_secs(1));
+    assert_eq!(written_count(&mut client), 6, "Accounts not flushed as the budget is approached");
+
+    geyser_plugin.notify_end_of_startup().unwrap();
+    sleep(Duration::from_secs(1));
+    assert_eq!(written_count(&mut client), 8, "Accounts not flushed at the end of startup");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,57 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+#[test]
+fn test_startup_slot_chunks() {
+    // five slots written in chunks of two, the last chunk holding a single slot
+    let first_slot = rand::random::<u32>() as i64;
+    let slots = (first_slot..first_slot + 5).collect::<Vec<i64>>();
+    let addresses = slots.iter().map(|_| Keypair::new().pubkey()).collect::<Vec<Pubkey>>();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_startup_slot_chunks.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    for (address, slot) in addresses.iter().zip(&slots) {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 1,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: &[1, 2, 3],
+                    write_version: 0,
+                    txn_signature: None,
+                }),
+                *slot as u64,
+                true,
+            )
+            .unwrap();
+    }
+    geyser_plugin.notify_end_of_startup().unwrap();
+    geyser_plugin.on_unload();
+
+    let mut client = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let keys = addresses.iter().map(|address| address.to_string()).collect::<Vec<String>>();
+    let rows = client
+        .query("SELECT slot, status, epoch from slot where slot = ANY($1) ORDER BY slot", &[&slots])
+        .expect("Error selecting slots");
+    client.execute("DELETE FROM raw_account where pubkey = ANY($1)", &[&keys]).expect("Error deleting accounts");
+    client.execute("DELETE FROM slot where slot = ANY($1)", &[&slots]).expect("Error deleting slots");
+
+    assert_eq!(rows.iter().map(|row| row.get::<_, i64>("slot")).collect::<Vec<i64>>(), slots, "Startup slots not all written");
+    for row in &rows {
+        assert_eq!(row.get::<_, String>("status"), "rooted");
+        assert!(row.get::<_, Option<i64>>("epoch").is_some(), "Startup slot written without its epoch");
+    }
+    client.close().expect("Error disconnecting");
+}
@@ -0,0 +1,59 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+fn account(address: &Pubkey, slot: u64) -> DbAccountInfo {
+    DbAccountInfo::new(
+        &ReplicaAccountInfoV2 {
+            pubkey: address.as_ref(),
+            lamports: 1,
+            owner: OWNER.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data: &[1, 2, 3],
+            write_version: 0,
+            txn_signature: None,
+        },
+        slot,
+    )
+}
+
+#[test]
+fn test_startup_slots_cleared() {
+    let first_slot = rand::random::<u32>() as i64;
+    let slots = (first_slot..first_slot + 4).collect::<Vec<i64>>();
+    let addresses = slots.iter().map(|_| Keypair::new().pubkey()).collect::<Vec<Pubkey>>();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_join_flush.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+
+    for (address, slot) in addresses.iter().zip(&slots).take(3) {
+        client.update_account(account(address, *slot as u64), true).expect("Error buffering account");
+    }
+    let pending_before_flush = client.pending_startup_slot_count();
+    client.notify_end_of_startup().expect("Error flushing startup");
+    let pending_after_flush = client.pending_startup_slot_count();
+    // a startup account notified late is written, its slot not being kept
+    client.update_account(account(&addresses[3], slots[3] as u64), true).expect("Error writing account");
+    let pending_after_late_account = client.pending_startup_slot_count();
+
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let keys = addresses.iter().map(|address| address.to_string()).collect::<Vec<String>>();
+    reader.execute("DELETE FROM raw_account where pubkey = ANY($1)", &[&keys]).expect("Error deleting accounts");
+    reader.execute("DELETE FROM slot where slot = ANY($1)", &[&slots]).expect("Error deleting slots");
+    assert_eq!(pending_before_flush, 3);
+    assert_eq!(pending_after_flush, 0, "Startup slots kept after the flush");
+    assert_eq!(pending_after_late_account, 0, "Slot of a late startup account kept");
+    reader.close().expect("Error disconnecting");
+}
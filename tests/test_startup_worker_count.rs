@@ -0,0 +1,30 @@
+use std::io::Read;
+use std::os::unix::net::UnixStream;
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+
+fn running_worker_count() -> usize {
+    let mut stream = UnixStream::connect("/tmp/geyser-postgres-startup-workers-test.sock").expect("Failed to connect to the admin socket");
+    let mut response = String::new();
+    stream.read_to_string(&mut response).expect("Error reading the admin socket");
+    let stats: serde_json::Value = serde_json::from_str(&response).expect("Invalid JSON");
+    stats["workers"].as_array().expect("No workers found").iter().filter(|worker| worker["state"] != "stopped").count()
+}
+
+#[test]
+fn test_startup_worker_count() {
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_startup_worker_count.json")).unwrap();
+
+    sleep(Duration::from_secs(1));
+    assert_eq!(running_worker_count(), 3, "Incorrect number of workers during startup");
+
+    geyser_plugin.notify_end_of_startup().unwrap();
+    sleep(Duration::from_secs(1));
+    assert_eq!(running_worker_count(), 1, "Incorrect number of workers after startup");
+
+    geyser_plugin.on_unload();
+}
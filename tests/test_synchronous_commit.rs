@@ -0,0 +1,100 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+#[test]
+fn test_synchronous_commit() {
+    let address: Pubkey = Keypair::new().pubkey();
+    let slot = rand::random::<u32>() as u64;
+    let data = common::token_account_data(&Keypair::new().pubkey(), &Keypair::new().pubkey(), 0);
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_synchronous_commit.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+
+    // the level the writes of the account and of the slot are committed with is recorded by triggers
+    let suffix = &address.to_string()[..8].to_lowercase();
+    client
+        .batch_execute(&format!(
+            "
+                CREATE TABLE test_synchronous_commit_{0} (category TEXT, level TEXT);
+                CREATE FUNCTION test_synchronous_commit_{0}() RETURNS trigger AS $$ BEGIN
+                    IF TG_TABLE_NAME = 'spl_token_account' THEN
+                        IF NEW.pubkey = '{1}' THEN
+                            INSERT INTO test_synchronous_commit_{0} VALUES (TG_TABLE_NAME, current_setting('synchronous_commit'));
+                        END IF;
+                    ELSIF NEW.slot = {2} THEN
+                        INSERT INTO test_synchronous_commit_{0} VALUES (TG_TABLE_NAME, current_setting('synchronous_commit'));
+                    END IF;
+                    RETURN NEW;
+                END $$ LANGUAGE plpgsql;
+                CREATE TRIGGER test_synchronous_commit_{0} BEFORE INSERT OR UPDATE ON spl_token_account
+                    FOR EACH ROW EXECUTE FUNCTION test_synchronous_commit_{0}();
+                CREATE TRIGGER test_synchronous_commit_{0} BEFORE INSERT OR UPDATE ON slot
+                    FOR EACH ROW EXECUTE FUNCTION test_synchronous_commit_{0}();
+            ",
+            suffix, address, slot,
+        ))
+        .expect("Error creating trigger");
+
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports: 2039280,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            slot,
+            false,
+        )
+        .unwrap();
+    geyser_plugin.update_slot_status(slot, None, SlotStatus::Processed).unwrap();
+    sleep(Duration::from_secs(1));
+
+    let rows = client
+        .query(&format!("SELECT category, level FROM test_synchronous_commit_{} ORDER BY category", suffix), &[])
+        .expect("Error selecting levels");
+    let levels = rows
+        .iter()
+        .map(|row| (row.get::<_, String>("category"), row.get::<_, String>("level")))
+        .collect::<Vec<(String, String)>>();
+    client
+        .batch_execute(&format!(
+            "
+                DROP TRIGGER test_synchronous_commit_{0} ON spl_token_account;
+                DROP TRIGGER test_synchronous_commit_{0} ON slot;
+                DROP FUNCTION test_synchronous_commit_{0};
+                DROP TABLE test_synchronous_commit_{0};
+                DELETE FROM slot WHERE slot = {1};
+            ",
+            suffix, slot
+        ))
+        .expect("Error dropping trigger");
+    assert_eq!(
+        levels,
+        vec![("slot".to_string(), "local".to_string()), ("spl_token_account".to_string(), "off".to_string())],
+        "Writes not committed at the level of their category"
+    );
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+fn update_token_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, amount: u64, delegate: Option<(&Pubkey, u64)>, state: u8) {
+    let mut data = common::token_account_data(&Keypair::new().pubkey(), &Keypair::new().pubkey(), amount);
+    if let Some((delegate, delegated_amount)) = delegate {
+        data[72..76].copy_from_slice(&1_u32.to_le_bytes());
+        data[76..108].copy_from_slice(delegate.as_ref());
+        data[121..129].copy_from_slice(&delegated_amount.to_le_bytes());
+    }
+    data[108] = state;
+    common::update_token_program_account(geyser_plugin, address, &data, 1, 0);
+}
+
+#[test]
+fn test_token_account_balance() {
+    let delegated_address: Pubkey = Keypair::new().pubkey();
+    let frozen_address: Pubkey = Keypair::new().pubkey();
+    let delegate: Pubkey = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_single_threaded.json")).unwrap();
+    update_token_account(&mut geyser_plugin, &delegated_address, u64::MAX, Some((&delegate, 250)), 1);
+    update_token_account(&mut geyser_plugin, &frozen_address, 42, None, 2);
+    sleep(Duration::from_secs(1));
+
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let balance = |client: &mut postgres::Client, address: &Pubkey| -> (String, Option<String>, String, i16, bool) {
+        let row = client
+            .query_one(
+                "SELECT amount::TEXT, delegate, delegated_amount::TEXT, state, is_frozen from spl_token_account where pubkey=$1",
+                &[&address.to_string()],
+            )
+            .expect("Error selecting token account");
+        (row.get("amount"), row.get("delegate"), row.get("delegated_amount"), row.get("state"), row.get("is_frozen"))
+    };
+    assert_eq!(
+        balance(&mut client, &delegated_address),
+        (u64::MAX.to_string(), Some(delegate.to_string()), "250".to_string(), 1, false),
+        "Incorrect delegated token account"
+    );
+    assert_eq!(
+        balance(&mut client, &frozen_address),
+        ("42".to_string(), None, "0".to_string(), 2, true),
+        "Incorrect frozen token account"
+    );
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use rand::Rng;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+fn token_account(address: &Pubkey, mint: &Pubkey, amount: u64, slot: u64) -> DbAccountInfo {
+    // owned by itself, the updates of an account are upserts of the same row
+    let data = common::token_account_data(mint, address, amount);
+    DbAccountInfo::new(
+        &ReplicaAccountInfoV2 {
+            pubkey: address.as_ref(),
+            lamports: 2039280,
+            owner: OWNER.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data: &data,
+            write_version: 0,
+            txn_signature: None,
+        },
+        slot,
+    )
+}
+
+#[test]
+fn test_token_account_batch() {
+    let (address, other_address, mint) = (Keypair::new().pubkey(), Keypair::new().pubkey(), Keypair::new().pubkey());
+    let slot = rand::thread_rng().gen::<u32>() as u64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_token_account_batch.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    // a batch updating an account several times, out of order, keeps its latest update
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    client.update_account(token_account(&address, &mint, 1, slot), true).unwrap();
+    client.update_account(token_account(&address, &mint, 3, slot + 2), true).unwrap();
+    client.update_account(token_account(&address, &mint, 2, slot + 1), true).unwrap();
+    client.update_account(token_account(&other_address, &mint, 5, slot), true).unwrap();
+    assert_eq!(client.pending_account_update_count(), 0, "Batch not flushed");
+
+    let mut client = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let rows = client
+        .query("SELECT pubkey, amount::TEXT, slot from spl_token_account where mint=$1 ORDER BY amount", &[&mint.to_string()])
+        .expect("Error selecting token accounts");
+    let rows = rows.iter().map(|row| (row.get(0), row.get(1), row.get(2))).collect::<Vec<(String, String, i64)>>();
+    assert_eq!(
+        rows,
+        vec![(address.to_string(), "3".to_string(), slot as i64 + 2), (other_address.to_string(), "5".to_string(), slot as i64)],
+        "Incorrect token accounts"
+    );
+    client.close().expect("Error disconnecting");
+}
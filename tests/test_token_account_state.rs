@@ -0,0 +1,76 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+static TOKEN_ACCOUNT_OWNER: Pubkey = pubkey!("cpmaMZyBQiPxpeuxNsQhW7N8z1o9yaNdLgiPhWGUEiX");
+static MINT: Pubkey = pubkey!("DUSTawucrTsGU8hcqRdHDCbuYhCPADMLM2VcCb8VnFnQ");
+
+const INITIALIZED: u8 = 1;
+const FROZEN: u8 = 2;
+
+fn token_account_data(state: u8) -> Vec<u8> {
+    let mut data = common::token_account_data(&MINT, &TOKEN_ACCOUNT_OWNER, 1);
+    data[108] = state;
+    data
+}
+
+#[test]
+fn test_token_account_freeze_thaw() {
+    let address: Pubkey = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_single_threaded.json")).unwrap();
+
+    // initialized, frozen then thawed
+    for (slot, state) in [(1, INITIALIZED), (2, FROZEN), (3, INITIALIZED)] {
+        geyser_plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: address.as_ref(),
+                    lamports: 2039280,
+                    owner: OWNER.as_ref(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: &token_account_data(state),
+                    write_version: slot,
+                    txn_signature: None,
+                }),
+                slot,
+                false,
+            )
+            .unwrap();
+    }
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let rows = client
+        .query(
+            "SELECT old_state, new_state, slot from spl_token_account_state_history where pubkey=$1 ORDER BY slot",
+            &[&address.to_string()],
+        )
+        .expect("Error selecting state history");
+    let history = rows
+        .iter()
+        .map(|row| (row.get::<_, i16>("old_state"), row.get::<_, i16>("new_state"), row.get::<_, i64>("slot")))
+        .collect::<Vec<(i16, i16, i64)>>();
+    assert_eq!(history, vec![(1, 2, 2), (2, 1, 3)], "Incorrect state history");
+
+    let state: i16 = client
+        .query_one("SELECT state from spl_token_account where pubkey=$1", &[&address.to_string()])
+        .expect("Error selecting account")
+        .get("state");
+    assert_eq!(state, INITIALIZED as i16, "Incorrect state");
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
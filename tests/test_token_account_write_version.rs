@@ -0,0 +1,56 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use rand::Rng;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+struct TokenAccount {
+    address: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+}
+
+fn update_token_account(geyser_plugin: &mut GeyserPluginPostgres, account: &TokenAccount, amount: u64, slot: u64, write_version: u64) {
+    let data = common::token_account_data(&account.mint, &account.owner, amount);
+    common::update_token_program_account(geyser_plugin, &account.address, &data, slot, write_version);
+}
+
+#[test]
+fn test_token_account_write_version() {
+    let account = TokenAccount {
+        address: Keypair::new().pubkey(),
+        mint: Keypair::new().pubkey(),
+        owner: Keypair::new().pubkey(),
+    };
+    let slot = rand::thread_rng().gen::<u32>() as u64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_single_threaded.json")).unwrap();
+    // the later write of the slot is kept whatever the order of the notifications
+    update_token_account(&mut geyser_plugin, &account, 10, slot, 5);
+    update_token_account(&mut geyser_plugin, &account, 20, slot, 7);
+    update_token_account(&mut geyser_plugin, &account, 15, slot, 6);
+    sleep(Duration::from_secs(1));
+
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let balance = |client: &mut postgres::Client| -> (String, i64) {
+        let row = client
+            .query_one("SELECT amount::TEXT, write_version from spl_token_account where pubkey=$1", &[&account.address.to_string()])
+            .expect("Error selecting token account");
+        (row.get("amount"), row.get("write_version"))
+    };
+    assert_eq!(balance(&mut client), ("20".to_string(), 7), "Token account overwritten by an earlier write of its slot");
+
+    update_token_account(&mut geyser_plugin, &account, 30, slot + 1, 1);
+    sleep(Duration::from_secs(1));
+    assert_eq!(balance(&mut client), ("30".to_string(), 1), "Token account not updated at a later slot");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
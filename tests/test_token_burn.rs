@@ -0,0 +1,62 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+fn update_mint(geyser_plugin: &mut GeyserPluginPostgres, mint: &Pubkey, supply: u64, slot: u64) {
+    // an NFT mint: no decimals, its authority revoked
+    let mut data = vec![0_u8; 82];
+    data[36..44].copy_from_slice(&supply.to_le_bytes());
+    data[45] = 1;
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: mint.as_ref(),
+                lamports: 1461600,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            slot,
+            false,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_token_burn() {
+    let mint: Pubkey = Keypair::new().pubkey();
+    let slot = rand::random::<u32>() as u64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_token_burn.json")).unwrap();
+    update_mint(&mut geyser_plugin, &mint, 1, slot);
+    sleep(Duration::from_secs(1));
+    // the NFT is burnt, then the mint notified again at the same supply
+    update_mint(&mut geyser_plugin, &mint, 0, slot + 1);
+    sleep(Duration::from_secs(1));
+    update_mint(&mut geyser_plugin, &mint, 0, slot + 2);
+    sleep(Duration::from_secs(1));
+
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let rows = client
+        .query("SELECT slot, previous_supply::TEXT, supply::TEXT from token_burn where mint=$1", &[&mint.to_string()])
+        .expect("Error selecting burns");
+    let burns = rows.iter().map(|row| (row.get(0), row.get(1), row.get(2))).collect::<Vec<(i64, String, String)>>();
+    assert_eq!(burns, vec![(slot as i64 + 1, "1".to_string(), "0".to_string())], "Incorrect burns");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
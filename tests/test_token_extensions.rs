@@ -0,0 +1,195 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+fn push_extension(data: &mut Vec<u8>, extension_type: u16, value: &[u8]) {
+    data.extend_from_slice(&extension_type.to_le_bytes());
+    data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    data.extend_from_slice(value);
+}
+
+fn update_account(geyser_plugin: &mut GeyserPluginPostgres, pubkey: &Pubkey, data: &[u8]) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: pubkey.as_ref(),
+                lamports: 2039280,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            1,
+            false,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_token_extensions() {
+    let mint: Pubkey = Keypair::new().pubkey();
+    let permanent_delegate: Pubkey = Keypair::new().pubkey();
+    // initialized mint padded to the length of a token account, followed by its account type and extensions
+    let mut mint_data = vec![0_u8; 165];
+    mint_data[44] = 6;
+    mint_data[45] = 1;
+    mint_data.push(1);
+    let mut transfer_fee_config = vec![0_u8; 108];
+    transfer_fee_config[98..106].copy_from_slice(&5000_u64.to_le_bytes());
+    transfer_fee_config[106..108].copy_from_slice(&250_u16.to_le_bytes());
+    push_extension(&mut mint_data, 1, &transfer_fee_config);
+    push_extension(&mut mint_data, 12, permanent_delegate.as_ref());
+    push_extension(&mut mint_data, 9, &[]);
+
+    let token_account: Pubkey = Keypair::new().pubkey();
+    let mut account_data = common::token_account_data(&mint, &Keypair::new().pubkey(), 0);
+    account_data.push(2);
+    // an extension unknown to the handler is skipped
+    push_extension(&mut account_data, 0xfff0, &[7; 3]);
+    push_extension(&mut account_data, 2, &42_u64.to_le_bytes());
+    push_extension(&mut account_data, 13, &[]);
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_token_extensions.json")).unwrap();
+    update_account(&mut geyser_plugin, &mint, &mint_data);
+    update_account(&mut geyser_plugin, &token_account, &account_data);
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let row = client
+        .query_one(
+            "SELECT transfer_fee_basis_points, transfer_fee_maximum::TEXT, permanent_delegate, non_transferable from spl_mint where pubkey=$1",
+            &[&mint.to_string()],
+        )
+        .expect("Error selecting mint");
+    assert_eq!(row.get::<_, Option<i32>>("transfer_fee_basis_points"), Some(250), "Incorrect transfer fee basis points");
+    assert_eq!(row.get::<_, Option<String>>("transfer_fee_maximum"), Some("5000".to_string()), "Incorrect maximum transfer fee");
+    assert_eq!(row.get::<_, Option<String>>("permanent_delegate"), Some(permanent_delegate.to_string()), "Incorrect permanent delegate");
+    assert!(row.get::<_, bool>("non_transferable"), "Mint not non-transferable");
+
+    let row = client
+        .query_one(
+            "SELECT transfer_fee_withheld::TEXT, non_transferable from spl_token_account where pubkey=$1",
+            &[&token_account.to_string()],
+        )
+        .expect("Error selecting token account");
+    assert_eq!(row.get::<_, Option<String>>("transfer_fee_withheld"), Some("42".to_string()), "Incorrect withheld transfer fee");
+    assert!(row.get::<_, bool>("non_transferable"), "Token account not non-transferable");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
+
+#[test]
+fn test_token_account_extension_list() {
+    let token_account: Pubkey = Keypair::new().pubkey();
+    let mut account_data = common::token_account_data(&Keypair::new().pubkey(), &Keypair::new().pubkey(), 0);
+    account_data.push(2);
+    push_extension(&mut account_data, 7, &[]);
+    push_extension(&mut account_data, 8, &[1]);
+    push_extension(&mut account_data, 0xfff0, &[7; 3]);
+    push_extension(&mut account_data, 11, &[0]);
+    // the walk ends at the padding of an uninitialized extension
+    account_data.extend_from_slice(&[0; 8]);
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_token_extensions.json")).unwrap();
+    update_account(&mut geyser_plugin, &token_account, &account_data);
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let row = client
+        .query_one("SELECT extensions from spl_token_account where pubkey=$1", &[&token_account.to_string()])
+        .expect("Error selecting token account");
+    assert_eq!(
+        row.get::<_, Vec<String>>("extensions"),
+        vec!["immutable_owner", "memo_transfer", "65520", "cpi_guard"],
+        "Incorrect extensions"
+    );
+    let rows = client
+        .query(
+            "SELECT pubkey from spl_token_account where pubkey=$1 AND 'memo_transfer' = ANY(extensions)",
+            &[&token_account.to_string()],
+        )
+        .expect("Error filtering token accounts");
+    assert_eq!(rows.len(), 1, "Token account not filtered by extension");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
+
+fn push_string(value: &mut Vec<u8>, text: &str) {
+    value.extend_from_slice(&(text.len() as u32).to_le_bytes());
+    value.extend_from_slice(text.as_bytes());
+}
+
+#[test]
+fn test_token_metadata_extension() {
+    let mint: Pubkey = Keypair::new().pubkey();
+    let update_authority: Pubkey = Keypair::new().pubkey();
+    let mut mint_data = vec![0_u8; 165];
+    mint_data[45] = 1;
+    mint_data.push(1);
+    // the metadata pointer to the mint itself, then the variable-length metadata
+    let mut metadata_pointer = vec![0_u8; 32];
+    metadata_pointer.extend_from_slice(mint.as_ref());
+    push_extension(&mut mint_data, 18, &metadata_pointer);
+    let mut token_metadata = update_authority.to_bytes().to_vec();
+    token_metadata.extend_from_slice(mint.as_ref());
+    push_string(&mut token_metadata, "Token\0 #1");
+    push_string(&mut token_metadata, "TKN");
+    push_string(&mut token_metadata, "https://example.com/1.json");
+    token_metadata.extend_from_slice(&2_u32.to_le_bytes());
+    for (key, value) in [("rarity", "legendary"), ("artist's", "anon")] {
+        push_string(&mut token_metadata, key);
+        push_string(&mut token_metadata, value);
+    }
+    push_extension(&mut mint_data, 19, &token_metadata);
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_token_extensions.json")).unwrap();
+    update_account(&mut geyser_plugin, &mint, &mint_data);
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let metadata_address: Option<String> = client
+        .query_one("SELECT metadata_address from spl_mint where pubkey=$1", &[&mint.to_string()])
+        .expect("Error selecting mint")
+        .get(0);
+    assert_eq!(metadata_address, Some(mint.to_string()), "Incorrect metadata address");
+    let row = client
+        .query_one(
+            "SELECT update_authority, name, symbol, uri, additional_metadata::TEXT from token22_metadata where mint=$1",
+            &[&mint.to_string()],
+        )
+        .expect("Error selecting metadata");
+    assert_eq!(
+        (row.get(0), row.get(1), row.get(2), row.get(3), row.get(4)),
+        (
+            Some(update_authority.to_string()),
+            "Token #1".to_string(),
+            "TKN".to_string(),
+            "https://example.com/1.json".to_string(),
+            r#"[["rarity", "legendary"], ["artist's", "anon"]]"#.to_string()
+        ),
+        "Incorrect metadata"
+    );
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,105 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static TOKEN_2022: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+static METADATA_PROGRAM: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+fn update_account(geyser_plugin: &mut GeyserPluginPostgres, pubkey: &Pubkey, owner: &Pubkey, data: &[u8], write_version: u64) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: pubkey.as_ref(),
+                lamports: 2039280,
+                owner: owner.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version,
+                txn_signature: None,
+            }),
+            1,
+            false,
+        )
+        .unwrap();
+}
+
+/// An initialized Token-2022 mint with a metadata pointer to the address
+fn mint_data(metadata_address: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0_u8; 165];
+    data[45] = 1;
+    data.push(1);
+    data.extend_from_slice(&18_u16.to_le_bytes());
+    data.extend_from_slice(&64_u16.to_le_bytes());
+    data.extend_from_slice(&[0_u8; 32]);
+    data.extend_from_slice(metadata_address.as_ref());
+    data
+}
+
+/// A Metaplex metadata account of the mint, without creators
+fn metadata_data(mint: &Pubkey, name: &str) -> Vec<u8> {
+    let mut data = vec![4_u8];
+    data.extend_from_slice(Keypair::new().pubkey().as_ref());
+    data.extend_from_slice(mint.as_ref());
+    for text in [name, "TKN", "https://example.com/1.json"] {
+        data.extend_from_slice(&(text.len() as u32).to_le_bytes());
+        data.extend_from_slice(text.as_bytes());
+    }
+    data.resize(679, 0);
+    data
+}
+
+#[test]
+fn test_token_metadata_pointer() {
+    let mint: Pubkey = Keypair::new().pubkey();
+    let metadata_address: Pubkey = Keypair::new().pubkey();
+    let unindexed_mint: Pubkey = Keypair::new().pubkey();
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_token_metadata_pointer.json")).unwrap();
+    update_account(&mut geyser_plugin, &mint, &TOKEN_2022, &mint_data(&metadata_address), 0);
+    update_account(&mut geyser_plugin, &metadata_address, &METADATA_PROGRAM, &metadata_data(&mint, "Token #1"), 1);
+    update_account(&mut geyser_plugin, &unindexed_mint, &TOKEN_2022, &mint_data(&Keypair::new().pubkey()), 2);
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let pointers = client
+        .query(
+            "SELECT mint from token2022_metadata_pointer where mint = ANY($1)",
+            &[&vec![mint.to_string(), unindexed_mint.to_string()]],
+        )
+        .expect("Error selecting pointers");
+    assert_eq!(pointers.len(), 2, "Incorrect pointers");
+    let rows = client
+        .query(
+            "SELECT mint, metadata_address, metadata_mint, name from token2022_pointer_metadata where mint = ANY($1)",
+            &[&vec![mint.to_string(), unindexed_mint.to_string()]],
+        )
+        .expect("Error selecting pointer metadata");
+    assert_eq!(rows.len(), 1, "The pointer to an unindexed account is linked");
+    assert_eq!(
+        (rows[0].get(0), rows[0].get(1), rows[0].get(2), rows[0].get(3)),
+        (mint.to_string(), metadata_address.to_string(), mint.to_string(), "Token #1".to_string()),
+        "Incorrect pointer metadata"
+    );
+
+    // pointing to itself, the mint embeds its metadata and the external pointer is removed
+    update_account(&mut geyser_plugin, &mint, &TOKEN_2022, &mint_data(&mint), 3);
+    sleep(Duration::from_secs(1));
+    let pointers = client
+        .query("SELECT mint from token2022_metadata_pointer where mint=$1", &[&mint.to_string()])
+        .expect("Error selecting pointers");
+    assert!(pointers.is_empty(), "The embedded metadata pointer is recorded");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
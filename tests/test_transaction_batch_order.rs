@@ -0,0 +1,77 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::build_db_transaction;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::TransactionStatusMeta;
+
+#[test]
+fn test_transaction_batch_order() {
+    let slot = rand::random::<u32>() as u64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_transaction_batch_size.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    // record the order the rows are inserted in
+    reader
+        .batch_execute(
+            "
+                CREATE TABLE IF NOT EXISTS transaction_insert_order (signature BYTEA NOT NULL, position BIGSERIAL);
+                CREATE OR REPLACE FUNCTION record_transaction_insert_order() RETURNS trigger AS $$
+                BEGIN
+                    INSERT INTO transaction_insert_order (signature) VALUES (NEW.signature);
+                    RETURN NEW;
+                END $$ LANGUAGE plpgsql;
+                CREATE OR REPLACE TRIGGER record_transaction_insert_order AFTER INSERT ON transaction FOR EACH ROW EXECUTE FUNCTION record_transaction_insert_order();
+            ",
+        )
+        .expect("Error creating trigger");
+
+    // (slot, index) in arrival order
+    let arrivals = [(slot + 1, 0), (slot, 2), (slot, 1)];
+    let transaction_status_meta = TransactionStatusMeta::default();
+    let mut signatures: Vec<((u64, usize), Signature)> = Vec::new();
+    for (write_version, (transaction_slot, index)) in arrivals.into_iter().enumerate() {
+        let keypair = Keypair::new();
+        let message = Message::new(&[system_instruction::transfer(&keypair.pubkey(), &keypair.pubkey(), 1)], Some(&keypair.pubkey()));
+        let transaction = Transaction::new(&[&keypair], message, Hash::default());
+        let signature = transaction.signatures[0];
+        let transaction = SanitizedTransaction::from_transaction_for_tests(transaction);
+        let transaction_info = ReplicaTransactionInfoV2 {
+            signature: &signature,
+            is_vote: false,
+            transaction: &transaction,
+            transaction_status_meta: &transaction_status_meta,
+            index,
+        };
+        client.log_transaction(build_db_transaction(transaction_slot, &transaction_info, write_version as u64)).unwrap();
+        signatures.push(((transaction_slot, index), signature));
+    }
+
+    let batch = signatures.iter().map(|(_, signature)| signature.as_ref().to_vec()).collect::<Vec<Vec<u8>>>();
+    let inserted = reader
+        .query("SELECT signature from transaction_insert_order where signature = ANY($1) ORDER BY position", &[&batch])
+        .expect("Error selecting insert order")
+        .iter()
+        .map(|row| row.get::<_, Vec<u8>>("signature"))
+        .collect::<Vec<Vec<u8>>>();
+    reader
+        .batch_execute("DROP TRIGGER record_transaction_insert_order ON transaction; DROP FUNCTION record_transaction_insert_order();")
+        .expect("Error dropping trigger");
+    signatures.sort_by_key(|(key, _)| *key);
+    let expected = signatures.iter().map(|(_, signature)| signature.as_ref().to_vec()).collect::<Vec<Vec<u8>>>();
+    assert_eq!(inserted, expected, "Transactions not inserted by slot and index");
+    reader.close().expect("Error disconnecting");
+}
@@ -0,0 +1,54 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::build_db_transaction;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::TransactionStatusMeta;
+
+#[test]
+fn test_transaction_batch_size() {
+    let slot = rand::random::<u32>() as i64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_transaction_batch_size.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let count = |reader: &mut postgres::Client| -> i64 {
+        reader
+            .query_one("SELECT COUNT(*) from transaction where slot=$1", &[&slot])
+            .expect("Error counting transactions")
+            .get(0)
+    };
+
+    let transaction_status_meta = TransactionStatusMeta::default();
+    for i in 0..3 {
+        let keypair = Keypair::new();
+        let message = Message::new(&[system_instruction::transfer(&keypair.pubkey(), &keypair.pubkey(), 1)], Some(&keypair.pubkey()));
+        let transaction = Transaction::new(&[&keypair], message, Hash::default());
+        let signature = transaction.signatures[0];
+        let transaction = SanitizedTransaction::from_transaction_for_tests(transaction);
+        let transaction_info = ReplicaTransactionInfoV2 {
+            signature: &signature,
+            is_vote: false,
+            transaction: &transaction,
+            transaction_status_meta: &transaction_status_meta,
+            index: i,
+        };
+        client.log_transaction(build_db_transaction(slot as u64, &transaction_info, i as u64)).unwrap();
+
+        // the buffer is only flushed once it holds transaction_batch_size transactions
+        let expected = if i < 2 { 0 } else { 3 };
+        assert_eq!(count(&mut reader), expected, "Incorrect number of transactions written");
+    }
+    reader.close().expect("Error disconnecting");
+}
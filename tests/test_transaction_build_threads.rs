@@ -0,0 +1,88 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::build_db_transaction;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::TransactionStatusMeta;
+
+static MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+#[test]
+fn test_transaction_build_threads() {
+    let slot = rand::random::<u32>() as u64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_transaction_build_threads.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    let transactions = (0..40)
+        .map(|index| {
+            let keypair = Keypair::new();
+            let message = Message::new(
+                &[
+                    system_instruction::transfer(&keypair.pubkey(), &Pubkey::new_unique(), index + 1),
+                    Instruction::new_with_bytes(MEMO_PROGRAM_ID, format!("memo {}", index).as_bytes(), vec![]),
+                ],
+                Some(&keypair.pubkey()),
+            );
+            let transaction_status_meta = TransactionStatusMeta {
+                fee: 5000 + index,
+                log_messages: Some(vec![format!("log {}", index)]),
+                ..TransactionStatusMeta::default()
+            };
+            (
+                SanitizedTransaction::from_transaction_for_tests(Transaction::new(&[&keypair], message, Hash::default())),
+                transaction_status_meta,
+            )
+        })
+        .collect::<Vec<(SanitizedTransaction, TransactionStatusMeta)>>();
+
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let signatures = transactions.iter().map(|(transaction, _)| transaction.signature().as_ref().to_vec()).collect::<Vec<Vec<u8>>>();
+    // the same transactions written serially then, once removed, encoded in parallel
+    let mut written = Vec::new();
+    for build_threads in [1, 4] {
+        let mut config = config.clone();
+        config.transaction_build_threads = build_threads;
+        let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+        for (index, (transaction, transaction_status_meta)) in transactions.iter().enumerate() {
+            let transaction_info = ReplicaTransactionInfoV2 {
+                signature: transaction.signature(),
+                is_vote: false,
+                transaction,
+                transaction_status_meta,
+                index,
+            };
+            client.log_transaction(build_db_transaction(slot, &transaction_info, index as u64)).unwrap();
+        }
+        client.flush_transactions().unwrap();
+        let rows = reader
+            .query(
+                "SELECT (to_jsonb(txn) - 'updated_on')::TEXT AS row FROM transaction txn WHERE slot = $1 AND signature = ANY($2) ORDER BY index",
+                &[&(slot as i64), &signatures],
+            )
+            .expect("Error selecting transactions")
+            .iter()
+            .map(|row| row.get::<_, String>("row"))
+            .collect::<Vec<String>>();
+        reader
+            .execute("DELETE FROM transaction WHERE signature = ANY($1)", &[&signatures])
+            .expect("Error deleting transactions");
+        written.push(rows);
+    }
+
+    assert_eq!(written[0].len(), transactions.len(), "Incorrect number of transactions encoded serially");
+    assert_eq!(written[1], written[0], "Transactions encoded in parallel differ from the ones encoded serially");
+    reader.close().expect("Error disconnecting");
+}
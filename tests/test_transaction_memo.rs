@@ -0,0 +1,69 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::TransactionStatusMeta;
+
+static MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+#[test]
+fn test_transaction_memo() {
+    let slot_num: u32 = rand::random::<u32>();
+    let slot = slot_num as i64;
+    let reference = format!("ref{}", rand::random::<u32>());
+    let memo = format!("payment {} for order", reference);
+
+    let keypair = Keypair::new();
+    let message = Message::new(&[Instruction::new_with_bytes(MEMO_PROGRAM_ID, memo.as_bytes(), vec![])], Some(&keypair.pubkey()));
+    let transaction = Transaction::new(&[&keypair], message, Hash::default());
+    let signature = transaction.signatures[0];
+    let transaction = SanitizedTransaction::from_transaction_for_tests(transaction);
+    let transaction_status_meta = TransactionStatusMeta::default();
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_memos.json")).unwrap();
+    geyser_plugin
+        .notify_transaction(
+            ReplicaTransactionInfoVersions::V0_0_2(&ReplicaTransactionInfoV2 {
+                signature: &signature,
+                is_vote: false,
+                transaction: &transaction,
+                transaction_status_meta: &transaction_status_meta,
+                index: 0,
+            }),
+            slot as u64,
+        )
+        .unwrap();
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let rows = client
+        .query(
+            "SELECT signature, memo from transaction where to_tsvector('simple', memo) @@ plainto_tsquery('simple', $1)",
+            &[&reference],
+        )
+        .expect("Error searching memos");
+    assert_eq!(rows.len(), 1, "Incorrect number of rows found");
+    let first_row = rows.first().expect("No results found");
+
+    let found_signature: Vec<u8> = first_row.get("signature");
+    assert_eq!(found_signature, signature.as_ref().to_vec(), "Incorrect signature");
+    let found_memo: String = first_row.get("memo");
+    assert_eq!(found_memo, memo, "Incorrect memo");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,57 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_transaction;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_transaction_status::TransactionStatusMeta;
+
+#[test]
+fn test_transaction_slot_conflict() {
+    let slot = rand::random::<u32>() as u64;
+    let keypair = Keypair::new();
+    let transaction = system_transaction::transfer(&keypair, &Keypair::new().pubkey(), 42, Hash::new_unique());
+    let signature = transaction.signatures[0];
+    let transaction = SanitizedTransaction::from_transaction_for_tests(transaction);
+    let transaction_status_meta = TransactionStatusMeta::default();
+    let transaction_info = ReplicaTransactionInfoV2 {
+        signature: &signature,
+        is_vote: false,
+        transaction: &transaction,
+        transaction_status_meta: &transaction_status_meta,
+        index: 0,
+    };
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_transaction_slot_conflict.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let mut stored_slots = || -> Vec<i64> {
+        sleep(Duration::from_secs(1));
+        client
+            .query("SELECT slot from transaction where signature=$1", &[&signature.as_ref()])
+            .expect("Error selecting transaction")
+            .iter()
+            .map(|row| row.get("slot"))
+            .collect()
+    };
+
+    geyser_plugin.notify_transaction(ReplicaTransactionInfoVersions::V0_0_2(&transaction_info), slot + 1).unwrap();
+    assert_eq!(stored_slots(), vec![slot as i64 + 1], "Incorrect slot");
+
+    // a fork at a lower slot does not regress it
+    geyser_plugin.notify_transaction(ReplicaTransactionInfoVersions::V0_0_2(&transaction_info), slot).unwrap();
+    assert_eq!(stored_slots(), vec![slot as i64 + 1], "Slot regressed");
+
+    // a higher slot advances it
+    geyser_plugin.notify_transaction(ReplicaTransactionInfoVersions::V0_0_2(&transaction_info), slot + 2).unwrap();
+    assert_eq!(stored_slots(), vec![slot as i64 + 2], "Slot did not advance");
+
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,70 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::v0;
+use solana_sdk::message::v0::LoadedAddresses;
+use solana_sdk::message::Message;
+use solana_sdk::message::SimpleAddressLoader;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_sdk::transaction::Transaction;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::TransactionStatusMeta;
+
+fn notify_transaction(geyser_plugin: &mut GeyserPluginPostgres, transaction: &SanitizedTransaction, slot: u64) -> Signature {
+    let signature = *transaction.signature();
+    geyser_plugin
+        .notify_transaction(
+            ReplicaTransactionInfoVersions::V0_0_2(&ReplicaTransactionInfoV2 {
+                signature: &signature,
+                is_vote: false,
+                transaction,
+                transaction_status_meta: &TransactionStatusMeta::default(),
+                index: 0,
+            }),
+            slot,
+        )
+        .unwrap();
+    signature
+}
+
+#[test]
+fn test_transaction_version() {
+    let slot = rand::random::<u32>() as u64;
+    let keypair = Keypair::new();
+    let instruction = system_instruction::transfer(&keypair.pubkey(), &Keypair::new().pubkey(), 42);
+
+    let legacy_transaction = Transaction::new(&[&keypair], Message::new(std::slice::from_ref(&instruction), Some(&keypair.pubkey())), Hash::new_unique());
+    let legacy_transaction = SanitizedTransaction::from_transaction_for_tests(legacy_transaction);
+    let v0_message = v0::Message::try_compile(&keypair.pubkey(), &[instruction], &[], Hash::new_unique()).unwrap();
+    let v0_transaction = VersionedTransaction::try_new(VersionedMessage::V0(v0_message), &[&keypair]).unwrap();
+    let v0_transaction = SanitizedTransaction::try_create(v0_transaction, Hash::new_unique(), Some(false), SimpleAddressLoader::Enabled(LoadedAddresses::default()), false).unwrap();
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_transactions.json")).unwrap();
+    let legacy_signature = notify_transaction(&mut geyser_plugin, &legacy_transaction, slot);
+    let v0_signature = notify_transaction(&mut geyser_plugin, &v0_transaction, slot);
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    for (signature, expected_version) in [(legacy_signature, "legacy"), (v0_signature, "0")] {
+        let version: String = client
+            .query_one("SELECT version from transaction where slot=$1 and signature=$2", &[&(slot as i64), &signature.as_ref()])
+            .expect("Error selecting transaction")
+            .get("version");
+        assert_eq!(version, expected_version, "Incorrect version");
+    }
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,64 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoVersions;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_transaction;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_transaction_status::TransactionStatusMeta;
+
+#[test]
+fn test_transactions_on_root() {
+    let slot_num: u32 = rand::random::<u32>();
+    let slot = slot_num as i64;
+    let keypair = Keypair::new();
+    let transaction = system_transaction::transfer(&keypair, &Keypair::new().pubkey(), 42, Hash::default());
+    let signature = transaction.signatures[0];
+    let transaction = SanitizedTransaction::from_transaction_for_tests(transaction);
+    let transaction_status_meta = TransactionStatusMeta::default();
+    let transaction_info = ReplicaTransactionInfoV2 {
+        signature: &signature,
+        is_vote: false,
+        transaction: &transaction,
+        transaction_status_meta: &transaction_status_meta,
+        index: 0,
+    };
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_transactions_on_root.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+
+    // seen as processed, then notified again while the slot confirms
+    geyser_plugin.notify_transaction(ReplicaTransactionInfoVersions::V0_0_2(&transaction_info), slot as u64).unwrap();
+    geyser_plugin.update_slot_status(slot as u64, None, SlotStatus::Processed).unwrap();
+    geyser_plugin.notify_transaction(ReplicaTransactionInfoVersions::V0_0_2(&transaction_info), slot as u64).unwrap();
+    geyser_plugin.update_slot_status(slot as u64, None, SlotStatus::Confirmed).unwrap();
+
+    sleep(Duration::from_secs(1));
+    let rows = client
+        .query("SELECT write_version from transaction where signature=$1", &[&signature.as_ref()])
+        .expect("Error selecting transactions");
+    assert_eq!(rows.len(), 0, "Transaction written before its slot rooted");
+
+    geyser_plugin.update_slot_status(slot as u64, None, SlotStatus::Rooted).unwrap();
+
+    sleep(Duration::from_secs(1));
+    let rows = client
+        .query("SELECT write_version from transaction where signature=$1", &[&signature.as_ref()])
+        .expect("Error selecting transactions");
+    assert_eq!(rows.len(), 1, "Incorrect number of rows found");
+
+    // the single write carries the latest notification
+    let write_version: i64 = rows[0].get("write_version");
+    assert_eq!(write_version, 2, "Transaction not written exactly once");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
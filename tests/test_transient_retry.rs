@@ -0,0 +1,117 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::build_db_transaction;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::SanitizedTransaction;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::TransactionStatusMeta;
+
+#[test]
+fn test_transient_retry() {
+    let contended_slot = rand::random::<u32>() as i64;
+    let deadlocked_slot = contended_slot + 1;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_transient_retry.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    // the write of the contended slot and the one of the second transaction of the batch fail once on a serialization
+    // failure, the ones of the deadlocked slot always on a deadlock. The attempts are counted by sequences, which are
+    // not rolled back with the writes
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    reader
+        .batch_execute(&format!(
+            "
+                CREATE SEQUENCE test_transient_retry_contended_{0};
+                CREATE SEQUENCE test_transient_retry_transactions_{0};
+                CREATE SEQUENCE test_transient_retry_deadlocked_{0};
+                CREATE FUNCTION test_transient_retry_{0}() RETURNS trigger AS $$ BEGIN
+                    IF TG_TABLE_NAME = 'transaction' THEN
+                        IF NEW.slot = {0} AND nextval('test_transient_retry_transactions_{0}') = 2 THEN
+                            RAISE EXCEPTION 'transaction contended' USING ERRCODE = 'serialization_failure';
+                        END IF;
+                        RETURN NEW;
+                    END IF;
+                    IF NEW.slot = {0} AND nextval('test_transient_retry_contended_{0}') = 1 THEN
+                        RAISE EXCEPTION 'slot {0} contended' USING ERRCODE = 'serialization_failure';
+                    END IF;
+                    IF NEW.slot = {1} THEN
+                        PERFORM nextval('test_transient_retry_deadlocked_{0}');
+                        RAISE EXCEPTION 'slot {1} deadlocked' USING ERRCODE = 'deadlock_detected';
+                    END IF;
+                    RETURN NEW;
+                END $$ LANGUAGE plpgsql;
+                CREATE TRIGGER test_transient_retry_{0} BEFORE INSERT ON slot FOR EACH ROW EXECUTE FUNCTION test_transient_retry_{0}();
+                CREATE TRIGGER test_transient_retry_{0} BEFORE INSERT ON transaction FOR EACH ROW EXECUTE FUNCTION test_transient_retry_{0}();
+            ",
+            contended_slot, deadlocked_slot,
+        ))
+        .expect("Error creating triggers");
+
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    let contended_result = client.update_slot_status(contended_slot as u64, None, SlotStatus::Processed);
+    let deadlocked_result = client.update_slot_status(deadlocked_slot as u64, None, SlotStatus::Processed);
+    // a batch of transactions is retried as a whole, the first one being rolled back with the failing second one
+    let transaction_status_meta = TransactionStatusMeta::default();
+    let signatures = (0..2)
+        .map(|index| {
+            let keypair = Keypair::new();
+            let message = Message::new(&[system_instruction::transfer(&keypair.pubkey(), &keypair.pubkey(), 1)], Some(&keypair.pubkey()));
+            let transaction = SanitizedTransaction::from_transaction_for_tests(Transaction::new(&[&keypair], message, Hash::default()));
+            let transaction_info = ReplicaTransactionInfoV2 {
+                signature: transaction.signature(),
+                is_vote: false,
+                transaction: &transaction,
+                transaction_status_meta: &transaction_status_meta,
+                index,
+            };
+            client.log_transaction(build_db_transaction(contended_slot as u64, &transaction_info, index as u64)).unwrap();
+            transaction.signature().as_ref().to_vec()
+        })
+        .collect::<Vec<Vec<u8>>>();
+
+    let attempts = |reader: &mut postgres::Client, name: &str| -> i64 {
+        reader
+            .query_one(&format!("SELECT last_value FROM test_transient_retry_{}_{}", name, contended_slot), &[])
+            .expect("Error selecting sequence")
+            .get(0)
+    };
+    let contended_attempts = attempts(&mut reader, "contended");
+    let transaction_attempts = attempts(&mut reader, "transactions");
+    let deadlocked_attempts = attempts(&mut reader, "deadlocked");
+    let slots = reader.query("SELECT status from slot where slot=$1", &[&contended_slot]).expect("Error selecting slot");
+    let transactions = reader
+        .query("SELECT slot from transaction where signature = ANY($1)", &[&signatures])
+        .expect("Error selecting transactions");
+    reader
+        .batch_execute(&format!(
+            "
+                DROP TRIGGER test_transient_retry_{0} ON slot;
+                DROP TRIGGER test_transient_retry_{0} ON transaction;
+                DROP FUNCTION test_transient_retry_{0};
+                DROP SEQUENCE test_transient_retry_contended_{0};
+                DROP SEQUENCE test_transient_retry_transactions_{0};
+                DROP SEQUENCE test_transient_retry_deadlocked_{0};
+                DELETE FROM slot where slot={0};
+            ",
+            contended_slot
+        ))
+        .expect("Error dropping triggers");
+
+    assert!(contended_result.is_ok(), "Write not retried after the serialization failure: {:?}", contended_result.err());
+    assert_eq!(slots.len(), 1, "Slot of the retried write not found");
+    assert!(deadlocked_result.is_err(), "Deadlocked write succeeded");
+    assert_eq!(deadlocked_attempts, 3, "Deadlocked write not retried transient_error_retries times");
+    assert_eq!(contended_attempts, 2, "Incorrect number of attempts of the contended slot");
+    assert_eq!(transaction_attempts, 4, "Batch of transactions not retried as a whole");
+    assert_eq!(transactions.len(), 2, "Transactions of the retried batch not found");
+    reader.close().expect("Error disconnecting");
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static TOKEN_ACCOUNT_OWNER: Pubkey = pubkey!("cpmaMZyBQiPxpeuxNsQhW7N8z1o9yaNdLgiPhWGUEiX");
+
+fn update_mint(geyser_plugin: &mut GeyserPluginPostgres, mint: &Pubkey, decimals: u8) {
+    common::update_token_program_account(geyser_plugin, mint, &common::mint_data(0, decimals), 1, 1);
+}
+
+fn update_token_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, mint: &Pubkey, amount: u64) {
+    common::update_token_program_account(geyser_plugin, address, &common::token_account_data(mint, &TOKEN_ACCOUNT_OWNER, amount), 1, 1);
+}
+
+#[test]
+fn test_ui_amount() {
+    let mint = Keypair::new().pubkey();
+    let late_mint = Keypair::new().pubkey();
+    let address = Keypair::new().pubkey();
+    let early_address = Keypair::new().pubkey();
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_ui_amount.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let amounts = |client: &mut postgres::Client, address: &Pubkey| -> (Option<String>, Option<f64>) {
+        let row = client
+            .query_one("SELECT amount::TEXT, ui_amount::FLOAT8 from spl_token_account where pubkey=$1", &[&address.to_string()])
+            .expect("Error selecting account");
+        (row.get(0), row.get(1))
+    };
+
+    update_mint(&mut geyser_plugin, &mint, 6);
+    update_token_account(&mut geyser_plugin, &address, &mint, 1_500_000);
+    // written before its mint is seen
+    update_token_account(&mut geyser_plugin, &early_address, &late_mint, 25);
+    sleep(Duration::from_secs(1));
+    assert_eq!(amounts(&mut client, &address), (Some("1500000".to_string()), Some(1.5)), "Incorrect amounts");
+    assert_eq!(amounts(&mut client, &early_address), (Some("25".to_string()), None), "ui_amount set without the mint decimals");
+
+    update_mint(&mut geyser_plugin, &late_mint, 2);
+    sleep(Duration::from_secs(1));
+    assert_eq!(amounts(&mut client, &early_address), (Some("25".to_string()), Some(0.25)), "ui_amount not backfilled");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
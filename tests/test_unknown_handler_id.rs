@@ -0,0 +1,60 @@
+mod common;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPluginError;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::DbAccountInfo;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+const CONFIG_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_unknown_handler_id.json");
+
+static OWNER: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+#[test]
+fn test_unknown_handler_id() {
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    let result = geyser_plugin.on_load(CONFIG_PATH);
+    match result {
+        Err(GeyserPluginError::ConfigFileReadError { msg }) => assert!(msg.contains("bogus_handler"), "Unknown handler id not reported: {}", msg),
+        other => panic!("Expected a config error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_unknown_handler_id_dispatch() {
+    // a client built without the checks of the plugin load, the unknown handler reaching the dispatch
+    let config = GeyserPluginPostgresConfig::read_from(CONFIG_PATH).expect("Failed to read config");
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to build the client");
+    let data = common::token_account_data(&Pubkey::new_unique(), &Pubkey::new_unique(), 0);
+    let account = DbAccountInfo::from_raw(&Pubkey::new_unique(), &OWNER, &data, rand::random::<u32>() as u64);
+
+    match client.update_account(account.clone(), false) {
+        Err(GeyserPluginError::Custom(err)) => assert!(err.to_string().contains("bogus_handler"), "Unknown handler id not reported: {}", err),
+        other => panic!("Expected an error, got {:?}", other),
+    }
+
+    // the startup accounts buffered for the unknown handler are dropped, the ones of the other handlers written
+    let addresses = (0..3).map(|_| Pubkey::new_unique()).collect::<Vec<Pubkey>>();
+    for address in &addresses[..2] {
+        client
+            .update_account(DbAccountInfo::from_raw(address, &OWNER, &data, account.slot as u64), true)
+            .expect("Failed to buffer the startup account");
+    }
+    assert_eq!(client.pending_account_update_count(), 0, "Buffers not flushed at the batch size");
+    client
+        .update_account(DbAccountInfo::from_raw(&addresses[2], &OWNER, &data, account.slot as u64), true)
+        .expect("Failed to buffer the startup account");
+    client.notify_end_of_startup().expect("Failed to flush the startup accounts");
+    assert_eq!(client.pending_account_update_count(), 0);
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let pubkeys = addresses.iter().map(|address| address.to_string()).collect::<Vec<String>>();
+    let written = reader
+        .query_one("SELECT count(DISTINCT pubkey) FROM spl_token_account WHERE pubkey = ANY($1)", &[&pubkeys])
+        .unwrap()
+        .get::<_, i64>(0);
+    assert_eq!(written, 3, "Token accounts not written");
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+fn update_token_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, amount: u64, slot: u64) {
+    common::update_token_program_account(geyser_plugin, address, &common::token_account_data(&Pubkey::default(), address, amount), slot, 0);
+}
+
+#[test]
+fn test_unrooted_buffer_age() {
+    let (stale_address, address) = (Keypair::new().pubkey(), Keypair::new().pubkey());
+    let slot = rand::random::<u32>() as u64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_unrooted_buffer_age.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let amounts = |client: &mut postgres::Client, address: &Pubkey| -> Vec<Option<String>> {
+        ["confirmed", "rooted"]
+            .iter()
+            .map(|schema| {
+                client
+                    .query_opt(&format!("SELECT amount::TEXT from {}.spl_token_account where pubkey=$1", schema), &[&address.to_string()])
+                    .expect("Error selecting token account")
+                    .map(|row| row.get(0))
+            })
+            .collect()
+    };
+
+    // the update of slot is held until slot + 3 is notified, beyond the window of 2 slots
+    update_token_account(&mut geyser_plugin, &stale_address, 10, slot);
+    geyser_plugin.update_slot_status(slot, None, SlotStatus::Processed).unwrap();
+    update_token_account(&mut geyser_plugin, &address, 20, slot + 3);
+    geyser_plugin.update_slot_status(slot + 3, Some(slot), SlotStatus::Processed).unwrap();
+
+    // rooted after its buffer was discarded, the slot has nothing left to write
+    geyser_plugin.update_slot_status(slot, None, SlotStatus::Confirmed).unwrap();
+    geyser_plugin.update_slot_status(slot, None, SlotStatus::Rooted).unwrap();
+    geyser_plugin.update_slot_status(slot + 3, Some(slot), SlotStatus::Rooted).unwrap();
+    sleep(Duration::from_secs(1));
+    assert_eq!(amounts(&mut client, &stale_address), vec![None, None], "Stale unrooted buffer written");
+    assert_eq!(
+        amounts(&mut client, &address),
+        vec![Some("20".to_string()), Some("20".to_string())],
+        "Buffer within the window not written"
+    );
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
@@ -0,0 +1,91 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClientBuilder;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+const CONFIG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_upsert_guards.json");
+
+fn update_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, lamports: u64, data: &[u8], slot: u64) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            slot,
+            false,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_upsert_guards() {
+    let address = Keypair::new().pubkey();
+    let slot = rand::random::<u32>() as u64;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(CONFIG).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let row = |client: &mut postgres::Client| -> (i64, i64) {
+        let row = client
+            .query_one("SELECT slot, lamports FROM raw_account WHERE pubkey=$1", &[&address.to_string()])
+            .expect("Error selecting the account");
+        (row.get(0), row.get(1))
+    };
+
+    update_account(&mut geyser_plugin, &address, 1, &[1, 2, 3], slot);
+    sleep(Duration::from_secs(1));
+    // a later update with the same data is not written, the guard requiring the data to change
+    update_account(&mut geyser_plugin, &address, 2, &[1, 2, 3], slot + 1);
+    sleep(Duration::from_secs(1));
+    let unchanged = row(&mut client);
+    update_account(&mut geyser_plugin, &address, 3, &[4, 5, 6], slot + 2);
+    sleep(Duration::from_secs(1));
+    let changed = row(&mut client);
+
+    assert_eq!(unchanged, (slot as i64, 1), "Update with unchanged data written");
+    assert_eq!(changed, (slot as i64 + 2, 3), "Update with changed data not written");
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
+
+#[test]
+fn test_upsert_guards_validation() {
+    let config = GeyserPluginPostgresConfig::read_from(CONFIG).expect("Error reading config");
+    for (handler_id, predicate) in [
+        ("raw_account", "true; DROP TABLE raw_account"),
+        ("raw_account", "true) OR (true"),
+        ("raw_account", "raw.owner = 'a"),
+        ("raw_account", "true -- comment"),
+        ("raw_account", "raw.owner = $$a$$"),
+        ("raw_account", " "),
+        ("owner_history", "true"),
+    ] {
+        let config = GeyserPluginPostgresConfig {
+            upsert_guards: [(handler_id.to_string(), predicate.to_string())].into_iter().collect(),
+            ..config.clone()
+        };
+        assert!(
+            PostgresClientBuilder::build_pararallel_postgres_client(&config).is_err(),
+            "Predicate {:?} of {} accepted",
+            predicate,
+            handler_id
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use solana_geyser_plugin_postgres::config::GeyserPluginPostgresConfig;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+
+const CONFIG_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_verify_hostname.json");
+
+#[test]
+fn test_verify_hostname() {
+    let config = GeyserPluginPostgresConfig::read_from(CONFIG_PATH).expect("Failed to read config");
+    assert!(config.verify_hostname, "Hostname verification not on by default");
+
+    // the certificate of the server is its own authority, and names a host rather than the address connected to
+    let mut client = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    let setting = |client: &mut postgres::Client, name: &str| client.query_one(&format!("SHOW {}", name), &[]).unwrap().get::<_, String>(0);
+    if setting(&mut client, "ssl") != "on" {
+        eprintln!("SSL is off on the server, skipping");
+        return;
+    }
+    let (cert_file, key_file) = (setting(&mut client, "ssl_cert_file"), setting(&mut client, "ssl_key_file"));
+    let tls_config = GeyserPluginPostgresConfig {
+        use_ssl: Some(true),
+        server_ca: Some(cert_file.clone()),
+        client_cert: Some(cert_file),
+        client_key: Some(key_file),
+        ..config
+    };
+
+    let err = SimplePostgresClient::connect_to_db(&tls_config).err().expect("Connected to a host the certificate does not name");
+    assert!(err.to_string().contains("certificate verify failed"), "Unexpected error: {}", err);
+    let mut client = SimplePostgresClient::connect_to_db(&GeyserPluginPostgresConfig { verify_hostname: false, ..tls_config }).expect("Failed to connect without hostname verification");
+    assert!(client.query_one("SELECT ssl FROM pg_stat_ssl WHERE pid = pg_backend_pid()", &[]).unwrap().get::<_, bool>(0));
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use rand::Rng;
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+fn update_token_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, wallet: &Pubkey, slot: u64) {
+    common::update_token_program_account(geyser_plugin, address, &common::token_account_data(&Keypair::new().pubkey(), wallet, 0), slot, 0);
+}
+
+#[test]
+fn test_wallet() {
+    let wallet: Pubkey = Keypair::new().pubkey();
+    let slot = rand::thread_rng().gen_range(2..u32::MAX as u64);
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_wallets.json")).unwrap();
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let seen_slots = |client: &mut postgres::Client| -> (i64, i64) {
+        let row = client
+            .query_one("SELECT first_seen_slot, last_seen_slot from wallet where pubkey=$1", &[&wallet.to_string()])
+            .expect("Error selecting wallet");
+        (row.get(0), row.get(1))
+    };
+
+    update_token_account(&mut geyser_plugin, &Keypair::new().pubkey(), &wallet, slot);
+    sleep(Duration::from_secs(1));
+    assert_eq!(seen_slots(&mut client), (slot as i64, slot as i64), "Wallet not recorded");
+
+    // another token account of the wallet advances its last seen slot, an earlier update its first seen slot
+    update_token_account(&mut geyser_plugin, &Keypair::new().pubkey(), &wallet, slot + 5);
+    update_token_account(&mut geyser_plugin, &Keypair::new().pubkey(), &wallet, slot - 2);
+    sleep(Duration::from_secs(1));
+    assert_eq!(seen_slots(&mut client), (slot as i64 - 2, slot as i64 + 5), "Wallet slots not advanced");
+    let count: i64 = client
+        .query_one("SELECT COUNT(*) from spl_token_account where owner=$1", &[&wallet.to_string()])
+        .expect("Error selecting token accounts")
+        .get(0);
+    assert_eq!(count, 3, "Token accounts of the wallet not written");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}
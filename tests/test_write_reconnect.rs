@@ -0,0 +1,72 @@
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::PostgresClient;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+
+#[test]
+fn test_write_reconnect() {
+    let lost_slot = rand::random::<u32>() as i64;
+    let failing_slot = lost_slot + 1;
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_write_reconnect.json")).unwrap();
+    let config = geyser_plugin.config.clone().expect("No plugin config found");
+    geyser_plugin.on_unload();
+
+    // the connection writing the first slot is terminated once, the write of the second one always fails. The
+    // attempts are counted by sequences, which are not rolled back with the writes
+    let mut reader = SimplePostgresClient::connect_to_db(&config).expect("Failed to connect");
+    reader
+        .batch_execute(&format!(
+            "
+                CREATE SEQUENCE test_write_reconnect_lost_{0};
+                CREATE SEQUENCE test_write_reconnect_failing_{0};
+                CREATE FUNCTION test_write_reconnect_{0}() RETURNS trigger AS $$ BEGIN
+                    IF NEW.slot = {0} AND nextval('test_write_reconnect_lost_{0}') = 1 THEN
+                        PERFORM pg_terminate_backend(pg_backend_pid());
+                    END IF;
+                    IF NEW.slot = {1} THEN
+                        PERFORM nextval('test_write_reconnect_failing_{0}');
+                        RAISE EXCEPTION 'slot {1} rejected';
+                    END IF;
+                    RETURN NEW;
+                END $$ LANGUAGE plpgsql;
+                CREATE TRIGGER test_write_reconnect_{0} BEFORE INSERT ON slot FOR EACH ROW EXECUTE FUNCTION test_write_reconnect_{0}();
+            ",
+            lost_slot, failing_slot,
+        ))
+        .expect("Error creating trigger");
+
+    let mut client = SimplePostgresClient::new(&config, None).expect("Failed to connect");
+    let lost_result = client.update_slot_status(lost_slot as u64, None, SlotStatus::Processed);
+    let failing_result = client.update_slot_status(failing_slot as u64, None, SlotStatus::Processed);
+
+    let attempts = |reader: &mut postgres::Client, name: &str| -> i64 {
+        reader
+            .query_one(&format!("SELECT last_value FROM test_write_reconnect_{}_{}", name, lost_slot), &[])
+            .expect("Error selecting sequence")
+            .get(0)
+    };
+    let lost_attempts = attempts(&mut reader, "lost");
+    let failing_attempts = attempts(&mut reader, "failing");
+    let rows = reader.query("SELECT status from slot where slot=$1", &[&lost_slot]).expect("Error selecting slot");
+    reader
+        .batch_execute(&format!(
+            "
+                DROP TRIGGER test_write_reconnect_{0} ON slot;
+                DROP FUNCTION test_write_reconnect_{0};
+                DROP SEQUENCE test_write_reconnect_lost_{0};
+                DROP SEQUENCE test_write_reconnect_failing_{0};
+                DELETE FROM slot where slot={0};
+            ",
+            lost_slot
+        ))
+        .expect("Error dropping trigger");
+
+    assert!(lost_result.is_ok(), "Write not retried after the connection loss: {:?}", lost_result.err());
+    assert_eq!(lost_attempts, 2, "Incorrect number of attempts of the write whose connection was lost");
+    assert_eq!(rows.len(), 1, "Slot of the retried write not found");
+    assert!(failing_result.is_err(), "Failing write succeeded");
+    assert_eq!(failing_attempts, 1, "Failing write retried while its connection was up");
+    reader.close().expect("Error disconnecting");
+}
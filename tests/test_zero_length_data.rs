@@ -0,0 +1,64 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV2;
+use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoVersions;
+use solana_geyser_plugin_postgres::geyser_plugin_postgres::GeyserPluginPostgres;
+use solana_geyser_plugin_postgres::postgres_client::SimplePostgresClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+static OWNER: Pubkey = pubkey!("EmdsWm9dJ1d6BgQzHDcMJkDvB5SVvpfrAtpiGMVW1gxx");
+
+fn update_account(geyser_plugin: &mut GeyserPluginPostgres, address: &Pubkey, lamports: u64, data: &[u8], slot: u64) {
+    geyser_plugin
+        .update_account(
+            ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                pubkey: address.as_ref(),
+                lamports,
+                owner: OWNER.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version: 0,
+                txn_signature: None,
+            }),
+            slot,
+            false,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_zero_length_data() {
+    let emptied_address: Pubkey = Keypair::new().pubkey();
+    let empty_address: Pubkey = Keypair::new().pubkey();
+    let closed_address: Pubkey = Keypair::new().pubkey();
+
+    let mut geyser_plugin = GeyserPluginPostgres::default();
+    geyser_plugin.on_load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_config_zero_length_data.json")).unwrap();
+    update_account(&mut geyser_plugin, &emptied_address, 1, &[1, 2, 3], 1);
+    update_account(&mut geyser_plugin, &emptied_address, 1, &[], 2);
+    update_account(&mut geyser_plugin, &empty_address, 1, &[], 1);
+    // accounts without lamports are still deleted
+    update_account(&mut geyser_plugin, &closed_address, 1, &[1, 2, 3], 1);
+    update_account(&mut geyser_plugin, &closed_address, 0, &[], 2);
+
+    sleep(Duration::from_secs(1));
+    let mut client = SimplePostgresClient::connect_to_db(&geyser_plugin.config.clone().expect("No plugin config found")).expect("Failed to connect");
+    let raw_account = |client: &mut postgres::Client, address: &Pubkey| -> Option<(i64, String)> {
+        client
+            .query_opt("SELECT slot, data_base64 from raw_account where pubkey=$1", &[&address.to_string()])
+            .expect("Error selecting raw account")
+            .map(|row| (row.get(0), row.get(1)))
+    };
+    assert_eq!(raw_account(&mut client, &emptied_address), Some((1, base64::encode([1, 2, 3]))), "Account without data written");
+    assert_eq!(raw_account(&mut client, &empty_address), None, "Account without data written");
+    assert_eq!(raw_account(&mut client, &closed_address), None, "Closed account not deleted");
+
+    client.close().expect("Error disconnecting");
+    geyser_plugin.on_unload();
+}